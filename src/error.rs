@@ -0,0 +1,64 @@
+// Exibição dos erros e a trait de erro da biblioteca padrão
+use std::error::Error;
+use std::fmt;
+// Erros de entrada e saída e caminhos de arquivos da biblioteca padrão
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Erro da biblioteca, devolvido pelas funções que configuram, executam ou escrevem os resultados de
+// uma simulação no lugar de encerrar o programa
+#[derive(Debug)]
+pub enum SimulationError {
+    // Parâmetros rejeitados antes de a simulação começar, com uma mensagem para cada problema
+    InvalidParameters(Vec<String>),
+    // Erro ao ler ou escrever um arquivo
+    Io { path: PathBuf, source: io::Error },
+    // Arquivo de experimento ou de checkpoint com conteúdo inválido
+    InvalidFile { path: PathBuf, message: String },
+    // Estado da fila que não deveria ser alcançável, indicando um erro no simulador
+    InconsistentState(String),
+}
+
+impl SimulationError {
+    // Constrói o erro de entrada e saída de um arquivo, para ser usado com map_err
+    pub fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| SimulationError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for SimulationError {
+    // Mensagem do erro, como é impressa pela linha de comando
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimulationError::InvalidParameters(problems) => {
+                write!(formatter, "Parâmetros inválidos:")?;
+                for problem in problems {
+                    write!(formatter, "\n\t{}", problem)?;
+                }
+                Ok(())
+            }
+            SimulationError::Io { path, source } => {
+                write!(formatter, "Erro ao acessar {}: {}", path.display(), source)
+            }
+            SimulationError::InvalidFile { path, message } => {
+                write!(formatter, "Erro em {}: {}", path.display(), message)
+            }
+            SimulationError::InconsistentState(message) => {
+                write!(formatter, "Estado inconsistente da simulação: {}", message)
+            }
+        }
+    }
+}
+
+impl Error for SimulationError {
+    // Erro de origem, que só existe nos erros de entrada e saída
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SimulationError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,434 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a struct dos parâmetros e os enums que configuram uma execução
+use crate::simulator::{
+    AnalysisMethod, GeneratorKind, QuantileEstimator, QueueMode, QueuePolicy, ResultsFormat,
+    SimulationParameters, WarmupMethod, WriteMode,
+};
+// Valores padrão do tamanho e da quantidade de rodadas, os mesmos da linha de comando
+use crate::{DEFAULT_ROUNDS_COUNT, DEFAULT_ROUND_SIZE};
+// Derive que gera a leitura das seções do arquivo
+use serde::Deserialize;
+// Leitura de arquivos, caminhos e conversão de strings da biblioteca padrão
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Distribuição das chegadas e dos serviços, a única simulada pela nossa fila
+const EXPONENTIAL: &str = "exponential";
+
+// Arquivo de experimento como escrito em TOML ou JSON. Os campos ausentes ficam com os valores
+// padrão da linha de comando, e os enums são lidos como strings e convertidos na validação, para
+// que os erros digam qual campo está errado
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ExperimentFile {
+    seed: Option<u64>,       // Semente base, da qual a de cada simulação é derivada
+    output: Option<PathBuf>, // Arquivo dos resultados
+    // Formato dos resultados, csv, json ou jsonl, ou o da extensão quando o campo é ausente
+    output_format: Option<String>,
+    // Modo de escrita dos resultados, append ou overwrite
+    output_mode: Option<String>,
+    // Colunas dos resultados escritas, na ordem dada, ou todas quando o campo é ausente
+    output_columns: Option<Vec<String>>,
+    // Diretório dos gráficos das simulações e dos resultados em função de ρ, se pedidos
+    plots: Option<PathBuf>,
+    model: ModelSection, // Fila simulada
+    #[serde(default)]
+    analysis: AnalysisSection, // Análise de saída
+    #[serde(default)]
+    stopping: StoppingSection, // Regra de parada
+}
+
+// Seção [model], com a fila e os valores de ρ e políticas simulados. Cada combinação de ρ e
+// política vira uma simulação
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ModelSection {
+    mode: Option<String>,          // for-real ou check-correctness
+    arrivals: Option<String>,      // Distribuição dos tempos entre chegadas
+    service: Option<String>,       // Distribuição dos tempos de serviço
+    servers: Option<usize>,        // Quantidade de servidores
+    capacity: Option<usize>,       // Capacidade do sistema, ausente para buffer infinito
+    rhos: Option<Vec<f64>>,        // Utilizações simuladas
+    policies: Option<Vec<String>>, // Políticas de atendimento simuladas
+    allow_unstable: Option<bool>,  // Aceita ρ >= 1
+}
+
+// Seção [analysis], com o método de análise de saída e o que é coletado e exportado
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct AnalysisSection {
+    method: Option<String>,         // Método de análise de saída
+    warmup: Option<String>,         // Detecção da fase transiente
+    quantiles: Option<String>,      // Estimador dos quantis de W e T
+    generator: Option<String>,      // Gerador de números aleatórios
+    confidence: Option<f64>,        // Nível de confiança dos ICs
+    round_size: Option<usize>,      // Fregueses por rodada
+    rounds: Option<usize>,          // Quantidade inicial de rodadas
+    distributions: Option<bool>,    // Exporta as distribuições de W, T e N
+    autocorrelation: Option<usize>, // Maior lag da análise de autocorrelação
+    streaming: Option<Vec<String>>, // Métricas acumuladas em fluxo
+    parallel_queues: Option<usize>, // Filas independentes que dividem as rodadas em paralelo
+}
+
+// Seção [stopping], com a regra de parada sequencial
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct StoppingSection {
+    precision: Option<f64>,     // Precisão relativa alvo dos ICs
+    max_clients: Option<usize>, // Máximo de fregueses
+}
+
+// Converte o valor de um campo no enum correspondente, prefixando o erro com o nome do campo
+fn parse_field<T: FromStr<Err = String>>(
+    field: &str,
+    value: &Option<String>,
+    default: T,
+) -> Result<T, String> {
+    value.as_ref().map_or(Ok(default), |value| {
+        value
+            .parse()
+            .map_err(|error| format!("{}: {}", field, error))
+    })
+}
+
+// Verifica uma condição sobre um campo, devolvendo o erro com o nome do campo caso ela falhe
+fn check_field(condition: bool, field: &str, message: &str) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(format!("{}: {}", field, message))
+    }
+}
+
+impl ModelSection {
+    // Rejeita os modelos que a fila não simula, que é uma M/M/1 com buffer infinito
+    fn validate(&self) -> Result<(), String> {
+        for (field, distribution) in [
+            ("model.arrivals", &self.arrivals),
+            ("model.service", &self.service),
+        ]
+        .iter()
+        {
+            if let Some(distribution) = distribution {
+                check_field(
+                    distribution == EXPONENTIAL,
+                    field,
+                    &format!(
+                        "distribuição {} não suportada, o simulador só simula a {}",
+                        distribution, EXPONENTIAL
+                    ),
+                )?;
+            }
+        }
+        check_field(
+            self.servers.is_none_or(|servers| servers == 1),
+            "model.servers",
+            "o simulador só simula um servidor",
+        )?;
+        check_field(
+            self.capacity.is_none(),
+            "model.capacity",
+            "o simulador só simula buffer infinito, remova o campo",
+        )
+    }
+}
+
+// Chave do arquivo de experimento correspondente a um campo de SimulationParameters, com o índice
+// do ρ na lista quando o campo é o próprio ρ. Os campos que o arquivo não configura, como os dos
+// checkpoints, ficam com o próprio nome
+fn file_key(field: &str, rho_index: usize) -> String {
+    let key = match field {
+        "rho" => return format!("model.rhos[{}]", rho_index),
+        "analysis_method" => "analysis.method",
+        "round_size" => "analysis.round-size",
+        "rounds_count" => "analysis.rounds",
+        "confidence_level" => "analysis.confidence",
+        "streaming_metrics" => "analysis.streaming",
+        "autocorrelation_max_lag" => "analysis.autocorrelation",
+        "parallel_queues" => "analysis.parallel-queues",
+        "target_precision" => "stopping.precision",
+        "maximum_clients" => "stopping.max-clients",
+        "output_columns" => "output-columns",
+        field => field,
+    };
+    key.to_string()
+}
+
+// Carrega um arquivo de experimento, escolhendo o formato pela extensão .toml ou .json, e o
+// valida. Retorna os parâmetros de cada simulação, na ordem dos ρ e, para cada um, das políticas
+pub fn load_experiment(path: &Path) -> Result<Vec<SimulationParameters>, SimulationError> {
+    let contents = fs::read_to_string(path).map_err(SimulationError::io(path))?;
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let experiment = match extension {
+        Some("toml") => parse_toml(&contents),
+        Some("json") => parse_json(&contents),
+        _ => Err("o arquivo deve ter extensão .toml ou .json".to_string()),
+    };
+    experiment
+        .and_then(|experiment| experiment_parameters(&experiment))
+        .map_err(|message| SimulationError::InvalidFile {
+            path: path.to_path_buf(),
+            message,
+        })
+}
+
+// Lê um arquivo de experimento em TOML
+fn parse_toml(contents: &str) -> Result<ExperimentFile, String> {
+    toml::from_str(contents).map_err(|error| error.to_string())
+}
+
+// Lê um arquivo de experimento em JSON
+fn parse_json(contents: &str) -> Result<ExperimentFile, String> {
+    serde_json::from_str(contents).map_err(|error| error.to_string())
+}
+
+// Valida os campos do experimento e monta os parâmetros de cada simulação a partir dos padrões
+// de SimulationParameters
+fn experiment_parameters(experiment: &ExperimentFile) -> Result<Vec<SimulationParameters>, String> {
+    let model = &experiment.model;
+    let analysis = &experiment.analysis;
+    let stopping = &experiment.stopping;
+    model.validate()?;
+
+    let queue_mode = parse_field("model.mode", &model.mode, QueueMode::ForReal)?;
+    // No modo de corretude as chegadas são determinísticas e ρ é desconsiderado
+    let rhos = match (&model.rhos, queue_mode) {
+        (Some(rhos), _) => rhos.clone(),
+        (None, QueueMode::ForReal) => return Err("model.rhos: campo obrigatório".to_string()),
+        (None, QueueMode::CheckCorrectness) => vec![0.0],
+    };
+    check_field(!rhos.is_empty(), "model.rhos", "a lista está vazia")?;
+    let queue_policies = match &model.policies {
+        Some(policies) => {
+            check_field(!policies.is_empty(), "model.policies", "a lista está vazia")?;
+            policies
+                .iter()
+                .enumerate()
+                .map(|(index, policy)| {
+                    parse_field(
+                        &format!("model.policies[{}]", index),
+                        &Some(policy.clone()),
+                        QueuePolicy::FCFS,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        None => vec![QueuePolicy::FCFS],
+    };
+
+    let round_size = analysis.round_size.unwrap_or(DEFAULT_ROUND_SIZE);
+    let rounds_count = analysis.rounds.unwrap_or(DEFAULT_ROUNDS_COUNT);
+    let generator_kind = parse_field(
+        "analysis.generator",
+        &analysis.generator,
+        GeneratorKind::Std,
+    )?;
+    let analysis_method = parse_field("analysis.method", &analysis.method, AnalysisMethod::Rounds)?;
+    let warmup_method = parse_field(
+        "analysis.warmup",
+        &analysis.warmup,
+        WarmupMethod::Utilization,
+    )?;
+    let quantile_estimator = parse_field(
+        "analysis.quantiles",
+        &analysis.quantiles,
+        QuantileEstimator::Exact,
+    )?;
+    let output_format = match &experiment.output_format {
+        Some(_) => Some(parse_field(
+            "output-format",
+            &experiment.output_format,
+            ResultsFormat::Csv,
+        )?),
+        None => None,
+    };
+    let output_mode = parse_field("output-mode", &experiment.output_mode, WriteMode::Append)?;
+
+    let mut simulations = vec![];
+    for (rho_index, &rho) in rhos.iter().enumerate() {
+        for &queue_policy in queue_policies.iter() {
+            let mut parameters =
+                SimulationParameters::new(rho, round_size, rounds_count, queue_policy, queue_mode);
+            parameters.generator_kind = generator_kind;
+            if let Some(confidence_level) = analysis.confidence {
+                parameters.confidence_level = confidence_level;
+            }
+            parameters.analysis_method = analysis_method;
+            parameters.warmup_method = warmup_method;
+            parameters.quantile_estimator = quantile_estimator;
+            if let Some(target_precision) = stopping.precision {
+                parameters.target_precision = target_precision;
+            }
+            if let Some(maximum_clients) = stopping.max_clients {
+                parameters.maximum_clients = maximum_clients;
+            }
+            parameters.export_distributions = analysis.distributions.unwrap_or(false);
+            parameters.autocorrelation_max_lag = analysis.autocorrelation;
+            parameters.streaming_metrics = analysis.streaming.clone().unwrap_or_default();
+            parameters.parallel_queues = analysis.parallel_queues.unwrap_or(1);
+            parameters.allow_unstable = model.allow_unstable.unwrap_or(false);
+            parameters.seed = experiment.seed;
+            if let Some(output_path) = &experiment.output {
+                parameters.output_path = output_path.clone();
+            }
+            parameters.output_format = output_format;
+            parameters.output_mode = output_mode;
+            parameters.output_columns = experiment.output_columns.clone();
+            parameters.plots_directory = experiment.plots.clone();
+            // A validação é a dos parâmetros, só a chave do arquivo é prefixada a cada problema
+            let problems = parameters.field_problems();
+            if !problems.is_empty() {
+                return Err(problems
+                    .iter()
+                    .map(|(field, problem)| format!("{}: {}", file_key(field, rho_index), problem))
+                    .collect::<Vec<_>>()
+                    .join("\n\t"));
+            }
+            simulations.push(parameters);
+        }
+    }
+    Ok(simulations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lê e valida um experimento em TOML, como load_experiment faz após ler o arquivo
+    fn load_toml(contents: &str) -> Result<Vec<SimulationParameters>, String> {
+        parse_toml(contents).and_then(|experiment| experiment_parameters(&experiment))
+    }
+
+    #[test]
+    fn expands_rhos_and_policies_in_order() {
+        let simulations = load_toml(
+            "seed = 7\noutput = \"sweep.csv\"\noutput-mode = \"overwrite\"\n\
+             output-columns = [\"rho\", \"E[W]\"]\n\
+             [model]\nrhos = [0.3, 0.6]\npolicies = [\"fcfs\", \"lcfs\"]\n\
+             [analysis]\nmethod = \"batch-means\"\nround-size = 100\nrounds = 10\n\
+             [stopping]\nprecision = 0.1\nmax-clients = 5000\n",
+        )
+        .unwrap();
+        assert_eq!(simulations.len(), 4);
+        let rhos: Vec<f64> = simulations
+            .iter()
+            .map(|parameters| parameters.rho)
+            .collect();
+        assert_eq!(rhos, vec![0.3, 0.3, 0.6, 0.6]);
+        assert!(matches!(simulations[1].queue_policy, QueuePolicy::LCFS));
+        for parameters in simulations.iter() {
+            assert_eq!(parameters.seed, Some(7));
+            assert_eq!(parameters.output_path, PathBuf::from("sweep.csv"));
+            assert_eq!(parameters.output_format, None);
+            assert_eq!(parameters.output_mode, WriteMode::Overwrite);
+            assert_eq!(
+                parameters.output_columns,
+                Some(vec!["rho".to_string(), "E[W]".to_string()])
+            );
+            assert_eq!(parameters.analysis_method, AnalysisMethod::BatchMeans);
+            assert_eq!(parameters.round_size, 100);
+            assert_eq!(parameters.rounds_count, 10);
+            assert_eq!(parameters.target_precision, 0.1);
+            assert_eq!(parameters.maximum_clients, 5000);
+        }
+    }
+
+    #[test]
+    fn json_matches_toml() {
+        let from_json = parse_json(
+            "{\"model\": {\"rhos\": [0.5], \"policies\": [\"lcfs\"]}, \
+             \"analysis\": {\"confidence\": 0.9, \"round-size\": 50}}",
+        )
+        .and_then(|experiment| experiment_parameters(&experiment))
+        .unwrap();
+        let from_toml = load_toml(
+            "[model]\nrhos = [0.5]\npolicies = [\"lcfs\"]\n\
+             [analysis]\nconfidence = 0.9\nround-size = 50\n",
+        )
+        .unwrap();
+        assert_eq!(format!("{:?}", from_json), format!("{:?}", from_toml));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let simulations = load_toml("[model]\nmode = \"check-correctness\"\n").unwrap();
+        assert_eq!(simulations.len(), 1);
+        assert_eq!(simulations[0].queue_mode, QueueMode::CheckCorrectness);
+        assert_eq!(simulations[0].round_size, DEFAULT_ROUND_SIZE);
+        assert_eq!(simulations[0].rounds_count, DEFAULT_ROUNDS_COUNT);
+        assert_eq!(simulations[0].confidence_level, 0.95);
+        assert_eq!(simulations[0].seed, None);
+    }
+
+    #[test]
+    fn errors_name_the_bad_field() {
+        let error = |contents: &str| load_toml(contents).unwrap_err();
+        assert!(error("[model]\nrhos = [0.5, 1.2]\n").starts_with("model.rhos[1]:"));
+        assert!(error("[model]\n").starts_with("model.rhos:"));
+        assert!(
+            error("[model]\nrhos = [0.5]\npolicies = [\"fcfs\", \"sjf\"]\n")
+                .starts_with("model.policies[1]:")
+        );
+        assert!(error("[model]\nrhos = [0.5]\nservers = 2\n").starts_with("model.servers:"));
+        assert!(error("[model]\nrhos = [0.5]\ncapacity = 10\n").starts_with("model.capacity:"));
+        assert!(
+            error("[model]\nrhos = [0.5]\nservice = \"deterministic\"\n")
+                .starts_with("model.service:")
+        );
+        assert!(
+            error("[model]\nrhos = [0.5]\n[analysis]\nmethod = \"foo\"\n")
+                .starts_with("analysis.method:")
+        );
+        assert!(error("[model]\nrhos = [0.5]\n[analysis]\nrounds = 1\n")
+            .starts_with("analysis.rounds:"));
+        assert!(
+            error("[model]\nrhos = [0.5]\n[stopping]\nprecision = 0.0\n")
+                .starts_with("stopping.precision:")
+        );
+        // Os problemas vêm da validação dos parâmetros, com a chave do arquivo de cada campo
+        assert!(error(
+            "[model]\nrhos = [0.5]\n[analysis]\nmethod = \"batch-means\"\nparallel-queues = 2\n"
+        )
+        .starts_with("analysis.parallel-queues:"));
+        assert!(error("output-columns = []\n[model]\nrhos = [0.5]\n").contains("output-columns:"));
+        let problems =
+            error("[model]\nrhos = [0.5]\n[analysis]\nround-size = 0\nconfidence = 1.5\n");
+        assert!(problems.starts_with("analysis.round-size:"));
+        assert!(problems.contains("\n\tanalysis.confidence:"));
+        // Erros de sintaxe, de tipo e campos desconhecidos vêm do leitor de TOML com a chave
+        assert!(error("[model]\nrhos = 0.5\n").contains("model.rhos"));
+        assert!(error("[model]\nrhos = [0.5]\nrho = 0.5\n").contains("rho"));
+    }
+
+    #[test]
+    fn streaming_metrics_are_validated_against_the_method() {
+        let streaming = |method: &str| {
+            load_toml(&format!(
+                "[model]\nrhos = [0.5]\n[analysis]\nmethod = \"{}\"\nstreaming = [\"N\", \"Nq\"]\n",
+                method
+            ))
+        };
+        assert_eq!(
+            streaming("rounds").unwrap()[0].streaming_metrics,
+            vec!["N".to_string(), "Nq".to_string()]
+        );
+        assert!(streaming("batch-means").unwrap_err().contains("Nq"));
+    }
+
+    #[test]
+    fn unstable_rhos_need_to_be_allowed() {
+        let unstable = |method: &str| {
+            load_toml(&format!(
+                "[model]\nrhos = [1.2]\nallow-unstable = true\n[analysis]\nmethod = \"{}\"\n",
+                method
+            ))
+        };
+        assert!(unstable("batch-means").unwrap()[0].allow_unstable);
+        assert!(unstable("regenerative")
+            .unwrap_err()
+            .contains("regenerativo"));
+        assert!(unstable("rounds").unwrap_err().contains("rodadas"));
+    }
+}
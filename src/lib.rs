@@ -0,0 +1,55 @@
+// Biblioteca do simulador da fila M/M/1 com as políticas FCFS e LCFS. Os parâmetros de uma execução
+// são montados pelo SimulationBuilder, ou diretamente em SimulationParameters, e a função run
+// executa a simulação e devolve um SimulationReport com as estimativas, seus ICs e os valores
+// analíticos. O binário simulador_ad é apenas a linha de comando sobre esta biblioteca
+
+// Módulo com o tipo de erro da biblioteca
+mod error;
+// Módulo com a leitura e validação dos arquivos de experimento
+mod experiment_config;
+// Módulo onde definimos o simulador
+mod simulator;
+
+// Quantidade de fregueses por rodada quando --round-size não é passado
+pub const DEFAULT_ROUND_SIZE: usize = 1_000;
+// Quantidade de rodadas quando --rounds não é passado
+pub const DEFAULT_ROUNDS_COUNT: usize = 3200;
+
+// Exportando o tipo de erro devolvido pela biblioteca
+pub use error::SimulationError;
+// Exportando a leitura dos arquivos de experimento
+pub use experiment_config::load_experiment;
+// Exportando a execução do simulador, com e sem resultado estruturado, sua retomada de um
+// checkpoint, o sweep paralelo, a bateria de testes dos geradores e a impressão dos valores
+// analíticos
+pub use simulator::{
+    check_generators, print_analytic_values, resume_simulator, run, run_sweep, simulator,
+};
+// Exportando a struct dos parâmetros, seu builder e os enums que configuram uma execução
+pub use simulator::{
+    AnalysisMethod, GeneratorKind, QuantileEstimator, QueueMode, QueuePolicy, SimulationBuilder,
+    SimulationParameters, WarmupMethod,
+};
+// Exportando a fila, o freguês e a trait e a fábrica dos observadores dos eventos da fila
+pub use simulator::{Client, ObserverFactory, Queue, QueueObserver};
+// Exportando o registro dos eventos das filas, sua leitura e sua releitura
+pub use simulator::{
+    print_trace_replay, read_trace, replay_trace, EventTrace, ReplayedRound, TraceEvent,
+    TraceFilter, TraceRecord, TraceReplay,
+};
+// Exportando o resultado estruturado de uma execução e as representações que ele usa
+pub use simulator::{
+    AnalyticValues, ConfidenceInterval, Estimates, MetricEstimate, QuantileEstimate,
+    SimulationReport, StopReason,
+};
+// Exportando as colunas dos resultados de uma execução, seus formatos e modos de escrita
+pub use simulator::{
+    read_results, ResultValue, ResultsFormat, ResultsRecord, WriteMode, RESULTS_SCHEMA_VERSION,
+};
+// Exportando os gráficos dos resultados e o desenho de gráficos em SVG
+pub use simulator::{plot_results, Chart, Series, SeriesStyle};
+// Exportando a trait e os modelos analíticos, para que possam ser consultados sem simular
+pub use simulator::{
+    AnalyticModel, AnalyticModelKind, Kingman, MMc, NonPreemptivePriority, PriorityClass, MG1, MM1,
+    MM1K,
+};
@@ -1,40 +1,452 @@
-// Módulo onde definimos o simulador
-mod simulator;
+// Linha de comando do simulador, que só interpreta as opções e chama a biblioteca simulador_ad
 
-// Importamos nosso simulador e o enum de política de fila
-use simulator::simulator;
-use simulator::QueuePolicy;
-use crate::simulator::QueueMode;
+// Importamos a leitura dos arquivos de experimento e os valores padrão das rodadas
+use simulador_ad::{load_experiment, DEFAULT_ROUNDS_COUNT, DEFAULT_ROUND_SIZE};
+// Importamos o tipo de erro devolvido pela biblioteca
+use simulador_ad::SimulationError;
+// Importamos o registro de eventos, seu filtro e sua releitura
+use simulador_ad::{print_trace_replay, EventTrace, TraceFilter};
+// Importamos nosso simulador, sua retomada de um checkpoint, a bateria de testes dos geradores e a
+// impressão dos valores analíticos
+use simulador_ad::{
+    check_generators, print_analytic_values, resume_simulator, run_sweep, simulator,
+};
+// Importamos os enums e a struct que configuram uma execução
+use simulador_ad::{
+    AnalysisMethod, GeneratorKind, QuantileEstimator, QueueMode, QueuePolicy, SimulationParameters,
+    WarmupMethod,
+};
+// Importamos os gráficos dos resultados e o formato do arquivo lido por eles
+use simulador_ad::{plot_results, ResultsFormat};
+// Importamos a trait e os modelos analíticos consultados pelo subcomando analytic
+use simulador_ad::{
+    AnalyticModel, AnalyticModelKind, Kingman, MMc, NonPreemptivePriority, PriorityClass, MG1, MM1,
+    MM1K,
+};
+// Traits para converter os argumentos da linha de comando e exibir seus erros
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-fn main() {
+// Valores de ρ simulados pelo sweep quando --rhos não é passado
+const DEFAULT_SWEEP_RHOS: [f64; 4] = [0.2, 0.4, 0.6, 0.8];
+// Semente, tamanho da amostra e repetições da bateria de testes dos geradores
+const GENERATOR_CHECK_SEED: u64 = 2019;
+const GENERATOR_CHECK_SAMPLE_SIZE: usize = 100_000;
+const GENERATOR_CHECK_REPETITIONS: usize = 20;
+
+// Texto de ajuda, impresso quando o subcomando não é dado ou é desconhecido
+const USAGE: &str = "\
+Uso: simulador_ad <subcomando> [--opção=valor ...]
+
+Subcomandos:
+    run        Executa uma simulação (--rho é obrigatório no modo for-real)
+    sweep      Executa uma simulação para cada combinação de --rhos e --policies, em paralelo
+    verify     Executa o modo de corretude com as duas políticas e, com --generators, a bateria de
+               testes dos geradores de números aleatórios
+    analytic   Imprime os valores analíticos de um modelo, sem simular
+    experiment Valida e executa as simulações descritas num arquivo .toml ou .json (--config)
+    replay     Relê um registro de eventos (--trace) e recalcula as médias de W, N e Nq
+    plot       Desenha os gráficos em função de ρ a partir do arquivo dos resultados (--output)
+
+Opções das simulações:
+    --rho=<valor>              Utilização ρ = λ / μ, com μ = 1
+    --policy=<fcfs|lcfs>       Política de atendimento (padrão fcfs)
+    --mode=<for-real|check-correctness>
+    --rhos=<a,b,...>           Valores de ρ do sweep (padrão 0.2,0.4,0.6,0.8)
+    --policies=<a,b,...>       Políticas do sweep (padrão fcfs,lcfs)
+    --threads=<n>              Threads do sweep e do experiment (padrão uma por núcleo)
+    --rounds=<n>               Quantidade inicial de rodadas (padrão 3200)
+    --round-size=<n>           Fregueses por rodada (padrão 1000)
+    --seed=<n>                 Semente (padrão tirada do relógio)
+    --confidence=<valor>       Nível de confiança dos ICs (padrão 0.95)
+    --precision=<valor>        Precisão relativa alvo dos ICs (padrão 0.05)
+    --max-clients=<n>          Máximo de fregueses da regra de parada
+    --output=<arquivo>         Arquivo dos resultados (padrão output.csv)
+    --output-format=<csv|json|jsonl>  Formato dos resultados (padrão o da extensão do arquivo)
+    --output-mode=<append|overwrite>  Acrescenta ao arquivo ou o substitui (padrão append)
+    --output-columns=<a,b,...> Colunas dos resultados, na ordem dada (padrão todas)
+    --plots=<diretório>        Desenha em SVG a convergência das rodadas e o procedimento de Welch
+                               e, no sweep e no experiment, os gráficos em função de ρ
+    --generator=<std|pcg|xoshiro|chacha|lcg>
+    --method=<nome>            Método de análise de saída (padrão rounds)
+    --warmup=<nome>            Detecção da fase transiente (padrão utilization)
+    --quantiles=<exact|p-square>
+    --distributions            Exporta as distribuições de W, T e N
+    --autocorrelation=<lag>    Exporta as autocorrelações de W até o lag
+    --streaming[=<a,b,...>]    Acumula em fluxo as métricas dadas, ou todas das quais o método
+                               não precisa dos valores (X, N e, fora dos lotes, Nq)
+    --parallel-queues=<n>      Filas independentes que dividem as rodadas em paralelo (padrão 1)
+    --allow-unstable           Aceita ρ >= 1, simulando uma fila sem regime estacionário
+
+Opções do run:
+    --checkpoint=<arquivo>     Guarda periodicamente o estado do método das rodadas no arquivo
+    --checkpoint-interval=<n>  Rodadas entre dois checkpoints (padrão 100)
+    --resume=<arquivo>         Retoma a execução do checkpoint, com os parâmetros guardados nele
+    --trace=<arquivo>          Registra os eventos das filas num arquivo .csv ou .jsonl
+    --trace-window=<início,fim>  Registra apenas os eventos nessa janela de tempo
+    --trace-rounds=<primeira,última>  Registra apenas os eventos dessas rodadas (0 é a fase
+                               transiente)
+
+Opções do experiment:
+    --config=<arquivo>         Arquivo do experimento, com as seções model, analysis e stopping
+    --validate                 Apenas valida o arquivo e lista as simulações, sem executá-las
+
+Opções do plot:
+    --output=<arquivo>         Arquivo dos resultados (padrão output.csv)
+    --output-format=<csv|json|jsonl>  Formato dos resultados (padrão o da extensão do arquivo)
+    --plots=<diretório>        Diretório dos gráficos (padrão graficos)
+
+Opções do analytic:
+    --model=<mm1|mg1|mmc|mm1k|priority|kingman> (padrão mm1)
+    --rho=<valor>              Utilização de cada servidor, com E[X] = 1
+    --policy=<fcfs|lcfs>       Política da mm1
+    --service-moments=<E[X],E[X²],E[X³]>  Momentos do serviço da mg1 (padrão exponencial)
+    --servers=<c>              Servidores da mmc (padrão 2)
+    --capacity=<K>             Capacidade da mm1k
+    --classes=<n>              Classes de mesma carga da priority (padrão 2)
+    --arrival-scv=<valor>      ca² da kingman (padrão 1)
+    --service-scv=<valor>      cs² da kingman (padrão 1)
+";
+
+// Encerra o programa com a mensagem de erro
+fn exit_with_error(message: &dyn Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2);
+}
+
+// Devolve o valor de um resultado da biblioteca ou, caso seja um erro, encerra com ele
+fn exit_on_error<T>(result: Result<T, SimulationError>) -> T {
+    result.unwrap_or_else(|error| exit_with_error(&error))
+}
+
+// Lê um argumento no formato --nome=valor, caso ele tenha sido passado
+fn argument<T: FromStr>(name: &str) -> Option<T>
+where
+    T::Err: Display,
+{
+    let prefix = format!("--{}=", name);
+    std::env::args()
+        .find(|argument| argument.starts_with(&prefix))
+        .map(|argument| {
+            argument[prefix.len()..].parse().unwrap_or_else(|error| {
+                exit_with_error(&format!("Valor inválido para --{}: {}", name, error))
+            })
+        })
+}
+
+// Lê um argumento obrigatório no formato --nome=valor, encerrando com a ajuda caso ele falte
+fn required_argument<T: FromStr>(name: &str) -> T
+where
+    T::Err: Display,
+{
+    argument(name).unwrap_or_else(|| {
+        eprintln!("O argumento --{} é obrigatório\n\n{}", name, USAGE);
+        std::process::exit(2);
+    })
+}
+
+// Lê um argumento no formato --nome=a,b,c, caso ele tenha sido passado
+fn list_argument<T: FromStr>(name: &str) -> Option<Vec<T>>
+where
+    T::Err: Display,
+{
+    argument::<String>(name).map(|values| {
+        values
+            .split(',')
+            .map(|value| {
+                value.parse().unwrap_or_else(|error| {
+                    exit_with_error(&format!("Valor inválido para --{}: {}", name, error))
+                })
+            })
+            .collect()
+    })
+}
+
+// Lê um argumento no formato --nome=a,b, que deve ter exatamente dois valores
+fn pair_argument<T: FromStr + Copy>(name: &str) -> Option<(T, T)>
+where
+    T::Err: Display,
+{
+    list_argument(name).map(|values: Vec<T>| {
+        if values.len() != 2 {
+            exit_with_error(&format!(
+                "--{} precisa de dois valores separados por vírgula",
+                name
+            ));
+        }
+        (values[0], values[1])
+    })
+}
+
+// Indica se a opção --nome, sem valor, foi passada
+fn flag(name: &str) -> bool {
+    let option = format!("--{}", name);
+    std::env::args().any(|argument| argument == option)
+}
+
+// Monta os parâmetros de uma execução a partir das opções da linha de comando. As opções que não
+// são passadas ficam com os valores padrão de SimulationParameters
+fn parameters(rho: f64, queue_policy: QueuePolicy, queue_mode: QueueMode) -> SimulationParameters {
+    let mut parameters = SimulationParameters::new(
+        rho,
+        argument("round-size").unwrap_or(DEFAULT_ROUND_SIZE),
+        argument("rounds").unwrap_or(DEFAULT_ROUNDS_COUNT),
+        queue_policy,
+        queue_mode,
+    );
+    parameters.generator_kind = argument("generator").unwrap_or(GeneratorKind::Std);
+    if let Some(confidence_level) = argument("confidence") {
+        parameters.confidence_level = confidence_level;
+    }
+    parameters.analysis_method = argument("method").unwrap_or(AnalysisMethod::Rounds);
+    parameters.warmup_method = argument("warmup").unwrap_or(WarmupMethod::Utilization);
+    parameters.quantile_estimator = argument("quantiles").unwrap_or(QuantileEstimator::Exact);
+    if let Some(target_precision) = argument("precision") {
+        parameters.target_precision = target_precision;
+    }
+    if let Some(maximum_clients) = argument("max-clients") {
+        parameters.maximum_clients = maximum_clients;
+    }
+    parameters.export_distributions = flag("distributions");
+    parameters.autocorrelation_max_lag = argument("autocorrelation");
+    parameters.streaming_metrics = if flag("streaming") {
+        parameters
+            .streamable_metrics()
+            .iter()
+            .map(|metric| metric.to_string())
+            .collect()
+    } else {
+        list_argument("streaming").unwrap_or_default()
+    };
+    parameters.parallel_queues = argument("parallel-queues").unwrap_or(1);
+    parameters.allow_unstable = flag("allow-unstable");
+    parameters.seed = argument("seed");
+    if let Some(output_path) = argument("output") {
+        parameters.output_path = output_path;
+    }
+    parameters.output_format = argument("output-format");
+    if let Some(output_mode) = argument("output-mode") {
+        parameters.output_mode = output_mode;
+    }
+    parameters.output_columns = list_argument("output-columns");
+    parameters.plots_directory = argument("plots");
+    parameters
+}
 
-    // A taxa rho é desconsiderada quando executamos o simulador em modo de verificar corretude do mesmo
-    simulator(999.0, 1_000, 3200, QueuePolicy::FCFS, QueueMode::CheckCorrectness);
-    simulator(999.0, 1_000, 3200, QueuePolicy::LCFS, QueueMode::CheckCorrectness);
+// Subcomando run: uma única simulação, ou a retomada de uma a partir do seu checkpoint. A taxa ρ é
+// desconsiderada no modo de corretude
+fn run() {
+    if let Some(checkpoint_path) = argument::<PathBuf>("resume") {
+        exit_on_error(resume_simulator(&checkpoint_path));
+        return;
+    }
+    let queue_mode = argument("mode").unwrap_or(QueueMode::ForReal);
+    let rho = if queue_mode == QueueMode::ForReal {
+        required_argument("rho")
+    } else {
+        argument("rho").unwrap_or(0.0)
+    };
+    let queue_policy = argument("policy").unwrap_or(QueuePolicy::FCFS);
+    let mut parameters = parameters(rho, queue_policy, queue_mode);
+    parameters.checkpoint_path = argument("checkpoint");
+    if let Some(checkpoint_interval) = argument("checkpoint-interval") {
+        parameters.checkpoint_interval = checkpoint_interval;
+    }
+    let trace = argument::<PathBuf>("trace").map(|trace_path| {
+        let filter = TraceFilter {
+            time_window: pair_argument("trace-window"),
+            rounds: pair_argument("trace-rounds"),
+        };
+        exit_on_error(EventTrace::create(&trace_path, filter))
+    });
+    if let Some(trace) = &trace {
+        parameters.observer_factory = Some(trace.observer_factory());
+    }
+    exit_on_error(simulator(parameters));
+    if let Some(trace) = &trace {
+        exit_on_error(trace.finish());
+    }
+}
+
+// Quantidade de threads dos sweeps, por padrão uma por núcleo disponível
+fn threads() -> usize {
+    argument("threads")
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |threads| threads.get()))
+}
+
+// Subcomando sweep: uma simulação para cada ρ e política, executadas em paralelo
+fn sweep() {
+    let rhos = list_argument("rhos").unwrap_or_else(|| DEFAULT_SWEEP_RHOS.to_vec());
+    let queue_policies =
+        list_argument("policies").unwrap_or_else(|| vec![QueuePolicy::FCFS, QueuePolicy::LCFS]);
+    let mut simulations = vec![];
+    for &rho in rhos.iter() {
+        for &queue_policy in queue_policies.iter() {
+            simulations.push(parameters(rho, queue_policy, QueueMode::ForReal));
+        }
+    }
+    let plotted = simulations.clone();
+    exit_on_error(run_sweep(simulations, threads()));
+    draw_sweep_plots(&plotted);
+}
 
-    // Pequenos rhos, para constatar a corretude do simulador
-    simulator(0.1, 10_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-    simulator(0.1, 10_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+// Subcomando verify: o modo de corretude com as duas políticas e, opcionalmente, a bateria de
+// testes dos geradores. Retorna se os geradores passaram, ou verdadeiro quando não são testados
+fn verify() -> bool {
+    for &queue_policy in [QueuePolicy::FCFS, QueuePolicy::LCFS].iter() {
+        exit_on_error(simulator(parameters(
+            0.0,
+            queue_policy,
+            QueueMode::CheckCorrectness,
+        )));
+    }
+    !flag("generators")
+        || check_generators(
+            argument("seed").unwrap_or(GENERATOR_CHECK_SEED),
+            GENERATOR_CHECK_SAMPLE_SIZE,
+            GENERATOR_CHECK_REPETITIONS,
+        )
+}
 
-    simulator(0.01, 10_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-    simulator(0.01, 10_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+// Subcomando analytic: monta o modelo pedido e imprime seus valores analíticos. Todos os modelos
+// têm serviço médio 1, então ρ é a utilização de cada servidor
+fn analytic() {
+    let rho: f64 = required_argument("rho");
+    let model: Box<dyn AnalyticModel> = match argument("model").unwrap_or(AnalyticModelKind::MM1) {
+        AnalyticModelKind::MM1 => Box::new(exit_on_error(MM1::new(
+            rho,
+            1.0,
+            argument("policy").unwrap_or(QueuePolicy::FCFS),
+        ))),
+        AnalyticModelKind::MG1 => {
+            let moments: Vec<f64> =
+                list_argument("service-moments").unwrap_or_else(|| vec![1.0, 2.0, 6.0]);
+            if moments.len() != 2 && moments.len() != 3 {
+                exit_with_error(
+                    &"--service-moments precisa de E[X], E[X²] e, opcionalmente, E[X³]",
+                );
+            }
+            Box::new(exit_on_error(MG1::new(
+                rho / moments[0],
+                moments[0],
+                moments[1],
+                moments.get(2).copied(),
+            )))
+        }
+        AnalyticModelKind::MMc => {
+            let servers: usize = argument("servers").unwrap_or(2);
+            Box::new(exit_on_error(MMc::new(rho * servers as f64, 1.0, servers)))
+        }
+        AnalyticModelKind::MM1K => {
+            let model = exit_on_error(MM1K::new(rho, 1.0, required_argument("capacity")));
+            println!(
+                "Probabilidade de bloqueio = {:0.5}",
+                model.blocking_probability()
+            );
+            Box::new(model)
+        }
+        AnalyticModelKind::Priority => {
+            let classes_count: usize = argument("classes").unwrap_or(2);
+            let class = PriorityClass {
+                arrival_rate: rho / classes_count as f64,
+                mean_service_time: 1.0,
+                second_moment: 2.0,
+            };
+            let model = exit_on_error(NonPreemptivePriority::new(vec![class; classes_count]));
+            for class_index in 0..classes_count {
+                println!(
+                    "E[W] da classe {} = {:0.5}",
+                    class_index + 1,
+                    model.class_mean_w(class_index)
+                );
+            }
+            Box::new(model)
+        }
+        AnalyticModelKind::Kingman => Box::new(exit_on_error(Kingman::new(
+            rho,
+            1.0,
+            argument("arrival-scv").unwrap_or(1.0),
+            argument("service-scv").unwrap_or(1.0),
+        ))),
+    };
+    print_analytic_values(model.as_ref());
+}
 
-    simulator(0.001, 10_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-    simulator(0.001, 10_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+// Subcomando experiment: carrega e valida o arquivo de experimento e executa suas simulações em
+// paralelo. Um arquivo inválido encerra com o erro antes de qualquer simulação
+fn experiment() {
+    let config_path: PathBuf = required_argument("config");
+    let simulations = exit_on_error(load_experiment(&config_path));
+    if flag("validate") {
+        println!(
+            "{} é válido, com {} simulações:",
+            config_path.display(),
+            simulations.len()
+        );
+        for parameters in simulations.iter() {
+            println!(
+                "\tρ = {}; Política = {:?}; Modo = {:?}; Método = {:?}",
+                parameters.rho,
+                parameters.queue_policy,
+                parameters.queue_mode,
+                parameters.analysis_method
+            );
+        }
+        return;
+    }
+    let plotted = simulations.clone();
+    exit_on_error(run_sweep(simulations, threads()));
+    draw_sweep_plots(&plotted);
+}
 
-    // Para 0.0001 começamos com 31000 pois menos do que isso ele não convergia
-    simulator(0.000_1, 31_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-    simulator(0.000_1, 31_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+// Desenha os gráficos em função de ρ a partir do arquivo dos resultados, imprimindo os arquivos
+// criados
+fn draw_result_plots(results_path: &Path, format: Option<ResultsFormat>, directory: &Path) {
+    let format = format.unwrap_or_else(|| ResultsFormat::from_path(results_path));
+    for path in exit_on_error(plot_results(results_path, format, directory)) {
+        println!("Gráfico desenhado em {}", path.display());
+    }
+}
 
-    // Simulação com os rhos pedidos
-    let rhos = [0.2, 0.4, 0.6, 0.8];
-    for &rho in &rhos {
-        simulator(rho, 1_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-        simulator(rho, 1_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+// Desenha os gráficos em função de ρ de um sweep ou experiment, quando pedidos, a partir do arquivo
+// dos resultados da primeira simulação
+fn draw_sweep_plots(simulations: &[SimulationParameters]) {
+    if let Some(parameters) = simulations.first() {
+        if let Some(directory) = &parameters.plots_directory {
+            draw_result_plots(&parameters.output_path, parameters.output_format, directory);
+        }
     }
-    // Fizemos rho = 0.9 começar em 15000 pois demorava bem mais para convergir
-    simulator(0.9, 15_000, 3200, QueuePolicy::FCFS, QueueMode::ForReal);
-    simulator(0.9, 15_000, 3200, QueuePolicy::LCFS, QueueMode::ForReal);
+}
+
+// Subcomando plot: desenha os gráficos em função de ρ a partir do arquivo dos resultados
+fn plot() {
+    let results_path = argument("output").unwrap_or_else(|| PathBuf::from("output.csv"));
+    let directory = argument("plots").unwrap_or_else(|| PathBuf::from("graficos"));
+    draw_result_plots(&results_path, argument("output-format"), &directory);
+}
 
+// Subcomando replay: relê o registro de eventos de uma execução e imprime as médias recalculadas
+fn replay() {
+    let trace_path: PathBuf = required_argument("trace");
+    exit_on_error(print_trace_replay(&trace_path));
+}
 
+fn main() {
+    match std::env::args().nth(1).as_deref() {
+        Some("run") => run(),
+        Some("sweep") => sweep(),
+        Some("verify") => {
+            let all_passed = verify();
+            std::process::exit(if all_passed { 0 } else { 1 });
+        }
+        Some("analytic") => analytic(),
+        Some("experiment") => experiment(),
+        Some("replay") => replay(),
+        Some("plot") => plot(),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
 }
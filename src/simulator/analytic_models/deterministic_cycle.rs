@@ -0,0 +1,91 @@
+// Importando o enum da política de atendimento
+use crate::simulator::QueuePolicy;
+// Importando a trait dos modelos analíticos
+use super::AnalyticModel;
+
+// Fila determinística que repete sempre o mesmo ciclo, como a do modo de corretude. Os valores
+// vêm das esperas dos fregueses de um ciclo e da quantidade N de fregueses no sistema em cada
+// unidade de tempo do ciclo, e são calculados pela definição
+pub struct DeterministicCycle {
+    policy: QueuePolicy,      // Política de atendimento
+    waiting_times: Vec<f64>,  // Espera de cada freguês do ciclo
+    system_sizes: Vec<usize>, // N em cada unidade de tempo do ciclo
+}
+
+// Média simples de um vetor de valores
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Variância populacional de um vetor de valores, E[Y²] - E[Y]²
+fn variance(values: &[f64]) -> f64 {
+    let squares: Vec<f64> = values.iter().map(|value| value.powi(2)).collect();
+    mean(&squares) - mean(values).powi(2)
+}
+
+impl DeterministicCycle {
+    // Instancia o modelo a partir de um ciclo
+    pub fn new(policy: QueuePolicy, waiting_times: Vec<f64>, system_sizes: Vec<usize>) -> Self {
+        Self {
+            policy,
+            waiting_times,
+            system_sizes,
+        }
+    }
+
+    // Ciclo de 9 segundos do modo de corretude, com chegadas nos instantes 0, 1, 2 e 3 e serviço
+    // constante igual a 2. Na FCFS os fregueses começam a ser atendidos em 0, 2, 4 e 6. Na LCFS o
+    // freguês que chega em 2 é atendido na hora, o que chega em 3 começa em 4 e o que chega em 1
+    // só começa em 6
+    pub fn correctness_check(policy: QueuePolicy) -> Self {
+        let waiting_times = match policy {
+            QueuePolicy::FCFS => vec![0.0, 1.0, 2.0, 3.0],
+            QueuePolicy::LCFS => vec![0.0, 5.0, 0.0, 1.0],
+        };
+        Self::new(policy, waiting_times, vec![1, 2, 2, 3, 2, 2, 1, 1, 0])
+    }
+
+    // Nq em cada unidade de tempo do ciclo
+    fn queue_sizes(&self) -> Vec<f64> {
+        self.system_sizes
+            .iter()
+            .map(|&size| size.saturating_sub(1) as f64)
+            .collect()
+    }
+}
+
+impl AnalyticModel for DeterministicCycle {
+    fn name(&self) -> String {
+        format!("Ciclo determinístico {:?}", self.policy)
+    }
+
+    fn mean_w(&self) -> f64 {
+        mean(&self.waiting_times)
+    }
+
+    fn variance_w(&self) -> Option<f64> {
+        Some(variance(&self.waiting_times))
+    }
+
+    fn mean_nq(&self) -> f64 {
+        mean(&self.queue_sizes())
+    }
+
+    fn variance_nq(&self) -> Option<f64> {
+        Some(variance(&self.queue_sizes()))
+    }
+
+    fn mean_n(&self) -> f64 {
+        let system_sizes: Vec<f64> = self.system_sizes.iter().map(|&size| size as f64).collect();
+        mean(&system_sizes)
+    }
+
+    fn waiting_probability(&self) -> f64 {
+        let waiting = self
+            .waiting_times
+            .iter()
+            .filter(|&&time| time > 0.0)
+            .count();
+        waiting as f64 / self.waiting_times.len() as f64
+    }
+}
@@ -0,0 +1,91 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, stable_utilization, AnalyticModel};
+
+// Aproximação de Kingman para a fila G/G/1 em tráfego pesado,
+// E[W] ≈ E[X] ρ / (1 - ρ) (ca² + cs²) / 2, onde ca² e cs² são os quadrados dos coeficientes de
+// variação dos intervalos entre chegadas e dos tempos de serviço. Ela é exata na M/M/1 e fica
+// melhor quanto mais perto ρ está de 1. As variâncias não são aproximadas
+pub struct Kingman {
+    arrival_rate: f64,                   // Taxa de chegadas λ
+    mean_service_time: f64,              // E[X]
+    interarrival_squared_variation: f64, // ca²
+    service_squared_variation: f64,      // cs²
+}
+
+impl Kingman {
+    // Instancia o modelo, que só tem regime estacionário com ρ = λ E[X] em (0, 1)
+    pub fn new(
+        arrival_rate: f64,
+        mean_service_time: f64,
+        interarrival_squared_variation: f64,
+        service_squared_variation: f64,
+    ) -> Result<Self, SimulationError> {
+        check_model_parameters(vec![
+            (
+                mean_service_time > 0.0,
+                format!("E[X] = {} deve ser positivo", mean_service_time),
+            ),
+            (
+                interarrival_squared_variation >= 0.0 && service_squared_variation >= 0.0,
+                format!(
+                    "os coeficientes de variação ao quadrado ca² = {} e cs² = {} não podem ser \
+                     negativos",
+                    interarrival_squared_variation, service_squared_variation
+                ),
+            ),
+            stable_utilization("G/G/1", arrival_rate * mean_service_time),
+        ])?;
+        Ok(Self {
+            arrival_rate,
+            mean_service_time,
+            interarrival_squared_variation,
+            service_squared_variation,
+        })
+    }
+
+    // Utilização do servidor
+    fn rho(&self) -> f64 {
+        self.arrival_rate * self.mean_service_time
+    }
+}
+
+impl AnalyticModel for Kingman {
+    fn name(&self) -> String {
+        "G/G/1 FCFS (Kingman)".to_string()
+    }
+
+    fn exact(&self) -> bool {
+        false
+    }
+
+    fn mean_w(&self) -> f64 {
+        let rho = self.rho();
+        self.mean_service_time * rho / (1.0 - rho)
+            * (self.interarrival_squared_variation + self.service_squared_variation)
+            / 2.0
+    }
+
+    fn variance_w(&self) -> Option<f64> {
+        None
+    }
+
+    fn mean_nq(&self) -> f64 {
+        self.arrival_rate * self.mean_w()
+    }
+
+    fn variance_nq(&self) -> Option<f64> {
+        None
+    }
+
+    fn mean_n(&self) -> f64 {
+        self.mean_nq() + self.rho()
+    }
+
+    // Sem chegadas Poisson a fração de chegadas que encontra o servidor ocupado não é ρ, então
+    // isso também é uma aproximação
+    fn waiting_probability(&self) -> f64 {
+        self.rho()
+    }
+}
@@ -0,0 +1,90 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, stable_utilization, AnalyticModel};
+
+// Fila M/G/1 FCFS pela fórmula de Pollaczek-Khinchine. E[W] e E[Nq] valem para qualquer política
+// conservativa e não preemptiva que não olhe o tempo de serviço. As variâncias precisam do
+// terceiro momento do serviço, que é opcional
+pub struct MG1 {
+    arrival_rate: f64,         // Taxa de chegadas λ
+    mean_service_time: f64,    // E[X]
+    second_moment: f64,        // E[X²]
+    third_moment: Option<f64>, // E[X³], quando conhecido
+}
+
+impl MG1 {
+    // Instancia o modelo, que só tem regime estacionário com ρ = λ E[X] em (0, 1). Os momentos
+    // precisam ser os de uma distribuição, com E[X²] >= E[X]²
+    pub fn new(
+        arrival_rate: f64,
+        mean_service_time: f64,
+        second_moment: f64,
+        third_moment: Option<f64>,
+    ) -> Result<Self, SimulationError> {
+        check_model_parameters(vec![
+            (
+                mean_service_time > 0.0,
+                format!("E[X] = {} deve ser positivo", mean_service_time),
+            ),
+            (
+                second_moment >= mean_service_time.powi(2),
+                format!(
+                    "E[X²] = {} deve ser pelo menos E[X]² = {}",
+                    second_moment,
+                    mean_service_time.powi(2)
+                ),
+            ),
+            stable_utilization("M/G/1", arrival_rate * mean_service_time),
+        ])?;
+        Ok(Self {
+            arrival_rate,
+            mean_service_time,
+            second_moment,
+            third_moment,
+        })
+    }
+
+    // Utilização do servidor
+    fn rho(&self) -> f64 {
+        self.arrival_rate * self.mean_service_time
+    }
+}
+
+impl AnalyticModel for MG1 {
+    fn name(&self) -> String {
+        "M/G/1 FCFS".to_string()
+    }
+
+    // E[W] = λ E[X²] / (2 (1 - ρ))
+    fn mean_w(&self) -> f64 {
+        self.arrival_rate * self.second_moment / (2.0 * (1.0 - self.rho()))
+    }
+
+    // Pela fórmula de Takács, E[W²] = 2 E[W]² + λ E[X³] / (3 (1 - ρ))
+    fn variance_w(&self) -> Option<f64> {
+        let mean_w = self.mean_w();
+        self.third_moment.map(|third_moment| {
+            mean_w.powi(2) + self.arrival_rate * third_moment / (3.0 * (1.0 - self.rho()))
+        })
+    }
+
+    fn mean_nq(&self) -> f64 {
+        self.arrival_rate * self.mean_w()
+    }
+
+    // Na FCFS, Nq na saída de um freguês é a quantidade de chegadas Poisson durante sua espera,
+    // então pela lei de Little distribucional V(Nq) = λ² V(W) + λ E[W]
+    fn variance_nq(&self) -> Option<f64> {
+        self.variance_w()
+            .map(|variance_w| self.arrival_rate.powi(2) * variance_w + self.mean_nq())
+    }
+
+    fn mean_n(&self) -> f64 {
+        self.mean_nq() + self.rho()
+    }
+
+    fn waiting_probability(&self) -> f64 {
+        self.rho()
+    }
+}
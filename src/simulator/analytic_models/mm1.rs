@@ -0,0 +1,80 @@
+// Importando o enum da política de atendimento
+use crate::simulator::QueuePolicy;
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, stable_utilization, AnalyticModel};
+
+// Fila M/M/1 com política FCFS ou LCFS não preemptiva. As médias e a distribuição de Nq não
+// dependem da política, só a variância de W
+pub struct MM1 {
+    arrival_rate: f64,   // Taxa de chegadas λ
+    service_rate: f64,   // Taxa de serviço μ
+    policy: QueuePolicy, // Política de atendimento
+}
+
+impl MM1 {
+    // Instancia o modelo, que só tem regime estacionário com ρ = λ / μ em (0, 1)
+    pub fn new(
+        arrival_rate: f64,
+        service_rate: f64,
+        policy: QueuePolicy,
+    ) -> Result<Self, SimulationError> {
+        check_model_parameters(vec![
+            (
+                service_rate > 0.0,
+                format!("a taxa de serviço μ = {} deve ser positiva", service_rate),
+            ),
+            stable_utilization("M/M/1", arrival_rate / service_rate),
+        ])?;
+        Ok(Self {
+            arrival_rate,
+            service_rate,
+            policy,
+        })
+    }
+
+    // Utilização do servidor
+    fn rho(&self) -> f64 {
+        self.arrival_rate / self.service_rate
+    }
+}
+
+impl AnalyticModel for MM1 {
+    fn name(&self) -> String {
+        format!("M/M/1 {:?}", self.policy)
+    }
+
+    fn mean_w(&self) -> f64 {
+        self.rho() / (self.service_rate - self.arrival_rate)
+    }
+
+    fn variance_w(&self) -> Option<f64> {
+        let rho = self.rho();
+        // Fórmulas com μ = 1, reescaladas pelo quadrado de μ
+        let unit_variance = match self.policy {
+            QueuePolicy::FCFS => (2.0 * rho - rho.powi(2)) / (1.0 - rho).powi(2),
+            QueuePolicy::LCFS => (2.0 * rho - rho.powi(2) + rho.powi(3)) / (1.0 - rho).powi(3),
+        };
+        Some(unit_variance / self.service_rate.powi(2))
+    }
+
+    fn mean_nq(&self) -> f64 {
+        let rho = self.rho();
+        rho.powi(2) / (1.0 - rho)
+    }
+
+    fn variance_nq(&self) -> Option<f64> {
+        let rho = self.rho();
+        Some((rho.powi(2) + rho.powi(3) - rho.powi(4)) / (1.0 - rho).powi(2))
+    }
+
+    fn mean_n(&self) -> f64 {
+        let rho = self.rho();
+        rho / (1.0 - rho)
+    }
+
+    fn waiting_probability(&self) -> f64 {
+        self.rho()
+    }
+}
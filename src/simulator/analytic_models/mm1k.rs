@@ -0,0 +1,124 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, AnalyticModel};
+
+// Fila M/M/1/K FCFS, com no máximo K fregueses no sistema. Quem chega com o sistema cheio é
+// bloqueado, então as métricas por freguês são dos fregueses admitidos, que chegam à taxa
+// λ (1 - P(N = K)). Como o buffer é finito, o modelo vale para qualquer ρ
+pub struct MM1K {
+    arrival_rate: f64, // Taxa de chegadas λ
+    service_rate: f64, // Taxa de serviço μ
+    capacity: usize,   // Capacidade K do sistema, incluindo o freguês em serviço
+}
+
+impl MM1K {
+    // Instancia o modelo, que aceita qualquer ρ positivo
+    pub fn new(
+        arrival_rate: f64,
+        service_rate: f64,
+        capacity: usize,
+    ) -> Result<Self, SimulationError> {
+        check_model_parameters(vec![
+            (
+                capacity > 0,
+                "a fila M/M/1/K precisa de capacidade para pelo menos um freguês".to_string(),
+            ),
+            (
+                service_rate > 0.0,
+                format!("a taxa de serviço μ = {} deve ser positiva", service_rate),
+            ),
+            (
+                arrival_rate > 0.0 && arrival_rate.is_finite(),
+                format!("a taxa de chegadas λ = {} deve ser positiva", arrival_rate),
+            ),
+        ])?;
+        Ok(Self {
+            arrival_rate,
+            service_rate,
+            capacity,
+        })
+    }
+
+    // Distribuição estacionária P(N = n) ∝ ρ^n, para n = 0, ..., K
+    fn state_probabilities(&self) -> Vec<f64> {
+        let rho = self.arrival_rate / self.service_rate;
+        let weights: Vec<f64> = (0..=self.capacity).map(|n| rho.powi(n as i32)).collect();
+        let total: f64 = weights.iter().sum();
+        weights.iter().map(|weight| weight / total).collect()
+    }
+
+    // Probabilidade de um freguês ser bloqueado, que pelas chegadas Poisson é P(N = K)
+    pub fn blocking_probability(&self) -> f64 {
+        self.state_probabilities()[self.capacity]
+    }
+
+    // Distribuição de N vista por um freguês admitido, P(N = n) / (1 - P(N = K)) para n < K
+    fn admitted_arrival_probabilities(&self) -> Vec<f64> {
+        let probabilities = self.state_probabilities();
+        let admitted = 1.0 - probabilities[self.capacity];
+        probabilities[..self.capacity]
+            .iter()
+            .map(|probability| probability / admitted)
+            .collect()
+    }
+
+    // Momento de ordem order de Nq = max(N - 1, 0), ponderado pelo tempo
+    fn nq_moment(&self, order: i32) -> f64 {
+        self.state_probabilities()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(n, probability)| probability * ((n - 1) as f64).powi(order))
+            .sum()
+    }
+}
+
+impl AnalyticModel for MM1K {
+    fn name(&self) -> String {
+        format!("M/M/1/{} FCFS", self.capacity)
+    }
+
+    // Um freguês admitido que encontra n fregueses espera n serviços exponenciais
+    fn mean_w(&self) -> f64 {
+        self.admitted_arrival_probabilities()
+            .iter()
+            .enumerate()
+            .map(|(n, probability)| probability * n as f64)
+            .sum::<f64>()
+            / self.service_rate
+    }
+
+    // A espera de quem encontra n fregueses é Erlang(n, μ), com E[W²] = n (n + 1) / μ²
+    fn variance_w(&self) -> Option<f64> {
+        let second_moment = self
+            .admitted_arrival_probabilities()
+            .iter()
+            .enumerate()
+            .map(|(n, probability)| probability * (n * (n + 1)) as f64)
+            .sum::<f64>()
+            / self.service_rate.powi(2);
+        Some(second_moment - self.mean_w().powi(2))
+    }
+
+    fn mean_nq(&self) -> f64 {
+        self.nq_moment(1)
+    }
+
+    fn variance_nq(&self) -> Option<f64> {
+        Some(self.nq_moment(2) - self.nq_moment(1).powi(2))
+    }
+
+    fn mean_n(&self) -> f64 {
+        self.state_probabilities()
+            .iter()
+            .enumerate()
+            .map(|(n, probability)| probability * n as f64)
+            .sum()
+    }
+
+    // Probabilidade de um freguês admitido encontrar o servidor ocupado
+    fn waiting_probability(&self) -> f64 {
+        1.0 - self.admitted_arrival_probabilities()[0]
+    }
+}
@@ -0,0 +1,101 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, stable_utilization, AnalyticModel};
+
+// Fila M/M/c FCFS pela fórmula de Erlang C. Quem espera, espera um tempo exponencial com taxa
+// c μ - λ, e o tamanho da fila de quem espera é geométrico com razão ρ = λ / (c μ)
+pub struct MMc {
+    arrival_rate: f64, // Taxa de chegadas λ
+    service_rate: f64, // Taxa de serviço μ de cada servidor
+    servers: usize,    // Quantidade de servidores c
+}
+
+impl MMc {
+    // Instancia o modelo, que só tem regime estacionário com ρ = λ / (c μ) em (0, 1). A utilização
+    // só é verificada quando há servidores para dividi-la
+    pub fn new(
+        arrival_rate: f64,
+        service_rate: f64,
+        servers: usize,
+    ) -> Result<Self, SimulationError> {
+        let mut checks = vec![
+            (
+                servers > 0,
+                "a fila M/M/c precisa de pelo menos um servidor".to_string(),
+            ),
+            (
+                service_rate > 0.0,
+                format!("a taxa de serviço μ = {} deve ser positiva", service_rate),
+            ),
+        ];
+        if servers > 0 {
+            checks.push(stable_utilization(
+                "M/M/c",
+                arrival_rate / (servers as f64 * service_rate),
+            ));
+        }
+        check_model_parameters(checks)?;
+        Ok(Self {
+            arrival_rate,
+            service_rate,
+            servers,
+        })
+    }
+
+    // Utilização de cada servidor
+    fn rho(&self) -> f64 {
+        self.arrival_rate / (self.servers as f64 * self.service_rate)
+    }
+
+    // Taxa com que a fila é esvaziada quando todos os servidores estão ocupados, c μ - λ
+    fn drain_rate(&self) -> f64 {
+        self.servers as f64 * self.service_rate - self.arrival_rate
+    }
+
+    // Probabilidade de espera de Erlang C, calculada a partir da recursão estável de Erlang B,
+    // B(k) = a B(k - 1) / (k + a B(k - 1)), com a = λ / μ
+    fn erlang_c(&self) -> f64 {
+        let offered_load = self.arrival_rate / self.service_rate;
+        let erlang_b = (1..=self.servers).fold(1.0, |erlang_b, servers| {
+            offered_load * erlang_b / (servers as f64 + offered_load * erlang_b)
+        });
+        erlang_b / (1.0 - self.rho() * (1.0 - erlang_b))
+    }
+}
+
+impl AnalyticModel for MMc {
+    fn name(&self) -> String {
+        format!("M/M/{} FCFS", self.servers)
+    }
+
+    fn mean_w(&self) -> f64 {
+        self.erlang_c() / self.drain_rate()
+    }
+
+    // E[W²] = 2 C / (c μ - λ)²
+    fn variance_w(&self) -> Option<f64> {
+        let waiting_probability = self.erlang_c();
+        Some(waiting_probability * (2.0 - waiting_probability) / self.drain_rate().powi(2))
+    }
+
+    fn mean_nq(&self) -> f64 {
+        let rho = self.rho();
+        self.erlang_c() * rho / (1.0 - rho)
+    }
+
+    // P(Nq = k) = C (1 - ρ) ρ^k para k >= 1, então E[Nq²] = C ρ (1 + ρ) / (1 - ρ)²
+    fn variance_nq(&self) -> Option<f64> {
+        let rho = self.rho();
+        let second_moment = self.erlang_c() * rho * (1.0 + rho) / (1.0 - rho).powi(2);
+        Some(second_moment - self.mean_nq().powi(2))
+    }
+
+    fn mean_n(&self) -> f64 {
+        self.mean_nq() + self.arrival_rate / self.service_rate
+    }
+
+    fn waiting_probability(&self) -> f64 {
+        self.erlang_c()
+    }
+}
@@ -0,0 +1,323 @@
+// Módulo com o ciclo determinístico do modo de corretude
+pub(crate) mod deterministic_cycle;
+// Módulo com a aproximação de Kingman para a fila G/G/1
+pub(crate) mod kingman;
+// Módulo com a fila M/G/1 pela fórmula de Pollaczek-Khinchine
+pub(crate) mod mg1;
+// Módulo com a fila M/M/1 FCFS e LCFS
+pub(crate) mod mm1;
+// Módulo com a fila M/M/1/K de capacidade finita
+pub(crate) mod mm1k;
+// Módulo com a fila M/M/c pela fórmula de Erlang C
+pub(crate) mod mmc;
+// Módulo com a fila M/G/1 com prioridades não preemptivas pela fórmula de Cobham
+pub(crate) mod priority;
+
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a taxa de serviço da fila simulada
+use crate::simulator::queue::SERVICE_RATE;
+// Importando o modo de simulação da fila e os parâmetros de uma execução
+use crate::simulator::{QueueMode, SimulationParameters};
+// Importando os modelos executados pelo simulador
+use deterministic_cycle::DeterministicCycle;
+use mm1::MM1;
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
+
+// Enum para selecionar um modelo analítico pela linha de comando
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnalyticModelKind {
+    // M/M/1 FCFS ou LCFS
+    MM1,
+    // M/G/1 FCFS por Pollaczek-Khinchine
+    MG1,
+    // M/M/c FCFS por Erlang C
+    MMc,
+    // M/M/1/K FCFS com capacidade finita
+    MM1K,
+    // M/G/1 com prioridades não preemptivas por Cobham
+    Priority,
+    // Aproximação de Kingman para a G/G/1
+    Kingman,
+}
+
+impl FromStr for AnalyticModelKind {
+    type Err = String;
+
+    // Converte o nome do modelo, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "mm1" => Ok(AnalyticModelKind::MM1),
+            "mg1" => Ok(AnalyticModelKind::MG1),
+            "mmc" => Ok(AnalyticModelKind::MMc),
+            "mm1k" => Ok(AnalyticModelKind::MM1K),
+            "priority" => Ok(AnalyticModelKind::Priority),
+            "kingman" => Ok(AnalyticModelKind::Kingman),
+            _ => Err(format!("Modelo analítico desconhecido: {}", name)),
+        }
+    }
+}
+
+// Trait implementada por cada modelo analítico de fila. Os valores são exatos, a não ser que o
+// modelo seja uma aproximação. As variâncias são None quando o modelo não tem forma fechada
+// para elas
+pub trait AnalyticModel {
+    // Nome do modelo, como é impresso
+    fn name(&self) -> String;
+    // Indica se os valores são exatos ou aproximados
+    fn exact(&self) -> bool {
+        true
+    }
+    // Tempo médio de espera na fila E[W]
+    fn mean_w(&self) -> f64;
+    // Variância do tempo de espera na fila V(W)
+    fn variance_w(&self) -> Option<f64>;
+    // Número médio de fregueses na fila E[Nq]
+    fn mean_nq(&self) -> f64;
+    // Variância do número de fregueses na fila V(Nq)
+    fn variance_nq(&self) -> Option<f64>;
+    // Número médio de fregueses no sistema E[N]
+    fn mean_n(&self) -> f64;
+    // Probabilidade de um freguês precisar esperar, P(W > 0)
+    fn waiting_probability(&self) -> f64;
+}
+
+// Verifica os parâmetros de um modelo, devolvendo todos os problemas encontrados de uma vez, como
+// na validação dos parâmetros das simulações. Cada verificação é uma condição e o problema
+// descrito quando ela não vale
+fn check_model_parameters(checks: Vec<(bool, String)>) -> Result<(), SimulationError> {
+    let problems: Vec<String> = checks
+        .into_iter()
+        .filter(|(condition, _)| !condition)
+        .map(|(_, problem)| problem)
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(SimulationError::InvalidParameters(problems))
+    }
+}
+
+// Verificação de que a utilização de um modelo está em (0, 1), ou seja, de que ele tem regime
+// estacionário
+fn stable_utilization(model: &str, rho: f64) -> (bool, String) {
+    (
+        rho > 0.0 && rho < 1.0,
+        format!(
+            "ρ = {} deve estar em (0, 1) para a fila {} ser estável",
+            rho, model
+        ),
+    )
+}
+
+// Modelo analítico da fila simulada com os parâmetros dados, usado na validação dos resultados
+pub fn analytic_model(parameters: &SimulationParameters) -> Option<Box<dyn AnalyticModel>> {
+    // Uma fila instável não tem regime estacionário, então não tem valores analíticos
+    if !parameters.stable() {
+        return None;
+    }
+    match parameters.queue_mode {
+        QueueMode::ForReal => MM1::new(
+            parameters.rho * SERVICE_RATE,
+            SERVICE_RATE,
+            parameters.queue_policy,
+        )
+        .ok()
+        .map(|model| Box::new(model) as Box<dyn AnalyticModel>),
+        QueueMode::CheckCorrectness => Some(Box::new(DeterministicCycle::correctness_check(
+            parameters.queue_policy,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::kingman::Kingman;
+    use super::mg1::MG1;
+    use super::mm1k::MM1K;
+    use super::mmc::MMc;
+    use super::priority::{NonPreemptivePriority, PriorityClass};
+    use super::*;
+    use crate::simulator::test_helpers::assert_close;
+    use crate::simulator::QueuePolicy;
+
+    const RHO: f64 = 0.7;
+
+    // Compara todos os valores de dois modelos, incluindo as variâncias quando ambos as conhecem
+    fn assert_same_model(actual: &dyn AnalyticModel, expected: &dyn AnalyticModel) {
+        assert_close(actual.mean_w(), expected.mean_w());
+        assert_close(actual.mean_nq(), expected.mean_nq());
+        assert_close(actual.mean_n(), expected.mean_n());
+        assert_close(actual.waiting_probability(), expected.waiting_probability());
+        if let (Some(actual), Some(expected)) = (actual.variance_w(), expected.variance_w()) {
+            assert_close(actual, expected);
+        }
+        if let (Some(actual), Some(expected)) = (actual.variance_nq(), expected.variance_nq()) {
+            assert_close(actual, expected);
+        }
+    }
+
+    #[test]
+    fn mm1_matches_the_textbook_formulas() {
+        let model = MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap();
+        assert_close(model.mean_w(), RHO / (1.0 - RHO));
+        assert_close(model.mean_nq(), RHO.powi(2) / (1.0 - RHO));
+        assert_close(model.mean_n(), RHO / (1.0 - RHO));
+        // A LCFS só muda a variância de W, que é maior
+        let lcfs = MM1::new(RHO, 1.0, QueuePolicy::LCFS).unwrap();
+        assert_close(lcfs.mean_w(), model.mean_w());
+        assert!(lcfs.variance_w().unwrap() > model.variance_w().unwrap());
+    }
+
+    #[test]
+    fn mg1_with_exponential_service_is_the_mm1() {
+        // Momentos da exponencial com taxa 1: E[X] = 1, E[X²] = 2 e E[X³] = 6
+        let mg1 = MG1::new(RHO, 1.0, 2.0, Some(6.0)).unwrap();
+        assert_same_model(&mg1, &MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap());
+        assert!(mg1.variance_w().is_some() && mg1.variance_nq().is_some());
+        assert!(MG1::new(RHO, 1.0, 2.0, None)
+            .unwrap()
+            .variance_w()
+            .is_none());
+    }
+
+    #[test]
+    fn mg1_with_deterministic_service_halves_the_wait() {
+        let deterministic = MG1::new(RHO, 1.0, 1.0, Some(1.0)).unwrap();
+        let exponential = MG1::new(RHO, 1.0, 2.0, Some(6.0)).unwrap();
+        assert_close(deterministic.mean_w(), exponential.mean_w() / 2.0);
+    }
+
+    #[test]
+    fn mmc_with_one_server_is_the_mm1() {
+        assert_same_model(
+            &MMc::new(RHO, 1.0, 1).unwrap(),
+            &MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap(),
+        );
+    }
+
+    #[test]
+    fn mm2_matches_hand_computed_erlang_c() {
+        // Com a = 1 e c = 2: P(N = 0) = 1 / 3 e C = 1 / 3, então E[W] = C / (c μ - λ) = 1 / 3
+        let model = MMc::new(1.0, 1.0, 2).unwrap();
+        assert_close(model.waiting_probability(), 1.0 / 3.0);
+        assert_close(model.mean_w(), 1.0 / 3.0);
+        assert_close(model.mean_nq(), 1.0 / 3.0);
+        assert_close(model.mean_n(), 4.0 / 3.0);
+    }
+
+    #[test]
+    fn mm1k_with_a_large_buffer_is_the_mm1() {
+        let model = MM1K::new(RHO, 1.0, 400).unwrap();
+        assert!(model.blocking_probability() < 1e-12);
+        assert_same_model(&model, &MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap());
+    }
+
+    #[test]
+    fn mm1k_without_a_queue_blocks_instead_of_waiting() {
+        // Com K = 1 ninguém espera, e um freguês é bloqueado com probabilidade ρ / (1 + ρ)
+        let model = MM1K::new(RHO, 1.0, 1).unwrap();
+        assert_close(model.blocking_probability(), RHO / (1.0 + RHO));
+        assert_close(model.mean_w(), 0.0);
+        assert_close(model.mean_nq(), 0.0);
+        assert_close(model.waiting_probability(), 0.0);
+        // O buffer finito também tem regime estacionário com ρ >= 1
+        assert!(MM1K::new(2.0, 1.0, 5).unwrap().mean_n() < 5.0);
+    }
+
+    #[test]
+    fn priority_with_one_class_is_the_mg1() {
+        let class = PriorityClass {
+            arrival_rate: RHO,
+            mean_service_time: 1.0,
+            second_moment: 2.0,
+        };
+        assert_same_model(
+            &NonPreemptivePriority::new(vec![class]).unwrap(),
+            &MG1::new(RHO, 1.0, 2.0, None).unwrap(),
+        );
+    }
+
+    #[test]
+    fn priority_classes_conserve_the_fcfs_wait() {
+        let class = PriorityClass {
+            arrival_rate: RHO / 2.0,
+            mean_service_time: 1.0,
+            second_moment: 2.0,
+        };
+        let model = NonPreemptivePriority::new(vec![class, class]).unwrap();
+        // A classe mais prioritária espera menos, mas pela lei de conservação a espera média é a
+        // mesma da FCFS quando as classes têm o mesmo serviço
+        assert!(model.class_mean_w(0) < model.class_mean_w(1));
+        assert_close(
+            model.mean_w(),
+            MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap().mean_w(),
+        );
+    }
+
+    #[test]
+    fn kingman_is_exact_for_the_mm1() {
+        let model = Kingman::new(RHO, 1.0, 1.0, 1.0).unwrap();
+        assert!(!model.exact());
+        assert_close(
+            model.mean_w(),
+            MM1::new(RHO, 1.0, QueuePolicy::FCFS).unwrap().mean_w(),
+        );
+        // Sem variabilidade nenhuma ninguém espera
+        assert_close(Kingman::new(RHO, 1.0, 0.0, 0.0).unwrap().mean_w(), 0.0);
+    }
+
+    // Quantidade de problemas encontrados nos parâmetros de um modelo
+    fn problems<T>(model: Result<T, SimulationError>) -> usize {
+        match model {
+            Ok(_) => 0,
+            Err(SimulationError::InvalidParameters(problems)) => problems.len(),
+            Err(_) => panic!("erro inesperado"),
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_are_errors_instead_of_panics() {
+        assert_eq!(problems(MM1::new(1.5, 1.0, QueuePolicy::FCFS)), 1);
+        assert_eq!(problems(MM1::new(-1.0, 1.0, QueuePolicy::FCFS)), 1);
+        assert_eq!(problems(MM1::new(f64::NAN, 1.0, QueuePolicy::FCFS)), 1);
+        assert_eq!(problems(MG1::new(RHO, 1.0, 0.5, None)), 1);
+        assert_eq!(problems(MG1::new(RHO, 0.0, 2.0, None)), 2);
+        assert_eq!(problems(MMc::new(2.4, 1.0, 2)), 1);
+        // Sem servidores a utilização nem é calculada
+        assert_eq!(problems(MMc::new(0.0, 1.0, 0)), 1);
+        assert_eq!(problems(MM1K::new(RHO, 1.0, 0)), 1);
+        assert_eq!(problems(MM1K::new(-RHO, 1.0, 3)), 1);
+        assert_eq!(problems(Kingman::new(RHO, 1.0, -1.0, 1.0)), 1);
+        assert_eq!(problems(Kingman::new(1.2, 1.0, 1.0, 1.0)), 1);
+        assert_eq!(problems(NonPreemptivePriority::new(vec![])), 1);
+        let class = PriorityClass {
+            arrival_rate: 0.6,
+            mean_service_time: 1.0,
+            second_moment: 2.0,
+        };
+        assert_eq!(problems(NonPreemptivePriority::new(vec![class; 2])), 1);
+    }
+
+    #[test]
+    fn correctness_cycle_matches_the_definition() {
+        for &policy in [QueuePolicy::FCFS, QueuePolicy::LCFS].iter() {
+            let model = DeterministicCycle::correctness_check(policy);
+            assert_close(model.mean_w(), 1.5);
+            // Nq vale 1 por 4 segundos, 2 por 1 segundo e 0 pelos outros 4
+            assert_close(model.mean_nq(), 6.0 / 9.0);
+            assert_close(
+                model.variance_nq().unwrap(),
+                8.0 / 9.0 - (6.0f64 / 9.0).powi(2),
+            );
+            assert_close(model.mean_n(), 14.0 / 9.0);
+        }
+        let fcfs = DeterministicCycle::correctness_check(QueuePolicy::FCFS);
+        let lcfs = DeterministicCycle::correctness_check(QueuePolicy::LCFS);
+        assert_close(fcfs.variance_w().unwrap(), 14.0 / 4.0 - 1.5f64.powi(2));
+        assert_close(lcfs.variance_w().unwrap(), 26.0 / 4.0 - 1.5f64.powi(2));
+        assert_close(fcfs.waiting_probability(), 0.75);
+        assert_close(lcfs.waiting_probability(), 0.5);
+    }
+}
@@ -0,0 +1,118 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a trait dos modelos analíticos e as verificações dos seus parâmetros
+use super::{check_model_parameters, stable_utilization, AnalyticModel};
+
+// Classe de fregueses da fila com prioridades, com chegadas Poisson e serviço geral
+#[derive(Debug, Copy, Clone)]
+pub struct PriorityClass {
+    pub arrival_rate: f64,      // Taxa de chegadas λ_k da classe
+    pub mean_service_time: f64, // E[X_k]
+    pub second_moment: f64,     // E[X_k²]
+}
+
+// Fila M/G/1 com prioridades não preemptivas pela fórmula de Cobham. As classes vêm em ordem
+// decrescente de prioridade, e a espera média da classe k é
+// E[W_k] = W0 / ((1 - σ_(k-1)) (1 - σ_k)), onde W0 = Σ λ_i E[X_i²] / 2 é o trabalho residual
+// médio e σ_k a soma das utilizações das k classes mais prioritárias. As métricas agregadas são
+// médias ponderadas pelas taxas de chegada. As variâncias não têm forma fechada simples
+pub struct NonPreemptivePriority {
+    classes: Vec<PriorityClass>, // Classes em ordem decrescente de prioridade
+}
+
+impl NonPreemptivePriority {
+    // Instancia o modelo, que só tem regime estacionário com utilização total em (0, 1). A
+    // utilização só é verificada quando há classes para somá-la
+    pub fn new(classes: Vec<PriorityClass>) -> Result<Self, SimulationError> {
+        let mut checks = vec![(
+            !classes.is_empty(),
+            "a fila com prioridades precisa de pelo menos uma classe".to_string(),
+        )];
+        for (index, class) in classes.iter().enumerate() {
+            checks.push((
+                class.arrival_rate > 0.0 && class.mean_service_time > 0.0,
+                format!(
+                    "a classe {} deve ter taxa de chegadas e tempo médio de serviço positivos",
+                    index + 1
+                ),
+            ));
+        }
+        if !classes.is_empty() {
+            let rho = classes
+                .iter()
+                .map(|class| class.arrival_rate * class.mean_service_time)
+                .sum();
+            checks.push(stable_utilization("com prioridades", rho));
+        }
+        check_model_parameters(checks)?;
+        Ok(Self { classes })
+    }
+
+    // Utilização total das classes
+    fn rho(&self) -> f64 {
+        self.utilization_up_to(self.classes.len())
+    }
+
+    // Soma das utilizações das count classes mais prioritárias
+    fn utilization_up_to(&self, count: usize) -> f64 {
+        self.classes[..count]
+            .iter()
+            .map(|class| class.arrival_rate * class.mean_service_time)
+            .sum()
+    }
+
+    // Taxa total de chegadas
+    fn arrival_rate(&self) -> f64 {
+        self.classes.iter().map(|class| class.arrival_rate).sum()
+    }
+
+    // Espera média da classe de índice class_index, começando da mais prioritária
+    pub fn class_mean_w(&self, class_index: usize) -> f64 {
+        let residual_work: f64 = self
+            .classes
+            .iter()
+            .map(|class| class.arrival_rate * class.second_moment / 2.0)
+            .sum();
+        residual_work
+            / ((1.0 - self.utilization_up_to(class_index))
+                * (1.0 - self.utilization_up_to(class_index + 1)))
+    }
+}
+
+impl AnalyticModel for NonPreemptivePriority {
+    fn name(&self) -> String {
+        format!(
+            "M/G/1 com {} prioridades não preemptivas",
+            self.classes.len()
+        )
+    }
+
+    fn mean_w(&self) -> f64 {
+        self.mean_nq() / self.arrival_rate()
+    }
+
+    fn variance_w(&self) -> Option<f64> {
+        None
+    }
+
+    // Pela lei de Little em cada classe, E[Nq] = Σ λ_k E[W_k]
+    fn mean_nq(&self) -> f64 {
+        self.classes
+            .iter()
+            .enumerate()
+            .map(|(class_index, class)| class.arrival_rate * self.class_mean_w(class_index))
+            .sum()
+    }
+
+    fn variance_nq(&self) -> Option<f64> {
+        None
+    }
+
+    fn mean_n(&self) -> f64 {
+        self.mean_nq() + self.rho()
+    }
+
+    fn waiting_probability(&self) -> f64 {
+        self.rho()
+    }
+}
@@ -0,0 +1,106 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Serialização dos checkpoints
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+// Arquivos e caminhos da biblioteca padrão
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+// Versão do formato dos checkpoints, incrementada sempre que o estado guardado mudar
+const CHECKPOINT_VERSION: u32 = 8;
+
+// Conteúdo de um arquivo de checkpoint: a versão do formato e o estado da execução
+#[derive(Serialize, Deserialize)]
+struct CheckpointFile<T> {
+    version: u32,
+    state: T,
+}
+
+// Escreve o estado de uma execução no arquivo de checkpoint. O estado é escrito num arquivo
+// temporário que depois substitui o anterior, para que uma interrupção no meio da escrita não
+// destrua o último checkpoint válido
+pub fn write_checkpoint<T: Serialize>(path: &Path, state: &T) -> Result<(), SimulationError> {
+    let temporary_path = path.with_extension("tmp");
+    let file = File::create(&temporary_path).map_err(SimulationError::io(&temporary_path))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(
+        &mut writer,
+        &CheckpointFile {
+            version: CHECKPOINT_VERSION,
+            state,
+        },
+    )
+    .map_err(io::Error::from)
+    .map_err(SimulationError::io(&temporary_path))?;
+    writer
+        .flush()
+        .map_err(SimulationError::io(&temporary_path))?;
+    fs::rename(&temporary_path, path).map_err(SimulationError::io(path))
+}
+
+// Lê o estado de uma execução de um arquivo de checkpoint
+pub fn read_checkpoint<T: DeserializeOwned>(path: &Path) -> Result<T, SimulationError> {
+    let invalid_file = |message: String| SimulationError::InvalidFile {
+        path: path.to_path_buf(),
+        message,
+    };
+    let file = File::open(path).map_err(SimulationError::io(path))?;
+    let checkpoint: CheckpointFile<T> = serde_json::from_reader(BufReader::new(file))
+        .map_err(|error| invalid_file(format!("checkpoint inválido: {}", error)))?;
+    if checkpoint.version != CHECKPOINT_VERSION {
+        return Err(invalid_file(format!(
+            "o checkpoint tem a versão {} do formato, mas esta versão do simulador lê a {}",
+            checkpoint.version, CHECKPOINT_VERSION
+        )));
+    }
+    Ok(checkpoint.state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::output_analysis::rounds::RoundsAnalysis;
+    use crate::simulator::output_analysis::OutputAnalysis;
+    use crate::simulator::{QueueMode, QueuePolicy, SimulationParameters};
+
+    #[test]
+    fn resumed_rounds_match_an_uninterrupted_run() {
+        let mut parameters =
+            SimulationParameters::new(0.7, 200, 10, QueuePolicy::LCFS, QueueMode::ForReal);
+        parameters.deferred_output = true;
+        parameters.parallel_queues = 2;
+        let mut uninterrupted = RoundsAnalysis::new(&parameters, 42).unwrap();
+        uninterrupted.extend(2000).unwrap();
+        uninterrupted.extend(1000).unwrap();
+
+        let path = std::env::temp_dir().join("simulador_ad_checkpoint_test.json");
+        let mut interrupted = RoundsAnalysis::new(&parameters, 42).unwrap();
+        interrupted.extend(2000).unwrap();
+        write_checkpoint(&path, &interrupted).unwrap();
+        let mut resumed: RoundsAnalysis = read_checkpoint(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        resumed.extend(1000).unwrap();
+
+        let expected = uninterrupted.estimates(0.95);
+        let estimates = resumed.estimates(0.95);
+        assert_eq!(resumed.clients(), uninterrupted.clients());
+        assert_eq!(estimates.means_n_t_x, expected.means_n_t_x);
+        assert_eq!(estimates.variances_n_t_x, expected.variances_n_t_x);
+        for ((_, estimate), (_, expected_estimate)) in
+            estimates.metrics().iter().zip(expected.metrics().iter())
+        {
+            assert_eq!(estimate.value, expected_estimate.value);
+        }
+    }
+
+    #[test]
+    fn other_format_versions_are_rejected() {
+        let path = std::env::temp_dir().join("simulador_ad_checkpoint_version_test.json");
+        fs::write(&path, r#"{"version": 0, "state": 1}"#).unwrap();
+        let result: Result<u32, SimulationError> = read_checkpoint(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.unwrap_err().to_string().contains("versão 0"));
+    }
+}
@@ -1,8 +1,15 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
 // Estrutura de dados HashMap da biblioteca padrão
 use std::collections::HashMap;
 
-// Struct que representa um freguês na fila, seu tempo de atendimento e seus possíveis eventos
+// Struct que representa um freguês na fila, seu identificador, seu tempo de atendimento e seus
+// possíveis eventos
+#[derive(Serialize, Deserialize)]
 pub struct Client {
+    id: usize,
     x: f64,
     start_event: HashMap<String, f64>,
     end_event: HashMap<String, f64>,
@@ -10,9 +17,10 @@ pub struct Client {
 }
 
 impl Client {
-    // Instancia um novo freguês, com o tempo de atendimento e HashMaps de eventos
-    pub fn new(x: f64, color: usize) -> Self {
+    // Instancia um novo freguês, com seu identificador, o tempo de atendimento e HashMaps de eventos
+    pub fn new(id: usize, x: f64, color: usize) -> Self {
         Self {
+            id,
             x,
             start_event: HashMap::new(),
             end_event: HashMap::new(),
@@ -20,6 +28,11 @@ impl Client {
         }
     }
 
+    // Getter do identificador, que é a ordem de chegada do freguês na fila
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     // Getter do tempo de atendimento
     pub fn x(&self) -> f64 {
         self.x
@@ -40,15 +53,29 @@ impl Client {
         self.end_event.insert(name.to_string(), leave_time);
     }
 
-    // Calcula o tempo total de algum evento
-    pub fn calculate_event_time(&self, name: &str) -> f64 {
-        assert!(
-            self.start_event.contains_key(name) && self.end_event.contains_key(name),
-            format!(
+    // Calcula o tempo total de algum evento, que deve ter sido iniciado e encerrado
+    pub fn calculate_event_time(&self, name: &str) -> Result<f64, SimulationError> {
+        match (self.start_event.get(name), self.end_event.get(name)) {
+            (Some(start), Some(end)) => Ok(end - start),
+            _ => Err(SimulationError::InconsistentState(format!(
                 "O evento {} deve ter sido iniciado e encerrado para calcular seu tempo",
                 name
-            )
-        );
-        self.end_event[name] - self.start_event[name]
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfinished_events_have_no_time() {
+        let mut client = Client::new(0, 1.0, 0);
+        client.register_start("W", 2.0);
+        assert!(client.calculate_event_time("W").is_err());
+        client.register_end("W", 3.5);
+        assert_eq!(client.calculate_event_time("W").unwrap(), 1.5);
+        assert!(client.calculate_event_time("X").is_err());
     }
 }
@@ -1,4 +1,5 @@
 // Struct para representar um intervalo de confiança
+#[derive(Debug, Copy, Clone)]
 pub struct ConfidenceInterval {
     lower_bound: f64,
     upper_bound: f64,
@@ -33,6 +34,10 @@ impl ConfidenceInterval {
     pub fn precision(&self) -> f64 {
         self.precision
     }
+    // Meia largura do IC, isto é, a maior distância aceita entre o centro e o valor real
+    pub fn half_width(&self) -> f64 {
+        (self.upper_bound - self.lower_bound) / 2.0
+    }
     // Verifica a convergência entre dois intervalos de confiança
     pub fn check_convergence(first: Self, second: Self) -> bool {
         first.lower_bound <= second.center
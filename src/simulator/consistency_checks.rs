@@ -0,0 +1,229 @@
+// Importando a trait dos modelos analíticos e a fila M/G/1 de Pollaczek-Khinchine
+use crate::simulator::analytic_models::{mg1::MG1, AnalyticModel};
+// Importando a representação das estimativas produzidas por um método de análise
+use crate::simulator::output_analysis::{Estimates, MetricEstimate};
+// Importando o quantil da T-Student
+use crate::simulator::statistical_distributions::student_t_quantile;
+// Folga relativa para erros numéricos de float, necessária no modo de corretude, onde os ICs têm
+// largura zero
+const NUMERICAL_TOLERANCE: f64 = 1e-9;
+
+// Resultado da verificação de uma relação que as estimativas precisam satisfazer
+pub struct ConsistencyCheck {
+    name: &'static str, // Relação verificada, como é impressa
+    observed: f64,      // Valor estimado diretamente pela simulação
+    expected: f64,      // Valor previsto pela relação a partir das outras estimativas
+    tolerance: f64,     // Maior diferença aceita, vinda das meias larguras dos ICs envolvidos
+}
+
+impl ConsistencyCheck {
+    // Getter da relação verificada
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    // Getter do valor estimado diretamente
+    pub fn observed(&self) -> f64 {
+        self.observed
+    }
+    // Getter do valor previsto pela relação
+    pub fn expected(&self) -> f64 {
+        self.expected
+    }
+    // Getter da tolerância
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    // Indica se a diferença entre os dois lados da relação está dentro da tolerância
+    pub fn passed(&self) -> bool {
+        (self.observed - self.expected).abs()
+            <= self.tolerance + NUMERICAL_TOLERANCE * f64::max(1.0, self.expected.abs())
+    }
+}
+
+// Verifica a consistência interna das estimativas:
+// - a lei de Little na fila, E[Nq] = λ E[W], e no sistema, E[N] = λ E[T];
+// - a utilização, P(N > 0) = λ E[X];
+// - a fórmula de Pollaczek-Khinchine, E[W] = λ E[X²] / (2 (1 - λ E[X])), com os momentos de X
+//   estimados na própria execução. Pela lei de conservação de Kleinrock ela vale para qualquer
+//   disciplina conservativa, não preemptiva e que não olha o tempo de serviço, então a mesma
+//   identidade é verificada em cada execução, FCFS ou LCFS, e a comparação direta entre as duas
+//   políticas fica com conservation_check, quando as duas são executadas com o mesmo ρ.
+//   Ela só vale com chegadas Poisson, então só é verificada quando poisson_arrivals é verdadeiro.
+// A taxa de chegadas λ é a observada na simulação, e não a configurada, de forma que as relações
+// valem qualquer que seja a carga. A utilização não tem IC próprio, então sua tolerância é a meia
+// largura do IC de λ E[X], com o nível de confiança dado: os tempos de serviço são independentes,
+// e o IC de E[X] vem da sua variância e da quantidade de fregueses clients. Como N = Nq + 1{N > 0}
+// e T = W + X, a tolerância do sistema é a soma das tolerâncias da fila e da utilização
+pub fn consistency_checks(
+    estimates: &Estimates,
+    clients: usize,
+    confidence_level: f64,
+    poisson_arrivals: bool,
+) -> Vec<ConsistencyCheck> {
+    let arrival_rate = estimates.arrival_rate;
+    let [mean_n, mean_t, mean_x] = estimates.means_n_t_x;
+    let mean_w = &estimates.mean_w;
+    let mean_nq = &estimates.mean_nq;
+    let w_half_width = mean_w.t_student.half_width();
+
+    let queue_tolerance = mean_nq.t_student.half_width() + arrival_rate * w_half_width;
+    let x_half_width = if clients > 1 {
+        student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, (clients - 1) as f64)
+            * (estimates.variances_n_t_x[2] / clients as f64).sqrt()
+    } else {
+        f64::INFINITY
+    };
+    let utilization_tolerance = arrival_rate * x_half_width;
+    let mut checks = vec![
+        ConsistencyCheck {
+            name: "E[Nq] = λ E[W]",
+            observed: mean_nq.value,
+            expected: arrival_rate * mean_w.value,
+            tolerance: queue_tolerance,
+        },
+        ConsistencyCheck {
+            name: "E[N] = λ E[T]",
+            observed: mean_n,
+            expected: arrival_rate * mean_t,
+            tolerance: queue_tolerance + utilization_tolerance,
+        },
+        ConsistencyCheck {
+            name: "P(N > 0) = λ E[X]",
+            observed: estimates.utilization,
+            expected: arrival_rate * mean_x,
+            tolerance: utilization_tolerance,
+        },
+    ];
+    if poisson_arrivals {
+        // Com λ E[X] >= 1 estimado não há regime estacionário, e a espera prevista é infinita
+        let second_moment_x = estimates.variances_n_t_x[2] + mean_x.powi(2);
+        checks.push(ConsistencyCheck {
+            name: "Pollaczek-Khinchine E[W] = λ E[X²] / (2 (1 - λ E[X]))",
+            observed: mean_w.value,
+            expected: MG1::new(arrival_rate, mean_x, second_moment_x, None)
+                .map_or(f64::INFINITY, |model| model.mean_w()),
+            tolerance: w_half_width,
+        });
+    }
+    checks
+}
+
+// Lei de conservação de Kleinrock entre políticas: numa fila com chegadas Poisson, toda disciplina
+// conservativa, não preemptiva e que não olha o tempo de serviço tem o mesmo E[W], então execuções
+// FCFS e LCFS com o mesmo ρ precisam concordar. As execuções são independentes, e a tolerância é a
+// soma das meias larguras dos dois ICs
+pub fn conservation_check(
+    fcfs_mean_w: &MetricEstimate,
+    lcfs_mean_w: &MetricEstimate,
+) -> ConsistencyCheck {
+    ConsistencyCheck {
+        name: "Kleinrock E[W] FCFS = E[W] LCFS",
+        observed: lcfs_mean_w.value,
+        expected: fcfs_mean_w.value,
+        tolerance: fcfs_mean_w.t_student.half_width() + lcfs_mean_w.t_student.half_width(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::confidence_interval::ConfidenceInterval;
+    use crate::simulator::output_analysis::MetricEstimate;
+    use crate::simulator::test_helpers::assert_close;
+
+    // Quantidade de fregueses das estimativas construídas à mão
+    const CLIENTS: usize = 10_000;
+
+    // Estimativa com um IC simétrico de meia largura dada
+    fn estimate(value: f64, half_width: f64) -> MetricEstimate {
+        MetricEstimate {
+            value,
+            t_student: ConfidenceInterval::new(value - half_width, value + half_width),
+            chi_square: None,
+        }
+    }
+
+    // Estimativas de uma M/M/1 com λ = 0.5 e E[X] = 1, que satisfazem todas as relações: E[W] = 1
+    // por Pollaczek-Khinchine, E[Nq] = λ E[W] = 0.5, E[T] = 2 e E[N] = λ E[T] = 1
+    fn mm1_estimates(mean_nq: f64) -> Estimates {
+        Estimates {
+            means_n_t_x: [1.0, 2.0, 1.0],
+            variances_n_t_x: [2.0, 4.0, 1.0],
+            arrival_rate: 0.5,
+            utilization: 0.5,
+            mean_w: estimate(1.0, 0.1),
+            variance_w: estimate(3.0, 0.3),
+            mean_nq: estimate(mean_nq, 0.05),
+            variance_nq: estimate(0.75, 0.1),
+            quantiles_w: vec![],
+            quantiles_t: vec![],
+        }
+    }
+
+    // Relações violadas, pelos nomes
+    fn violated(estimates: &Estimates) -> Vec<&'static str> {
+        consistency_checks(estimates, CLIENTS, 0.95, true)
+            .iter()
+            .filter(|check| !check.passed())
+            .map(|check| check.name())
+            .collect()
+    }
+
+    #[test]
+    fn consistent_estimates_pass_every_check() {
+        let checks = consistency_checks(&mm1_estimates(0.5), CLIENTS, 0.95, true);
+        assert_eq!(checks.len(), 4);
+        assert!(checks.iter().all(|check| check.passed()));
+        // Sem chegadas Poisson a fórmula de Pollaczek-Khinchine não é verificada
+        assert_eq!(
+            consistency_checks(&mm1_estimates(0.5), CLIENTS, 0.95, false).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn perturbations_beyond_the_tolerance_are_violations() {
+        // A tolerância da lei de Little na fila é 0.05 + λ 0.1 = 0.1
+        let checks = consistency_checks(&mm1_estimates(0.5), CLIENTS, 0.95, true);
+        assert_close(checks[0].tolerance(), 0.1);
+        assert!(violated(&mm1_estimates(0.59)).is_empty());
+        assert_eq!(violated(&mm1_estimates(0.62)), vec!["E[Nq] = λ E[W]"]);
+        assert_eq!(violated(&mm1_estimates(0.38)), vec!["E[Nq] = λ E[W]"]);
+        // Uma espera fora do IC viola Pollaczek-Khinchine, e a lei de Little com ela
+        let mut estimates = mm1_estimates(0.5);
+        estimates.mean_w = estimate(1.3, 0.1);
+        assert_eq!(
+            violated(&estimates),
+            vec![
+                "E[Nq] = λ E[W]",
+                "Pollaczek-Khinchine E[W] = λ E[X²] / (2 (1 - λ E[X]))"
+            ]
+        );
+    }
+
+    #[test]
+    fn utilization_tolerance_comes_from_the_service_time_interval() {
+        // Com V(X) = 1 e 10000 fregueses o IC de E[X] tem meia largura t 0.01, e o de λ E[X] a
+        // metade disso
+        let checks = consistency_checks(&mm1_estimates(0.5), CLIENTS, 0.95, true);
+        let utilization_tolerance = 0.5 * student_t_quantile(0.975, 9999.0) * 0.01;
+        assert_close(checks[2].tolerance(), utilization_tolerance);
+        assert_close(checks[1].tolerance(), 0.1 + utilization_tolerance);
+        let mut estimates = mm1_estimates(0.5);
+        estimates.utilization = 0.515;
+        assert_eq!(violated(&estimates), vec!["P(N > 0) = λ E[X]"]);
+        // Com mais fregueses a tolerância diminui, e sem dois fregueses não há IC
+        let more_clients = consistency_checks(&estimates, 4 * CLIENTS, 0.95, true);
+        assert!(more_clients[2].tolerance() < utilization_tolerance / 1.9);
+        assert!(consistency_checks(&estimates, 1, 0.95, true)[2].passed());
+    }
+
+    #[test]
+    fn conservation_tolerates_the_sum_of_the_half_widths() {
+        let check = conservation_check(&estimate(1.0, 0.1), &estimate(1.25, 0.2));
+        assert_close(check.tolerance(), 0.3);
+        assert!(check.passed());
+        assert!(!conservation_check(&estimate(1.0, 0.1), &estimate(1.35, 0.2)).passed());
+    }
+}
@@ -0,0 +1,359 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando o freguês e os observadores da fila, que alimentam o registro de eventos
+use crate::simulator::client::Client;
+use crate::simulator::queue_observer::{ObserverFactory, QueueObserver};
+// Serialização dos registros em JSON Lines
+use serde::{Deserialize, Serialize};
+
+// Arquivos, caminhos e estruturas compartilhadas entre threads da biblioteca padrão
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+// Cabeçalho do registro de eventos em .csv, com as colunas na ordem de TraceRecord
+const CSV_HEADER: &str = "queue,time,event,round,client,queue_length,in_service";
+
+// Formato do arquivo de eventos, escolhido pela extensão do arquivo
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TraceFormat {
+    Csv,
+    JsonLines,
+}
+
+impl TraceFormat {
+    // Formato correspondente à extensão do arquivo, .csv ou .jsonl
+    fn from_path(path: &Path) -> Result<Self, SimulationError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Ok(TraceFormat::Csv),
+            Some("jsonl") => Ok(TraceFormat::JsonLines),
+            _ => Err(SimulationError::InvalidFile {
+                path: path.to_path_buf(),
+                message: "o registro de eventos deve ter extensão .csv ou .jsonl".to_string(),
+            }),
+        }
+    }
+}
+
+// Tipo de um evento registrado. Além dos eventos dos observadores, o início de cada rodada é
+// registrado junto com o fim da anterior ou da fase transiente, para que o registro filtrado por
+// rodada tenha o instante e o estado em que cada rodada começou
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceEvent {
+    Arrival,
+    ServiceStart,
+    Departure,
+    RoundStart,
+    RoundEnd,
+    WarmupEnd,
+}
+
+impl TraceEvent {
+    // Nome do evento, como é escrito no registro
+    fn name(self) -> &'static str {
+        match self {
+            TraceEvent::Arrival => "arrival",
+            TraceEvent::ServiceStart => "service_start",
+            TraceEvent::Departure => "departure",
+            TraceEvent::RoundStart => "round_start",
+            TraceEvent::RoundEnd => "round_end",
+            TraceEvent::WarmupEnd => "warmup_end",
+        }
+    }
+}
+
+impl FromStr for TraceEvent {
+    type Err = String;
+
+    // Converte o nome do evento, como é escrito no registro, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "arrival" => Ok(TraceEvent::Arrival),
+            "service_start" => Ok(TraceEvent::ServiceStart),
+            "departure" => Ok(TraceEvent::Departure),
+            "round_start" => Ok(TraceEvent::RoundStart),
+            "round_end" => Ok(TraceEvent::RoundEnd),
+            "warmup_end" => Ok(TraceEvent::WarmupEnd),
+            _ => Err(format!("Evento desconhecido: {}", name)),
+        }
+    }
+}
+
+// Um evento processado por uma fila, com o estado da fila logo depois dele
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    pub queue: u64,            // Semente da fila, que distingue as filas de uma execução
+    pub time: f64,             // Tempo atual da fila no evento
+    pub event: TraceEvent,     // Tipo do evento
+    pub round: usize,          // Rodada do evento, 0 na fase transiente
+    pub client: Option<usize>, // Freguês do evento, se houver
+    pub queue_length: usize,   // Fregueses na fila de espera
+    pub in_service: Option<usize>, // Freguês em atendimento, se houver
+}
+
+impl TraceRecord {
+    // Linha do registro em .csv, sem a quebra de linha. Os tempos são escritos com todos os
+    // dígitos necessários para serem lidos de volta sem perda
+    fn csv_line(&self) -> String {
+        let optional = |value: Option<usize>| value.map_or(String::new(), |id| id.to_string());
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.queue,
+            self.time,
+            self.event.name(),
+            self.round,
+            optional(self.client),
+            self.queue_length,
+            optional(self.in_service)
+        )
+    }
+
+    // Lê um registro de uma linha do .csv
+    fn from_csv_line(line: &str) -> Result<Self, String> {
+        let columns: Vec<&str> = line.split(',').collect();
+        if columns.len() != 7 {
+            return Err(format!(
+                "esperadas 7 colunas, encontradas {}",
+                columns.len()
+            ));
+        }
+        let number = |column: &str| column.parse::<usize>().map_err(|error| error.to_string());
+        let optional = |column: &str| {
+            if column.is_empty() {
+                Ok(None)
+            } else {
+                number(column).map(Some)
+            }
+        };
+        Ok(Self {
+            queue: columns[0].parse().map_err(|error| format!("{}", error))?,
+            time: columns[1].parse().map_err(|error| format!("{}", error))?,
+            event: columns[2].parse()?,
+            round: number(columns[3])?,
+            client: optional(columns[4])?,
+            queue_length: number(columns[5])?,
+            in_service: optional(columns[6])?,
+        })
+    }
+}
+
+// Filtro dos eventos registrados, por janela de tempo e por intervalo de rodadas, ambos fechados.
+// Sem filtros todos os eventos são registrados
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceFilter {
+    pub time_window: Option<(f64, f64)>, // Primeiro e último instantes registrados
+    pub rounds: Option<(usize, usize)>,  // Primeira e última rodadas registradas
+}
+
+impl TraceFilter {
+    // Indica se o evento passa pelo filtro
+    fn accepts(&self, record: &TraceRecord) -> bool {
+        self.time_window
+            .is_none_or(|(start, end)| start <= record.time && record.time <= end)
+            && self
+                .rounds
+                .is_none_or(|(first, last)| first <= record.round && record.round <= last)
+    }
+
+    // Valida os intervalos do filtro, listando todos os problemas encontrados
+    fn validate(&self) -> Result<(), SimulationError> {
+        let mut problems = vec![];
+        if let Some((start, end)) = self.time_window {
+            if start.is_nan() || end.is_nan() || start > end {
+                problems.push(format!(
+                    "a janela de tempo do registro de eventos [{}, {}] é vazia",
+                    start, end
+                ));
+            }
+        }
+        if let Some((first, last)) = self.rounds {
+            if first > last {
+                problems.push(format!(
+                    "o intervalo de rodadas do registro de eventos [{}, {}] é vazio",
+                    first, last
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SimulationError::InvalidParameters(problems))
+        }
+    }
+}
+
+// Saída compartilhada pelos observadores de todas as filas de uma execução, com o primeiro erro de
+// escrita, já que os observadores não podem devolver erros
+struct TraceOutput {
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+// Registro dos eventos processados pelas filas de uma execução, escrito em .csv ou em JSON Lines
+// conforme a extensão do arquivo. Cada fila é observada por um TraceWriter criado pela fábrica,
+// e todos escrevem no mesmo arquivo, então as linhas de filas paralelas se intercalam
+pub struct EventTrace {
+    path: PathBuf,                   // Arquivo do registro
+    format: TraceFormat,             // Formato do arquivo
+    filter: TraceFilter,             // Filtro dos eventos registrados
+    output: Arc<Mutex<TraceOutput>>, // Saída compartilhada pelos observadores
+}
+
+impl EventTrace {
+    // Cria o arquivo do registro, substituindo um anterior, e escreve o cabeçalho do .csv
+    pub fn create(path: &Path, filter: TraceFilter) -> Result<Self, SimulationError> {
+        filter.validate()?;
+        let format = TraceFormat::from_path(path)?;
+        let file = File::create(path).map_err(SimulationError::io(path))?;
+        let mut writer = BufWriter::new(file);
+        if format == TraceFormat::Csv {
+            writeln!(writer, "{}", CSV_HEADER).map_err(SimulationError::io(path))?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            filter,
+            output: Arc::new(Mutex::new(TraceOutput {
+                writer,
+                error: None,
+            })),
+        })
+    }
+
+    // Fábrica dos observadores que registram os eventos, para os parâmetros de uma execução
+    pub fn observer_factory(&self) -> ObserverFactory {
+        let format = self.format;
+        let filter = self.filter;
+        let output = Arc::clone(&self.output);
+        ObserverFactory::new(move |seed| {
+            Box::new(TraceWriter {
+                queue: seed,
+                format,
+                filter,
+                output: Arc::clone(&output),
+                round: 0,
+                warmed_up: false,
+                queue_length: 0,
+                in_service: None,
+            })
+        })
+    }
+
+    // Escreve o que ainda está no buffer e devolve o primeiro erro de escrita, se houve algum
+    pub fn finish(&self) -> Result<(), SimulationError> {
+        let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = match output.error.take() {
+            Some(error) => Err(error),
+            None => output.writer.flush(),
+        };
+        result.map_err(SimulationError::io(&self.path))
+    }
+}
+
+// Observador que registra os eventos de uma fila. Ele reconstrói o tamanho da fila de espera e o
+// freguês em atendimento a partir dos eventos, então deve observar a fila desde que ela foi criada
+struct TraceWriter {
+    queue: u64,                      // Semente da fila observada
+    format: TraceFormat,             // Formato do arquivo
+    filter: TraceFilter,             // Filtro dos eventos registrados
+    output: Arc<Mutex<TraceOutput>>, // Saída compartilhada com os observadores das outras filas
+    round: usize,                    // Rodada atual, 0 na fase transiente
+    warmed_up: bool,                 // Indica se a fase transiente já terminou
+    queue_length: usize,             // Fregueses na fila de espera
+    in_service: Option<usize>,       // Freguês em atendimento, se houver
+}
+
+impl TraceWriter {
+    // Registra um evento com o estado atual da fila, caso ele passe pelo filtro
+    fn record(&mut self, time: f64, event: TraceEvent, client: Option<usize>) {
+        let record = TraceRecord {
+            queue: self.queue,
+            time,
+            event,
+            round: self.round,
+            client,
+            queue_length: self.queue_length,
+            in_service: self.in_service,
+        };
+        if !self.filter.accepts(&record) {
+            return;
+        }
+        let line = match self.format {
+            TraceFormat::Csv => record.csv_line(),
+            TraceFormat::JsonLines => {
+                serde_json::to_string(&record).expect("Um registro sempre pode ser serializado")
+            }
+        };
+        let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+        if output.error.is_none() {
+            if let Err(error) = writeln!(output.writer, "{}", line) {
+                output.error = Some(error);
+            }
+        }
+    }
+
+    // Começa a próxima rodada, registrando seu início no mesmo instante do fim da anterior
+    fn start_round(&mut self, time: f64) {
+        self.round += 1;
+        self.record(time, TraceEvent::RoundStart, None);
+    }
+}
+
+impl QueueObserver for TraceWriter {
+    fn on_arrival(&mut self, client: &Client, time: f64) {
+        self.queue_length += 1;
+        self.record(time, TraceEvent::Arrival, Some(client.id()));
+    }
+
+    fn on_service_start(&mut self, client: &Client, time: f64) {
+        self.queue_length -= 1;
+        self.in_service = Some(client.id());
+        self.record(time, TraceEvent::ServiceStart, Some(client.id()));
+    }
+
+    fn on_departure(&mut self, client: &Client, time: f64) {
+        self.in_service = None;
+        self.record(time, TraceEvent::Departure, Some(client.id()));
+    }
+
+    fn on_round_end(&mut self, _round: usize, time: f64) {
+        // As rodadas piloto dos procedimentos de aquecimento fazem parte da fase transiente
+        self.record(time, TraceEvent::RoundEnd, None);
+        if self.warmed_up {
+            self.start_round(time);
+        }
+    }
+
+    fn on_warmup_end(&mut self, _transient_phase_size: usize, time: f64) {
+        self.record(time, TraceEvent::WarmupEnd, None);
+        self.warmed_up = true;
+        self.start_round(time);
+    }
+}
+
+// Lê todos os registros de um arquivo de eventos, em .csv ou em JSON Lines
+pub fn read_trace(path: &Path) -> Result<Vec<TraceRecord>, SimulationError> {
+    let format = TraceFormat::from_path(path)?;
+    let file = File::open(path).map_err(SimulationError::io(path))?;
+    let mut records = vec![];
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(SimulationError::io(path))?;
+        if line.is_empty() || (format == TraceFormat::Csv && index == 0 && line == CSV_HEADER) {
+            continue;
+        }
+        let record = match format {
+            TraceFormat::Csv => TraceRecord::from_csv_line(&line),
+            TraceFormat::JsonLines => {
+                serde_json::from_str(&line).map_err(|error| error.to_string())
+            }
+        };
+        records.push(record.map_err(|message| SimulationError::InvalidFile {
+            path: path.to_path_buf(),
+            message: format!("linha {}: {}", index + 1, message),
+        })?);
+    }
+    Ok(records)
+}
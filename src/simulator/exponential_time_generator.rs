@@ -1,26 +1,35 @@
 // Importando os elementos da biblioteca de números aleatórios
 use rand::prelude::*;
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
 
-// Struct para armazenar o objeto gerador de números aleatórios
+// Importando o gerador de números aleatórios configurável
+use crate::simulator::random_number_generator::{GeneratorKind, RandomNumberGenerator};
+
+// Struct para armazenar o objeto gerador de números aleatórios. É serializado com o estado interno
+// do gerador, de forma que a retomada continua a sequência sem repetir os sorteios
+#[derive(Serialize, Deserialize)]
 pub struct ExponentialTime {
-    random_number_generator: StdRng,
+    random_number_generator: RandomNumberGenerator,
 }
 
 impl ExponentialTime {
-    // Instancia um novo gerador de amostras exponenciais
-    pub fn new(seed: u64) -> Self {
+    // Instancia um novo gerador de amostras exponenciais com o gerador de números escolhido
+    pub fn new(seed: u64, generator_kind: GeneratorKind) -> Self {
         Self {
-            random_number_generator: StdRng::seed_from_u64(seed),
+            random_number_generator: RandomNumberGenerator::new(generator_kind, seed),
         }
     }
 
+    // Sorteia um número aleatório entre (0, 1), usando o menor float positivo para garantir que
+    // não teremos ln(0)
+    fn draw(&mut self) -> f64 {
+        self.random_number_generator
+            .gen_range(f64::MIN_POSITIVE, 1.0f64)
+    }
+
     // Calcula uma amostra exponencial a partir de um número aleatório entre (0, 1) gerado
     pub fn get(&mut self, lambda: f64) -> f64 {
-        // Usando o menor float positivo para garantir que não teremos ln(0)
-        -self
-            .random_number_generator
-            .gen_range(std::f64::MIN_POSITIVE, 1.0f64)
-            .ln()
-            / lambda
+        -self.draw().ln() / lambda
     }
 }
@@ -0,0 +1,192 @@
+// Importando o gerador configurável e o trait que permite gerar floats uniformes
+use crate::simulator::random_number_generator::{GeneratorKind, RandomNumberGenerator};
+use crate::simulator::statistical_distributions::normal_quantile;
+use rand::Rng;
+
+// Nível de significância de cada teste individual, todos os testes abaixo são reduzidos a
+// uma estatística aproximadamente Normal padrão sob a hipótese de que o gerador é bom
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+// Quantidade de classes usadas no teste Chi² de uniformidade
+const CHI_SQUARE_BINS: usize = 100;
+
+// Struct com o resultado de um teste estatístico aplicado ao gerador
+pub struct QualityTestResult {
+    name: &'static str,
+    z_score: f64,
+}
+
+impl QualityTestResult {
+    // Getter do nome do teste
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    // Getter da estatística normalizada
+    pub fn z_score(&self) -> f64 {
+        self.z_score
+    }
+    // O teste é bilateral, rejeitamos tanto valores bons demais quanto ruins demais
+    pub fn passed(&self) -> bool {
+        self.z_score.abs() <= normal_quantile(1.0 - SIGNIFICANCE_LEVEL / 2.0)
+    }
+}
+
+// Mesmo um gerador perfeito falha em 5% das vezes em cada teste, então a bateria é repetida com
+// sementes diferentes e o gerador só é rejeitado se falhar mais vezes do que o esperado.
+// Retorna a maior quantidade de falhas que uma Binomial(repetições, 0.05) atinge com 99% de chance
+pub fn maximum_expected_failures(repetitions: usize) -> usize {
+    let mut probability = (1.0 - SIGNIFICANCE_LEVEL).powi(repetitions as i32);
+    let mut cumulative_probability = probability;
+    let mut failures = 0;
+    while cumulative_probability < 0.99 && failures < repetitions {
+        // Recorrência da função de probabilidade da Binomial: P(k + 1) a partir de P(k)
+        probability *= (repetitions - failures) as f64 / (failures + 1) as f64 * SIGNIFICANCE_LEVEL
+            / (1.0 - SIGNIFICANCE_LEVEL);
+        cumulative_probability += probability;
+        failures += 1;
+    }
+    failures
+}
+
+// Executa a bateria de testes para um gerador, com a semente e a quantidade de amostras pedidas
+pub fn run_quality_tests(
+    kind: GeneratorKind,
+    seed: u64,
+    sample_size: usize,
+) -> Vec<QualityTestResult> {
+    let mut generator = RandomNumberGenerator::new(kind, seed);
+    let values: Vec<f64> = (0..sample_size).map(|_| generator.gen::<f64>()).collect();
+    quality_tests(&values)
+}
+
+// Aplica os testes a uma sequência já gerada, supostamente Uniforme(0, 1) e independente
+fn quality_tests(values: &[f64]) -> Vec<QualityTestResult> {
+    vec![
+        chi_square_uniformity_test(values),
+        serial_correlation_test(values),
+        runs_up_and_down_test(values),
+    ]
+}
+
+// Teste Chi² de uniformidade, dividindo [0, 1) em classes de mesmo tamanho.
+// A estatística é aproximada por uma Normal usando a transformação de Wilson-Hilferty
+fn chi_square_uniformity_test(values: &[f64]) -> QualityTestResult {
+    let mut observed = [0usize; CHI_SQUARE_BINS];
+    for &value in values {
+        let bin = ((value * CHI_SQUARE_BINS as f64) as usize).min(CHI_SQUARE_BINS - 1);
+        observed[bin] += 1;
+    }
+    let expected = values.len() as f64 / CHI_SQUARE_BINS as f64;
+    let statistic = observed.iter().fold(0.0, |sum, &count| {
+        sum + (count as f64 - expected).powi(2) / expected
+    });
+    let degrees_of_freedom = (CHI_SQUARE_BINS - 1) as f64;
+    let wilson_hilferty_variance = 2.0 / (9.0 * degrees_of_freedom);
+    let z_score = ((statistic / degrees_of_freedom).cbrt() - (1.0 - wilson_hilferty_variance))
+        / wilson_hilferty_variance.sqrt();
+    QualityTestResult {
+        name: "Chi-Square uniformity",
+        z_score,
+    }
+}
+
+// Teste de correlação serial com lag 1, sob independência r * sqrt(n) é aproximadamente N(0, 1)
+fn serial_correlation_test(values: &[f64]) -> QualityTestResult {
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let denominator = values
+        .iter()
+        .fold(0.0, |sum, &value| sum + (value - mean).powi(2));
+    let numerator = values
+        .windows(2)
+        .fold(0.0, |sum, pair| sum + (pair[0] - mean) * (pair[1] - mean));
+    let statistic = numerator / denominator;
+    QualityTestResult {
+        name: "Serial correlation (lag 1)",
+        z_score: statistic * count.sqrt(),
+    }
+}
+
+// Teste de corridas para cima e para baixo, contando as sequências monótonas da amostra.
+// Sob independência a quantidade de corridas tem média (2n - 1) / 3 e variância (16n - 29) / 90
+fn runs_up_and_down_test(values: &[f64]) -> QualityTestResult {
+    let count = values.len() as f64;
+    let mut runs = 1usize;
+    let mut previous_direction = None;
+    for pair in values.windows(2) {
+        let direction = pair[1] > pair[0];
+        if let Some(previous) = previous_direction {
+            if previous != direction {
+                runs += 1;
+            }
+        }
+        previous_direction = Some(direction);
+    }
+    let expected = (2.0 * count - 1.0) / 3.0;
+    let variance = (16.0 * count - 29.0) / 90.0;
+    QualityTestResult {
+        name: "Runs up and down",
+        z_score: (runs as f64 - expected) / variance.sqrt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SIZE: usize = 10_000;
+
+    #[test]
+    fn maximum_expected_failures_is_the_binomial_quantile() {
+        // P(Binomial(20, 0.05) <= 3) = 0.984 e P(Binomial(20, 0.05) <= 4) = 0.997
+        assert_eq!(maximum_expected_failures(20), 4);
+        assert_eq!(maximum_expected_failures(1), 1);
+        assert_eq!(maximum_expected_failures(0), 0);
+    }
+
+    #[test]
+    fn every_backend_passes_the_battery() {
+        let repetitions = 20;
+        for &kind in GeneratorKind::ALL.iter() {
+            let failures = (0..repetitions as u64)
+                .flat_map(|seed| run_quality_tests(kind, seed, SAMPLE_SIZE))
+                .filter(|result| !result.passed())
+                .count();
+            // Cada repetição aplica três testes
+            assert!(
+                failures <= 3 * maximum_expected_failures(repetitions),
+                "{:?} falhou {} vezes",
+                kind,
+                failures
+            );
+        }
+    }
+
+    #[test]
+    fn the_battery_rejects_bad_generators() {
+        // Sequência de Weyl: uniforme demais e com passos correlacionados
+        let weyl: Vec<f64> = (0..SAMPLE_SIZE)
+            .map(|index| (index as f64 * 0.618_033_988_749_895).fract())
+            .collect();
+        let results = quality_tests(&weyl);
+        assert!(!results[0].passed());
+        assert!(!results[2].passed());
+
+        // Gerador "lento", cada valor é vizinho do anterior
+        let mut generator = RandomNumberGenerator::new(GeneratorKind::Xoshiro, 42);
+        let mut value: f64 = 0.5;
+        let sticky: Vec<f64> = (0..SAMPLE_SIZE)
+            .map(|_| {
+                value = (value + 0.05 * (generator.gen::<f64>() - 0.5)).rem_euclid(1.0);
+                value
+            })
+            .collect();
+        assert!(!quality_tests(&sticky)[1].passed());
+
+        // Valores concentrados perto de 0
+        let skewed: Vec<f64> = (0..SAMPLE_SIZE)
+            .map(|_| generator.gen::<f64>().powi(2))
+            .collect();
+        assert!(!quality_tests(&skewed)[0].passed());
+    }
+}
@@ -1,405 +1,923 @@
+// Módulo com os modelos analíticos usados na validação dos resultados
+mod analytic_models;
+// Módulo com a escrita e a leitura dos checkpoints das execuções longas
+mod checkpoint;
 // Módulo com a representação de um freguês
 mod client;
 // Módulo com a representação de um intervalo de confiança
 mod confidence_interval;
+// Módulo com as verificações de consistência interna das estimativas
+mod consistency_checks;
+// Módulo com o registro dos eventos processados pelas filas
+mod event_trace;
 // Módulo com o gerador de amostras exponenciais
 mod exponential_time_generator;
+// Módulo com a bateria de testes estatísticos de qualidade dos geradores de números aleatórios
+mod generator_quality_tests;
+// Módulo com os métodos de análise de saída usados para construir os intervalos de confiança
+mod output_analysis;
+// Módulo com a execução em paralelo das simulações de um sweep
+mod parallel_sweep;
+// Módulo com os gráficos dos resultados, da convergência das rodadas e do procedimento de Welch
+mod plots;
 // Módulo com a fila M/M/1
 mod queue;
+// Módulo com a trait dos observadores dos eventos da fila
+mod queue_observer;
+// Módulo com os geradores de números aleatórios disponíveis
+mod random_number_generator;
+// Módulo com o relatório textual de uma execução do simulador
+mod report;
+// Módulo com os resultados de uma execução e sua escrita em .csv, JSON ou JSON Lines
+mod results_writer;
 // Módulo com os acumuladores de amostras de variáveis aleatórias e processos estocásticos
 mod sample_accumulators;
+// Módulo com o builder dos parâmetros de uma execução, usado por quem embute o simulador
+mod simulation_builder;
+// Módulo com os parâmetros de uma execução do simulador
+mod simulation_parameters;
+// Módulo com o resultado estruturado de uma execução do simulador
+mod simulation_report;
+// Módulo com as funções de distribuição da Normal, T-Student e Chi² e suas inversas
+mod statistical_distributions;
 // Módulo com a exportação dos dados encontrados para um arquivo .csv para análise posterior
 mod statistics_output_files;
-
-// Biblioteca externa que renderiza uma progress bar no terminal conforme as rodadas acontecem
-use indicatif::{ProgressBar, ProgressStyle};
-// Estrutura de dados HashMap da biblioteca padrão do Rust
-use std::collections::HashMap;
-// Funcionalidade de temporização da biblioteca padrão
+// Módulo com o desenho de gráficos de linhas e pontos em SVG
+mod svg_chart;
+// Módulo com as asserções compartilhadas pelos testes
+#[cfg(test)]
+mod test_helpers;
+// Módulo com a releitura do registro de eventos, que confere os acumuladores
+mod trace_replay;
+// Módulo com os procedimentos de detecção do fim da fase transiente
+mod warmup_detection;
+
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Caminhos de arquivos e funcionalidade de temporização da biblioteca padrão
+use std::path::Path;
 use std::time::{Instant, SystemTime};
 
-// Importando a representação do nosso intervalo de confiança
-use confidence_interval::ConfidenceInterval;
-// Importando a representação do nossa fila M/M/1 e algumas constantes
-use queue::{Queue, N, NQ, T, W, X};
-// Importando a representação do nosso acumulador de amostras de variáveis aleatórias
-use sample_accumulators::sample::Sample;
-// Importando a função que escreve os dados coletados pelo simulador num arquivo .csv
-use statistics_output_files::write_csv_file;
+// Importando o modelo analítico da fila simulada
+use analytic_models::analytic_model;
+// Importando as verificações da lei de Little, da utilização e de Pollaczek-Khinchine
+use consistency_checks::consistency_checks;
+// Importando a bateria de testes dos geradores de números aleatórios
+use generator_quality_tests::{maximum_expected_failures, run_quality_tests};
+// Importando os métodos de análise de saída e a representação das estimativas
+use output_analysis::sequential::run_sequentially;
+use output_analysis::distributions::mm1_occupancy_quantile;
+use output_analysis::quantiles::{
+    mm1_fcfs_sojourn_time_quantile, mm1_fcfs_waiting_time_quantile, QUANTILE_PROBABILITIES,
+};
+use output_analysis::rounds::RoundsAnalysis;
+use output_analysis::{new_analysis, OutputAnalysis};
+// Importando o gráfico da convergência das médias das rodadas
+use plots::plot_convergence;
+// Importando o relatório textual de uma execução
+use report::Report;
+// Importando a escrita dos resultados de uma execução
+use results_writer::{check_results_file, write_results};
+// Importando as funções que escrevem os dados coletados pelo simulador em arquivos .csv
+use statistics_output_files::{
+    side_file_path, write_autocorrelation_csv_file, write_distributions_csv_file,
+};
 
 // Exportando o enum da nossa política de fila, pra ser usado por quem chamar o simulador
-pub(crate) use queue::QueuePolicy;
+pub use queue::QueuePolicy;
 // Exportando o enum do nosso modo de simulação de fila, pra ser usado por quem chamar o simulador
-pub(crate) use queue::QueueMode;
-
-// Função interna que constrói um HashMap para coleta de amostras das métricas N, T e X
-fn statistics_hash_map(rounds_count: usize) -> HashMap<String, Sample> {
-    let mut statistics = HashMap::new();
-    statistics.insert(N.to_string(), Sample::new(rounds_count));
-    statistics.insert(T.to_string(), Sample::new(rounds_count));
-    statistics.insert(X.to_string(), Sample::new(rounds_count));
-    statistics
+pub use queue::QueueMode;
+// Exportando a fila, o freguês e os observadores, para acompanhar os eventos de uma simulação
+pub use client::Client;
+pub use queue::Queue;
+pub use queue_observer::{ObserverFactory, QueueObserver};
+// Exportando o enum dos geradores de números aleatórios disponíveis
+pub use random_number_generator::GeneratorKind;
+// Exportando o enum dos métodos de análise de saída disponíveis
+pub use output_analysis::AnalysisMethod;
+// Exportando o enum dos procedimentos de detecção da fase transiente disponíveis
+pub use warmup_detection::WarmupMethod;
+// Exportando o enum dos estimadores de quantis disponíveis
+pub use output_analysis::quantiles::QuantileEstimator;
+// Exportando a struct com os parâmetros de uma execução do simulador e seu builder
+pub use simulation_builder::SimulationBuilder;
+pub use simulation_parameters::SimulationParameters;
+// Exportando o resultado estruturado de uma execução e as representações que ele usa
+pub use confidence_interval::ConfidenceInterval;
+pub use output_analysis::quantiles::QuantileEstimate;
+pub use output_analysis::sequential::StopReason;
+pub use output_analysis::{Estimates, MetricEstimate};
+pub use simulation_report::{AnalyticValues, SimulationReport};
+// Exportando a execução em paralelo das simulações de um sweep
+pub use parallel_sweep::run_sweep;
+// Exportando o registro de eventos e sua releitura
+pub use event_trace::{read_trace, EventTrace, TraceEvent, TraceFilter, TraceRecord};
+pub use trace_replay::{print_trace_replay, replay_trace, ReplayedRound, TraceReplay};
+// Exportando os resultados de uma execução, seus formatos e modos de escrita
+pub use results_writer::{
+    read_results, ResultValue, ResultsFormat, ResultsRecord, WriteMode, RESULTS_SCHEMA_VERSION,
+};
+// Exportando os gráficos dos resultados e o desenho de gráficos em SVG
+pub use plots::plot_results;
+pub use svg_chart::{Chart, Series, SeriesStyle};
+// Exportando a trait e os modelos analíticos, para que possam ser consultados sem simular
+pub use analytic_models::kingman::Kingman;
+pub use analytic_models::mg1::MG1;
+pub use analytic_models::mm1::MM1;
+pub use analytic_models::mm1k::MM1K;
+pub use analytic_models::mmc::MMc;
+pub use analytic_models::priority::{NonPreemptivePriority, PriorityClass};
+pub use analytic_models::{AnalyticModel, AnalyticModelKind};
+
+// Instancia uma fila vazia de acordo com os parâmetros e a semente
+pub(crate) fn new_queue(parameters: &SimulationParameters, seed: u64) -> Queue {
+    if parameters.queue_mode == QueueMode::ForReal {
+        Queue::new(
+            parameters.rho,
+            parameters.queue_policy,
+            seed,
+            parameters.generator_kind,
+        )
+    } else {
+        Queue::check_correctness(parameters.queue_policy)
+    }
 }
 
-// Função que executa o simulador
-pub fn simulator(
-    rho: f64,                  // Taxa de utilização do sistema
-    round_size: usize,         // Quantidade de fregueses por rodada
-    rounds_count: usize,       // Quantidade de rodadas
-    queue_policy: QueuePolicy, // Política de atendimento FCFS ou LCFS
-    queue_mode: QueueMode,     // Modo de simulação
-) {
-    // Semente a ser utilizada pelo gerador de amostras exponenciais
-    let seed = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("Erro ao obter o tempo do sistema")
-        .as_secs();
-
-    let now = Instant::now();
+// Instancia uma fila vazia de acordo com os parâmetros e a semente, com o observador criado pela
+// fábrica dos parâmetros, se houver
+pub(crate) fn observed_queue(parameters: &SimulationParameters, seed: u64) -> Queue {
+    let mut queue = new_queue(parameters, seed);
+    if let Some(observer_factory) = &parameters.observer_factory {
+        queue.add_observer(observer_factory.create(seed));
+    }
+    queue
+}
 
-    // HashMap para coletar médias amostrais de N, T e X por rodada
-    let mut means_statistics = statistics_hash_map(rounds_count);
-    // HashMap para coletar variâncias amostrais de N, T e X por rodada
-    let mut variances_statistics = statistics_hash_map(rounds_count);
-
-    // Acumulador de médias amostrais de W
-    let mut w_mean_statistics = Sample::new(rounds_count);
-    // Acumulador de variâncias amostrais de W
-    let mut w_variance_statistics = Sample::new(rounds_count);
-    // Acumulador de médias amostrais de Nq
-    let mut nq_mean_statistics = Sample::new(rounds_count);
-    // Acumulador de variâncias amostrais de Nq
-    let mut nq_variance_statistics = Sample::new(rounds_count);
-
-    // Objeto que representa nossa fila M/M/1
-    let mut queue = if queue_mode == QueueMode::ForReal {
-        Queue::new(rho, queue_policy, seed)
+// Instancia uma fila observada de acordo com os parâmetros e a semente, já executando sua fase
+// transiente, ou descartando o truncamento encontrado para toda a execução, quando dado por
+// execution_truncation. Retorna a fila e o tamanho da fase transiente
+pub(crate) fn warmed_up_queue(
+    parameters: &SimulationParameters,
+    seed: u64,
+    execution_truncation: Option<usize>,
+) -> Result<(Queue, usize), SimulationError> {
+    let mut queue = observed_queue(parameters, seed);
+    if parameters.queue_mode == QueueMode::ForReal {
+        let transient_phase_size =
+            warmup_detection::warm_up(&mut queue, parameters, seed, execution_truncation)?;
+        Ok((queue, transient_phase_size))
     } else {
-        Queue::check_correctness(queue_policy)
-    };
+        // O modo de corretude não tem fase transiente, mas os observadores são avisados do seu fim
+        queue.end_warmup(0);
+        Ok((queue, 0))
+    }
+}
 
-    // Executando a fase transiente
-    let transient_phase_size = if queue_mode == QueueMode::ForReal {
-        queue.transient_phase()
-    } else {0};
-    if queue_mode == QueueMode::ForReal {
-        println!(
-            "\nTotal de fregueses = {}; Política = {:?}; ρ = {}; Tamanho da fase transiente = {}\n",
-            round_size, queue_policy, rho, transient_phase_size
-        );
+// Semente tirada do relógio, usada quando nenhuma é dada nos parâmetros.
+// Um relógio anterior a 1970 não impede a execução, a distância até a época serve igualmente
+// como semente
+pub(crate) fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_else(|error| error.duration())
+        .as_secs()
+}
+
+// Função interna que imprime o cabeçalho de uma execução do simulador
+fn print_header(
+    report: &mut Report,
+    parameters: &SimulationParameters,
+    transient_phase_size: Option<usize>,
+) {
+    if parameters.queue_mode == QueueMode::ForReal {
+        report.println(&format!(
+            "\nTotal de fregueses = {}; Política = {:?}; ρ = {}; Gerador = {:?}; Método = {:?}; \
+            Aquecimento = {:?}; Tamanho da fase transiente = {}\n",
+            parameters.round_size,
+            parameters.queue_policy,
+            parameters.rho,
+            parameters.generator_kind,
+            parameters.analysis_method,
+            parameters.warmup_method,
+            transient_phase_size.map_or("uma por replicação".to_string(), |size| size.to_string())
+        ));
     } else {
-        println!(
+        report.println(&format!(
             "\nSimulação para aferição de Corretude do Simulador!\
-            \nTotal de fregueses = {}; Política = {:?};",
-            round_size, queue_policy
-        );
+            \nTotal de fregueses = {}; Política = {:?}; Método = {:?};",
+            parameters.round_size, parameters.queue_policy, parameters.analysis_method
+        ));
     }
+}
 
-    // Instanciando a barra de progresso que informa o andamento das rodadas de simulação
-    let progress_bar = ProgressBar::new(rounds_count as u64);
-    progress_bar.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.white} [{elapsed_precise}] [{bar:40.red/green}] {percent:>3}% {pos:>4}/{len} ({eta_precise})")
-            .progress_chars("🔥💧"),
-    );
-    // For que executa as rodadas da simulação
-    for _ in 0..rounds_count {
-        progress_bar.inc(1); // Incremento da barra de progresso
-                             // Executa uma rodada da simulação, retornando
-        let (samples, stochastic_process_samples) = queue.run_one_simulation_round(round_size);
-        // Coleta as médias e variâncias amostrais de W, X e T
-        for (name, sample) in samples {
-            if W == name {
-                w_mean_statistics.append(sample.mean());
-                w_variance_statistics.append(sample.variance());
-            } else {
-                means_statistics
-                    .get_mut(&name)
-                    .unwrap()
-                    .append(sample.mean());
-                variances_statistics
-                    .get_mut(&name)
-                    .unwrap()
-                    .append(sample.variance());
-            }
-        }
-        // Coleta as médias e variâncias amostrais de N e Nq
-        for (name, sample) in stochastic_process_samples {
-            if NQ == name {
-                nq_mean_statistics.append(sample.mean());
-                nq_variance_statistics.append(sample.variance());
-            } else {
-                means_statistics
-                    .get_mut(&name)
-                    .unwrap()
-                    .append(sample.mean());
-                variances_statistics
-                    .get_mut(&name)
-                    .unwrap()
-                    .append(sample.variance());
-            }
+// Executa o simulador e devolve seu resultado estruturado, sem escrever o arquivo dos resultados,
+// para que execuções paralelas sejam escritas em ordem. O relatório textual é impresso na hora ou,
+// com deferred_output, fica no resultado. Parâmetros inválidos são rejeitados antes de a simulação
+// começar
+pub fn run(parameters: &SimulationParameters) -> Result<SimulationReport, SimulationError> {
+    run_simulation(parameters, None)
+}
+
+// Função que executa o simulador, imprimindo seu relatório e escrevendo seus resultados no arquivo
+// dos resultados. Um arquivo incompatível com os resultados é rejeitado antes da simulação
+pub fn simulator(parameters: SimulationParameters) -> Result<(), SimulationError> {
+    check_results_file(&parameters, &results_column_names())?;
+    let report = run(&parameters)?;
+    write_results(&parameters, &report.results)
+}
+
+// Função que retoma uma execução do método das rodadas a partir do seu checkpoint, com os mesmos
+// parâmetros e continuando a guardar checkpoints no mesmo arquivo. Os resultados são idênticos aos
+// de uma execução sem interrupção, exceto pelo tempo de execução, que só conta a parte retomada
+pub fn resume_simulator(checkpoint_path: &Path) -> Result<(), SimulationError> {
+    let analysis = RoundsAnalysis::from_checkpoint(checkpoint_path)?;
+    let parameters = analysis.parameters().clone();
+    check_results_file(&parameters, &results_column_names())?;
+    let report = run_simulation(&parameters, Some(analysis))?;
+    write_results(&parameters, &report.results)
+}
+
+// Executa o simulador a partir do início ou, quando é dada, de uma análise retomada de um
+// checkpoint, que já passou pela fase transiente. Os parâmetros são validados antes de tudo
+fn run_simulation(
+    parameters: &SimulationParameters,
+    resumed_analysis: Option<RoundsAnalysis>,
+) -> Result<SimulationReport, SimulationError> {
+    parameters.validate()?;
+    let mut report = Report::new(parameters.deferred_output);
+    let SimulationParameters {
+        rho,
+        round_size,
+        queue_policy,
+        queue_mode,
+        confidence_level,
+        analysis_method,
+        ..
+    } = *parameters;
+    // Sem regime estacionário não há valores analíticos para comparar com as estimativas
+    let stable = parameters.stable();
+
+    // Semente a ser utilizada pelo gerador de amostras exponenciais. Quando não é dada, vem do
+    // relógio e é impressa para que a execução possa ser reproduzida. Numa execução retomada, é a
+    // semente guardada no checkpoint
+    let seed = match &resumed_analysis {
+        Some(analysis) => analysis.seed(),
+        None => parameters.seed.unwrap_or_else(clock_seed),
+    };
+    report.println(&format!("Semente = {}", seed));
+    if parameters.parallel_queues > 1 {
+        report.println(&format!(
+            "Filas paralelas = {}, com sementes derivadas da semente acima e a fase transiente \
+            média entre elas",
+            parameters.parallel_queues
+        ));
+    }
+
+    let now = Instant::now();
+
+    // Instancia o método de análise escolhido, que já executa a fase transiente, e coleta as
+    // estimativas e seus ICs pela regra de parada sequencial
+    let resumed = resumed_analysis.is_some();
+    let mut analysis: Box<dyn OutputAnalysis> = match resumed_analysis {
+        Some(analysis) => {
+            report.println(&format!(
+                "Execução retomada do checkpoint com {} rodadas já executadas",
+                analysis.rounds_count()
+            ));
+            Box::new(analysis)
         }
+        None => new_analysis(parameters, seed)?,
+    };
+    if analysis_method == AnalysisMethod::Replications {
+        // Cada replicação instancia e aquece sua própria fila
+        print_header(&mut report, parameters, None);
+    } else {
+        print_header(
+            &mut report,
+            parameters,
+            Some(analysis.transient_phase_size()),
+        );
+    }
+    let (estimates, stop_reason) =
+        run_sequentially(analysis.as_mut(), parameters, resumed, &mut report)?;
+    let transient_phase_size = analysis.transient_phase_size();
+    let total_clients = analysis.clients();
+    for line in analysis.diagnostics(confidence_level) {
+        report.println(&line);
     }
-    progress_bar.finish_with_message("Finalizado"); // Finaliza a barra de progresso
-
-    // Média das médias amostrais de cada rodada de N, T e X
-    let means_n_t_x = [
-        means_statistics["N"].mean(),
-        means_statistics["T"].mean(),
-        means_statistics["X"].mean(),
-    ];
-    println!(
+    report.println(&format!(
+        "Parada: {:?}; Total de fregueses usados = {}\n",
+        stop_reason, total_clients
+    ));
+
+    let means_n_t_x = estimates.means_n_t_x;
+    report.println(&format!(
         "Sample Means:\n\tE[N] = {:0.5}\tE[T] = {:0.5}\tE[X] = {:0.5}",
         means_n_t_x[0], means_n_t_x[1], means_n_t_x[2],
-    );
+    ));
 
-    // Média das variâncias amostrais de cada rodada de N, T e X
-    let variances_n_t_x = [
-        variances_statistics["N"].variance(),
-        variances_statistics["T"].variance(),
-        variances_statistics["X"].variance(),
-    ];
-    println!(
+    let variances_n_t_x = estimates.variances_n_t_x;
+    report.println(&format!(
         "Sample Variances:\n\tV(N) = {:0.5}\tV(T) = {:0.5}\tV(X) = {:0.5}\n",
         variances_n_t_x[0], variances_n_t_x[1], variances_n_t_x[2],
+    ));
+
+    // Itens a), b), c) e d) do relatório
+    print_estimate(
+        &mut report,
+        "Sample Mean",
+        "E[W] ",
+        &estimates.mean_w,
+        confidence_level,
     );
-
-    // Item a) do relatório
-    let mean_w_ci = w_mean_statistics.t_student_95percent();
-    let mean_and_ic_w = extract_statistics_and_ci_slice(w_mean_statistics.mean(), &mean_w_ci);
-    println!(
-        "Sample Mean and Confidence Interval:\n\tE[W]  = {:0.5}\n\t\tIC T-Student:\tL(0.05) = {:0.5};\
-         \tCenter = {:0.5}; \tU(0.05) = {:0.5}; \tPrecision = {:0.5}%",
-        mean_and_ic_w[0],
-        mean_and_ic_w[1],
-        mean_and_ic_w[2],
-        mean_and_ic_w[3],
-        100.0 * mean_and_ic_w[4],
+    print_estimate(
+        &mut report,
+        "Sample Variance",
+        "V(W) ",
+        &estimates.variance_w,
+        confidence_level,
     );
-
-    // Item b) do relatório
-    let w_variance = w_variance_statistics.mean();
-    let ts_ci_w = w_variance_statistics.t_student_95percent();
-    let c2_ci_w = w_variance_statistics.chi_square_95percent(w_variance);
-    let variance_and_ic_t_student_chi_square_w =
-        extract_statistics_and_2cis_slice(w_variance, &ts_ci_w, &c2_ci_w);
-    println!(
-        "Sample Variance and Confidence Interval:\n\tV(W)  = {:0.5}\n\t\tIC T-Student:\
-         \tL(0.05) = {:0.5};\tCenter = {:0.5};\tU(0.05) = {:0.5};\tPrecision = {:0.5}%\
-         \n\t\tIC Chi-Square:\tL(0.05) = {:0.5};\tCenter = {:0.5};\tU(0.05) = {:0.5};\
-         \tPrecision = {:0.5}%",
-        variance_and_ic_t_student_chi_square_w[0],
-        variance_and_ic_t_student_chi_square_w[1],
-        variance_and_ic_t_student_chi_square_w[2],
-        variance_and_ic_t_student_chi_square_w[3],
-        100.0 * variance_and_ic_t_student_chi_square_w[4],
-        variance_and_ic_t_student_chi_square_w[5],
-        variance_and_ic_t_student_chi_square_w[6],
-        variance_and_ic_t_student_chi_square_w[7],
-        100.0 * variance_and_ic_t_student_chi_square_w[8],
+    print_estimate(
+        &mut report,
+        "Sample Mean",
+        "E[Nq]",
+        &estimates.mean_nq,
+        confidence_level,
     );
-
-    // Item c) do relatório
-    let mean_nq_ci = nq_mean_statistics.t_student_95percent();
-    let mean_and_ic_nq = extract_statistics_and_ci_slice(nq_mean_statistics.mean(), &mean_nq_ci);
-    println!(
-        "Sample Mean and Confidence Interval:\n\tE[Nq] = {:0.5}\n\t\tIC T-Student:\tL(0.05) = {:0.5};\
-         \tCenter = {:0.5}; \tU(0.05) = {:0.5}; \tPrecision = {:0.5}%",
-        mean_and_ic_nq[0],
-        mean_and_ic_nq[1],
-        mean_and_ic_nq[2],
-        mean_and_ic_nq[3],
-        100.0 * mean_and_ic_nq[4],
-    );
-
-    // Item d) do relatório
-    let nq_variance = nq_variance_statistics.mean();
-    let ts_ci_nq = nq_variance_statistics.t_student_95percent();
-    let c2_ci_nq = nq_variance_statistics.chi_square_95percent(nq_variance);
-    let variance_and_ic_t_student_chi_square_nq =
-        extract_statistics_and_2cis_slice(nq_variance, &ts_ci_nq, &c2_ci_nq);
-    println!(
-        "Sample Variance and Confidence Interval:\n\tV(Nq) = {:0.5}\n\t\tIC T-Student:\
-         \tL(0.05) = {:0.5};\tCenter = {:0.5};\tU(0.05) = {:0.5};\tPrecision = {:0.5}%\
-         \n\t\tIC Chi-Square:\tL(0.05) = {:0.5};\tCenter = {:0.5};\tU(0.05) = {:0.5};\
-         \tPrecision = {:0.5}%",
-        variance_and_ic_t_student_chi_square_nq[0],
-        variance_and_ic_t_student_chi_square_nq[1],
-        variance_and_ic_t_student_chi_square_nq[2],
-        variance_and_ic_t_student_chi_square_nq[3],
-        100.0 * variance_and_ic_t_student_chi_square_nq[4],
-        variance_and_ic_t_student_chi_square_nq[5],
-        variance_and_ic_t_student_chi_square_nq[6],
-        variance_and_ic_t_student_chi_square_nq[7],
-        100.0 * variance_and_ic_t_student_chi_square_nq[8],
+    print_estimate(
+        &mut report,
+        "Sample Variance",
+        "V(Nq)",
+        &estimates.variance_nq,
+        confidence_level,
     );
 
-    // Calculando valores analíticos para E[W], V(W), E[Nq], V(Nq)
-    let analytic_mean_w = if queue_mode == QueueMode::ForReal {
-        rho / (1.0 - rho)
-    } else {
-        // Cálculo da esperança pela definição, ignoramos o valor de 0 * (1 / 4)
-        1.0 * (1.0 / 4.0) + 3.0 * (1.0 / 4.0) + 2.0 * (1.0 / 4.0)
-    };
-    let analytic_variance_w = match queue_policy {
-        QueuePolicy::FCFS => if queue_mode == QueueMode::ForReal {
-            (2.0 * rho - rho.powi(2)) / ((1.0 - rho) * (1.0 - rho))
-        } else {
-            1.0 * (1.0 / 4.0) + (2.0 * 2.0) * (1.0 / 4.0) + (3.0 * 3.0) * (1.0 / 4.0) - analytic_mean_w.powi(2)
-        },
-        QueuePolicy::LCFS => if queue_mode == QueueMode::ForReal {
-            (2.0 * rho - rho.powi(2) + rho.powi(3)) / ((1.0 - rho).powi(3))
-        } else {
-            1.0 * (1.0 / 4.0) + (5.0 * 5.0) * (1.0 / 4.0) - analytic_mean_w.powi(2)
-        }
+    // Quantis de W e T. Os valores analíticos só são conhecidos em forma fechada na M/M/1 FCFS
+    let fcfs_for_real =
+        stable && queue_mode == QueueMode::ForReal && matches!(queue_policy, QueuePolicy::FCFS);
+    let analytic_quantile_w = |probability| {
+        Some(mm1_fcfs_waiting_time_quantile(rho, probability)).filter(|_| fcfs_for_real)
     };
-    let analytic_mean_nq = if queue_mode == QueueMode::ForReal {
-        rho.powi(2) / (1.0 - rho)
-    } else {
-        // Cálculo da esperança pela definição ignoramos o 0 * (4 / 9)
-        1.0 * (4.0 / 9.0) + 2.0 * (1.0 / 9.0)
+    let analytic_quantile_t = |probability| {
+        Some(mm1_fcfs_sojourn_time_quantile(rho, probability)).filter(|_| fcfs_for_real)
     };
-    let analytic_variance_nq = if queue_mode == QueueMode::ForReal {
-        (rho.powi(2) + rho.powi(3) - rho.powi(4)) / (1.0 - rho).powi(2)
-    } else {
-        // Segundo momento - quadrado da média, ignorando o 0 * (4 / 9)
-        (1.0 * (4.0 / 9.0) + (2.0 * 2.0) * (1.0 / 9.0)) - analytic_mean_nq.powi(2)
-    };
-    println!(
-        "Analytical values:\n\tE[W]  = {:0.5}\n\tV(W)  = {:0.5}\n\tE[Nq] = {:0.5}\n\tV(Nq) = {:0.5}",
-        analytic_mean_w, analytic_variance_w, analytic_mean_nq, analytic_variance_nq
+    print_quantiles(
+        &mut report,
+        "W",
+        &estimates.quantiles_w,
+        confidence_level,
+        analytic_quantile_w,
     );
-
-    // Escreve os dados num arquivo .csv
-    write_csv_file(
-        rho,
-        round_size,
-        transient_phase_size,
-        queue_policy,
-        &means_n_t_x,
-        &variances_n_t_x,
-        &mean_and_ic_w,
-        &variance_and_ic_t_student_chi_square_w,
-        &mean_and_ic_nq,
-        &variance_and_ic_t_student_chi_square_nq,
-        analytic_mean_w,
-        analytic_variance_w,
-        analytic_mean_nq,
-        analytic_variance_nq,
-        now.elapsed().as_millis() as f64 / 1000.0,
+    print_quantiles(
+        &mut report,
+        "T",
+        &estimates.quantiles_t,
+        confidence_level,
+        analytic_quantile_t,
     );
-
-    let mut not_enough = false;
-
-    if !mean_w_ci.value_is_inside(analytic_mean_w) {
-        println!("O valor analítico de E[W] não está dentro do IC como esperado");
-        not_enough = true;
+    let mut extra_columns = quantile_csv_columns("W", &estimates.quantiles_w, analytic_quantile_w);
+    extra_columns.extend(quantile_csv_columns(
+        "T",
+        &estimates.quantiles_t,
+        analytic_quantile_t,
+    ));
+
+    // Verificações de consistência interna a partir da taxa de chegadas observada. A fórmula de
+    // Pollaczek-Khinchine depende de chegadas Poisson, que o modo de corretude não tem, e nenhuma
+    // das relações vale para uma fila instável, que não tem regime estacionário
+    report.println(&format!(
+        "Taxa de chegadas observada = {:0.5}; Utilização observada = {:0.5}",
+        estimates.arrival_rate, estimates.utilization
+    ));
+    let checks = if stable {
+        report.println("Verificações de consistência:");
+        consistency_checks(
+            &estimates,
+            total_clients,
+            confidence_level,
+            queue_mode == QueueMode::ForReal,
+        )
+    } else {
+        report.println("Verificações de consistência não se aplicam a uma fila instável");
+        vec![]
+    };
+    for check in checks.iter() {
+        report.println(&format!(
+            "\t{} : {:0.5} = {:0.5}\tTolerância = {:0.5}\t{}",
+            check.name(),
+            check.observed(),
+            check.expected(),
+            check.tolerance(),
+            if check.passed() { "OK" } else { "VIOLADA" }
+        ));
+    }
+    report.println("");
+    let violated_checks: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.passed())
+        .map(|check| check.name().to_string())
+        .collect();
+    extra_columns.push(("taxa_chegadas".to_string(), estimates.arrival_rate));
+    extra_columns.push(("utilizacao".to_string(), estimates.utilization));
+    extra_columns.push((
+        "violacoes_consistencia".to_string(),
+        violated_checks.len() as f64,
+    ));
+
+    // Histogramas de W e T e distribuição de ocupação de N, quando pedidos. A distribuição
+    // geométrica de N vale para ambas as políticas, já que nenhuma delas olha o tempo de serviço
+    if let Some(distributions) = analysis.distributions() {
+        let rows = distributions.rows(
+            rho,
+            fcfs_for_real,
+            stable && queue_mode == QueueMode::ForReal,
+        );
+        write_distributions_csv_file(&parameters.output_path, rho, queue_policy, &rows)?;
+        report.println("Quantis de N ponderados pelo tempo:");
+        for &probability in QUANTILE_PROBABILITIES.iter() {
+            report.print(&format!(
+                "\tp{} = {}",
+                100.0 * probability,
+                distributions.occupancy_quantile(probability)
+            ));
+            if stable && queue_mode == QueueMode::ForReal {
+                report.print(&format!(
+                    "\tAnalytical = {}",
+                    mm1_occupancy_quantile(rho, probability)
+                ));
+            }
+            report.println("");
+        }
+        report.println(&format!(
+            "Distribuições de W, T e N exportadas para {}\n",
+            side_file_path(&parameters.output_path, "distributions.csv").display()
+        ));
     }
 
-    if !(ts_ci_w.value_is_inside(analytic_variance_w)
-        && c2_ci_w.value_is_inside(analytic_variance_w))
-    {
-        println!("O valor analítico de V(W) não está dentro do IC como esperado");
-        not_enough = true;
+    // Autocorrelação de W por freguês e das médias de W por rodada, quando pedida
+    if let Some(autocorrelation_series) = analysis.autocorrelation_series() {
+        let client_analysis = autocorrelation_series.client_analysis(confidence_level);
+        let round_analysis = autocorrelation_series.round_analysis(confidence_level);
+        report.println(&format!(
+            "Tempo de autocorrelação integrado: W por freguês = {:0.5}; médias de W por rodada = \
+             {:0.5}",
+            client_analysis.integrated_time, round_analysis.integrated_time
+        ));
+        write_autocorrelation_csv_file(
+            &parameters.output_path,
+            rho,
+            queue_policy,
+            analysis_method,
+            &[("W", &client_analysis), ("W_rodada", &round_analysis)],
+        )?;
+        if autocorrelation_series.rounds_too_short(&client_analysis, &round_analysis) {
+            report.println(&format!(
+                "Rodadas de {} fregueses podem ser curtas demais para serem independentes",
+                autocorrelation_series.round_size()
+            ));
+        }
+        report.println(&format!(
+            "Autocorrelações exportadas para {}\n",
+            side_file_path(&parameters.output_path, "autocorrelation.csv").display()
+        ));
     }
 
-    if !mean_nq_ci.value_is_inside(analytic_mean_nq) {
-        println!("O valor analítico de E[Nq] não está dentro do IC como esperado");
-        not_enough = true;
+    let mean_w_ci = estimates.mean_w.t_student;
+    let ts_ci_w = estimates.variance_w.t_student;
+    let c2_ci_w = estimates.variance_w.chi_square;
+    let mean_nq_ci = estimates.mean_nq.t_student;
+    let ts_ci_nq = estimates.variance_nq.t_student;
+    let c2_ci_nq = estimates.variance_nq.chi_square;
+
+    // Valores analíticos de E[W], V(W), E[Nq], V(Nq), E[N] e P(W > 0) do modelo simulado. Uma
+    // variância sem forma fechada, ou todos os valores de uma fila instável, ficam como NaN nos
+    // resultados e não são validados
+    let model = analytic_model(parameters);
+    let analytic_values = model.as_deref().map(AnalyticValues::new);
+    let analytic_mean_w = analytic_values
+        .as_ref()
+        .map_or(f64::NAN, |values| values.mean_w);
+    let analytic_variance_w = analytic_values
+        .as_ref()
+        .and_then(|values| values.variance_w)
+        .unwrap_or(f64::NAN);
+    let analytic_mean_nq = analytic_values
+        .as_ref()
+        .map_or(f64::NAN, |values| values.mean_nq);
+    let analytic_variance_nq = analytic_values
+        .as_ref()
+        .and_then(|values| values.variance_nq)
+        .unwrap_or(f64::NAN);
+    match &model {
+        Some(model) => report.println(&analytic_values_text(model.as_ref())),
+        None => report.println(&format!(
+            "Fila instável com ρ = {}: sem regime estacionário não há valores analíticos",
+            rho
+        )),
     }
 
-    if !(ts_ci_nq.value_is_inside(analytic_variance_nq)
-        && c2_ci_nq.value_is_inside(analytic_variance_nq))
-    {
-        println!("O valor analítico de V(Nq) não está dentro do IC como esperado");
-        not_enough = true;
+    // Convergência das médias de W e Nq ao longo das rodadas, quando os gráficos foram pedidos
+    if let Some(directory) = &parameters.plots_directory {
+        match analysis.round_means() {
+            Some([w_means, nq_means]) => {
+                let finite = |value: f64| Some(value).filter(|value| value.is_finite());
+                plot_convergence(
+                    directory,
+                    parameters,
+                    "E[W]",
+                    w_means,
+                    finite(analytic_mean_w),
+                )?;
+                plot_convergence(
+                    directory,
+                    parameters,
+                    "E[Nq]",
+                    nq_means,
+                    finite(analytic_mean_nq),
+                )?;
+                report.println(&format!(
+                    "Gráficos de convergência exportados para {}\n",
+                    directory.display()
+                ));
+            }
+            None => report.println(
+                "O método de análise usado não tem rodadas para o gráfico de convergência\n",
+            ),
+        }
     }
 
-    // Caso não tenhamos precisão suficiente, executamos de novo para mais fregueses
-    if mean_w_ci.precision() > 0.05 {
-        println!(
-            "Precisão do IC de E[W] = {:0.5}% não é suficiente",
-            100.0 * mean_w_ci.precision(),
-        );
-        not_enough = true;
+    // Monta os resultados da execução, que são escritos por quem chamou. As colunas existentes
+    // mantêm sua ordem e as novas métricas vão para o fim, para não quebrar quem lê os resultados
+    let elapsed_time = now.elapsed();
+    let mut results = ResultsRecord::new();
+    results.number("rho", rho);
+    results.integer("fregueses", round_size as u64);
+    results.integer("fase_transiente", transient_phase_size as u64);
+    results.text("policy", &format!("{:?}", queue_policy));
+    results.text("method", &format!("{:?}", analysis_method));
+    results.number("confidence", confidence_level);
+    results.integer("fregueses_totais", total_clients as u64);
+    results.text("parada", &format!("{:?}", stop_reason));
+    for (name, value) in ["E[N]", "E[T]", "E[X]"].iter().zip(means_n_t_x.iter()) {
+        results.number(name, *value);
+    }
+    for (name, value) in ["V(N)", "V(T)", "V(X)"].iter().zip(variances_n_t_x.iter()) {
+        results.number(name, *value);
     }
+    estimate_columns(&mut results, "E[W]", &estimates.mean_w, false);
+    estimate_columns(&mut results, "V(W)", &estimates.variance_w, true);
+    estimate_columns(&mut results, "E[Nq]", &estimates.mean_nq, false);
+    estimate_columns(&mut results, "V(Nq)", &estimates.variance_nq, true);
+    results.number("E[W]_analytic", analytic_mean_w);
+    results.number("V(W)_analytic", analytic_variance_w);
+    results.number("E[Nq]_analytic", analytic_mean_nq);
+    results.number("V(Nq)_analytic", analytic_variance_nq);
+    results.number("elapsed_time(s)", elapsed_time.as_millis() as f64 / 1000.0);
+    // As colunas dos quantis e das verificações de consistência vêm depois, com seus nomes gerados
+    // junto com os valores
+    for (name, value) in extra_columns.iter() {
+        results.number(name, *value);
+    }
+    results.integer("semente", seed);
+    results.number(
+        "E[N]_analytic",
+        analytic_values
+            .as_ref()
+            .map_or(f64::NAN, |values| values.mean_n),
+    );
+    results.number(
+        "P(W>0)_analytic",
+        analytic_values
+            .as_ref()
+            .map_or(f64::NAN, |values| values.waiting_probability),
+    );
 
-    // Caso não tenhamos precisão suficiente, executamos de novo para mais fregueses
-    if ts_ci_w.precision() > 0.05 {
-        println!(
-            "Precisão do IC pela T-Student de V(W) = {:0.5}% não é suficiente",
-            100.0 * ts_ci_w.precision(),
-        );
-        not_enough = true;
+    // Verifica se os valores analíticos estão dentro dos ICs, apenas como validação. Isso não é
+    // usado como critério de parada, pois em média 5% dos ICs de 95% não contêm o valor real
+    if let Some(values) = &analytic_values {
+        if !mean_w_ci.value_is_inside(analytic_mean_w) {
+            report.println("O valor analítico de E[W] não está dentro do IC como esperado");
+        }
+
+        if values.variance_w.is_some()
+            && !(ts_ci_w.value_is_inside(analytic_variance_w)
+                && c2_ci_w.is_none_or(|ci| ci.value_is_inside(analytic_variance_w)))
+        {
+            report.println("O valor analítico de V(W) não está dentro do IC como esperado");
+        }
+
+        if !mean_nq_ci.value_is_inside(analytic_mean_nq) {
+            report.println("O valor analítico de E[Nq] não está dentro do IC como esperado");
+        }
+
+        if values.variance_nq.is_some()
+            && !(ts_ci_nq.value_is_inside(analytic_variance_nq)
+                && c2_ci_nq.is_none_or(|ci| ci.value_is_inside(analytic_variance_nq)))
+        {
+            report.println("O valor analítico de V(Nq) não está dentro do IC como esperado");
+        }
     }
 
-    // Caso não tenhamos precisão suficiente, executamos de novo para mais fregueses
-    if mean_nq_ci.precision() > 0.05 {
-        println!(
-            "Precisão do IC de E[Nq] = {:0.5}% não é suficiente",
-            100.0 * mean_nq_ci.precision(),
-        );
-        not_enough = true;
+    // Diferente dos valores analíticos, as relações de consistência valem para qualquer execução,
+    // então uma violação indica um erro na coleta ou ICs otimistas demais
+    for name in violated_checks.iter() {
+        report.println(&format!(
+            "A relação {} não é satisfeita pelas estimativas",
+            name
+        ));
     }
 
-    // Caso não tenhamos precisão suficiente, executamos de novo para mais fregueses
-    if ts_ci_nq.precision() > 0.05 {
-        println!(
-            "Precisão do IC pela T-Student de V(Nq) = {:0.5}% não é suficiente",
-            100.0 * ts_ci_nq.precision(),
-        );
-        not_enough = true;
+    if stop_reason == StopReason::BudgetExhausted {
+        report.println(&format!(
+            "A precisão alvo de {:0.5}% não foi atingida com o máximo de {} fregueses",
+            100.0 * parameters.target_precision,
+            parameters.maximum_clients
+        ));
     }
 
-    // Caso não tenhamos convergência dos ICs, executamos de novo para mais fregueses
-    if !ConfidenceInterval::check_convergence(c2_ci_w, ts_ci_w) {
-        println!("Os intervalos de confiança para V(W) com T-Student e Chi-Square não convergem");
-        not_enough = true;
+    Ok(SimulationReport {
+        parameters: parameters.clone(),
+        seed,
+        estimates,
+        analytic_values,
+        stop_reason,
+        transient_phase_size,
+        total_clients,
+        consistency_violations: violated_checks,
+        elapsed_time,
+        results,
+        text: report.into_text(),
+    })
+}
+
+// Imprime os valores analíticos de um modelo
+pub fn print_analytic_values(model: &dyn AnalyticModel) {
+    println!("{}", analytic_values_text(model));
+}
+
+// Texto de uma variância analítica, que pode não ter forma fechada no modelo
+fn analytic_variance_text(variance: Option<f64>) -> String {
+    variance.map_or("sem forma fechada".to_string(), |variance| {
+        format!("{:0.5}", variance)
+    })
+}
+
+// Texto com os valores analíticos de um modelo, usado no relatório e no subcomando analytic
+fn analytic_values_text(model: &dyn AnalyticModel) -> String {
+    format!(
+        "Analytical values ({}{}):\n\tE[W]  = {:0.5}\n\tV(W)  = {}\n\tE[Nq] = {:0.5}\n\t\
+         V(Nq) = {}\n\tE[N]  = {:0.5}\n\tP(W > 0) = {:0.5}",
+        model.name(),
+        if model.exact() { "" } else { ", aproximação" },
+        model.mean_w(),
+        analytic_variance_text(model.variance_w()),
+        model.mean_nq(),
+        analytic_variance_text(model.variance_nq()),
+        model.mean_n(),
+        model.waiting_probability()
+    )
+}
+
+// Nomes das colunas dos resultados de uma execução, na ordem em que run_simulation as monta. Eles
+// não dependem dos valores, então o arquivo dos resultados pode ser conferido antes da simulação
+pub(crate) fn results_column_names() -> Vec<String> {
+    let mut names: Vec<String> = [
+        "rho",
+        "fregueses",
+        "fase_transiente",
+        "policy",
+        "method",
+        "confidence",
+        "fregueses_totais",
+        "parada",
+        "E[N]",
+        "E[T]",
+        "E[X]",
+        "V(N)",
+        "V(T)",
+        "V(X)",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect();
+    for &(name, with_chi_square) in [
+        ("E[W]", false),
+        ("V(W)", true),
+        ("E[Nq]", false),
+        ("V(Nq)", true),
+    ]
+    .iter()
+    {
+        names.push(name.to_string());
+        let kinds: &[&str] = if with_chi_square {
+            &["TS", "C2"]
+        } else {
+            &["TS"]
+        };
+        for kind in kinds {
+            for suffix in ["L", "C", "U", "P"].iter() {
+                names.push(format!("{}_IC_{}_{}", name, kind, suffix));
+            }
+        }
+    }
+    for name in [
+        "E[W]_analytic",
+        "V(W)_analytic",
+        "E[Nq]_analytic",
+        "V(Nq)_analytic",
+        "elapsed_time(s)",
+    ]
+    .iter()
+    {
+        names.push(name.to_string());
+    }
+    for name in ["W", "T"].iter() {
+        for &probability in QUANTILE_PROBABILITIES.iter() {
+            let column = quantile_column(name, probability);
+            names.push(column.clone());
+            for suffix in ["IC_TS_L", "IC_TS_U", "analytic"].iter() {
+                names.push(format!("{}_{}", column, suffix));
+            }
+        }
+    }
+    for name in [
+        "taxa_chegadas",
+        "utilizacao",
+        "violacoes_consistencia",
+        "semente",
+        "E[N]_analytic",
+        "P(W>0)_analytic",
+    ]
+    .iter()
+    {
+        names.push(name.to_string());
     }
+    names
+}
 
-    // Caso não tenhamos convergência dos ICs, executamos de novo para mais fregueses
-    if !ConfidenceInterval::check_convergence(c2_ci_nq, ts_ci_nq) {
-        println!(
-            "Os intervalos de confiança para V(Nq) com T-Student e Chi-Square não convergem"
-        );
-        not_enough = true;
+// Função interna que acrescenta aos resultados uma estimativa com seu IC t-Student e, quando
+// pedido, seu IC qui-quadrado. Quando esse IC não existe para o método de análise usado, seus
+// valores ficam como NaN
+fn estimate_columns(
+    results: &mut ResultsRecord,
+    name: &str,
+    estimate: &MetricEstimate,
+    with_chi_square: bool,
+) {
+    results.number(name, estimate.value);
+    let mut ci_columns = |kind: &str, ci: Option<&ConfidenceInterval>| {
+        let values = ci.map_or([f64::NAN; 4], |ci| {
+            [
+                ci.lower_bound(),
+                ci.center(),
+                ci.upper_bound(),
+                ci.precision(),
+            ]
+        });
+        for (suffix, value) in ["L", "C", "U", "P"].iter().zip(values.iter()) {
+            results.number(&format!("{}_IC_{}_{}", name, kind, suffix), *value);
+        }
+    };
+    ci_columns("TS", Some(&estimate.t_student));
+    if with_chi_square {
+        ci_columns("C2", estimate.chi_square.as_ref());
     }
-    if queue_mode == QueueMode::CheckCorrectness {
-        not_enough = false;
+}
+
+// Função interna que imprime os quantis de uma métrica com seus ICs e, quando conhecidos, seus
+// valores analíticos, avisando quando o valor analítico não está dentro do IC
+fn print_quantiles<F: Fn(f64) -> Option<f64>>(
+    report: &mut Report,
+    name: &str,
+    quantiles: &[QuantileEstimate],
+    confidence_level: f64,
+    analytic_quantile: F,
+) {
+    report.println(&format!(
+        "Sample Quantiles of {} and {:.0}% Confidence Intervals:",
+        name,
+        100.0 * confidence_level
+    ));
+    for quantile in quantiles {
+        let ci = quantile.estimate.t_student;
+        report.print(&format!(
+            "\tp{} = {:0.5}\tL = {:0.5};\tU = {:0.5};\tPrecision = {:0.5}%",
+            100.0 * quantile.probability,
+            quantile.estimate.value,
+            ci.lower_bound(),
+            ci.upper_bound(),
+            100.0 * ci.precision(),
+        ));
+        if let Some(analytic_value) = analytic_quantile(quantile.probability) {
+            report.print(&format!("\tAnalytical = {:0.5}", analytic_value));
+            if !ci.value_is_inside(analytic_value) {
+                report.print(" (fora do IC)");
+            }
+        }
+        report.println("");
     }
+}
 
-    if not_enough {
-        println!("Rodando agora para {} clientes", round_size + 100);
-        return simulator(rho, round_size + 100, rounds_count, queue_policy, queue_mode);
+// Nome da coluna dos resultados com um quantil de uma métrica, como W_p99
+fn quantile_column(name: &str, probability: f64) -> String {
+    format!("{}_p{}", name, 100.0 * probability)
+}
+
+// Função interna que monta as colunas dos resultados com os quantis de uma métrica, seus ICs e valores
+// analíticos, que ficam como NaN quando não são conhecidos
+fn quantile_csv_columns<F: Fn(f64) -> Option<f64>>(
+    name: &str,
+    quantiles: &[QuantileEstimate],
+    analytic_quantile: F,
+) -> Vec<(String, f64)> {
+    let mut columns = vec![];
+    for quantile in quantiles {
+        let column = quantile_column(name, quantile.probability);
+        let ci = quantile.estimate.t_student;
+        columns.push((column.clone(), quantile.estimate.value));
+        columns.push((format!("{}_IC_TS_L", column), ci.lower_bound()));
+        columns.push((format!("{}_IC_TS_U", column), ci.upper_bound()));
+        columns.push((
+            format!("{}_analytic", column),
+            analytic_quantile(quantile.probability).unwrap_or(f64::NAN),
+        ));
     }
+    columns
 }
 
-// Função interna para extrair um array contendo a estatística em questão e seu IC
-fn extract_statistics_and_ci_slice(statistic: f64, ci: &ConfidenceInterval) -> [f64; 5] {
-    [
-        statistic,
-        ci.lower_bound(),
-        ci.center(),
-        ci.upper_bound(),
-        ci.precision(),
-    ]
+// Função interna que imprime uma estimativa com seus ICs
+fn print_estimate(
+    report: &mut Report,
+    title: &str,
+    name: &str,
+    estimate: &MetricEstimate,
+    confidence_level: f64,
+) {
+    report.println(&format!(
+        "{} and {:.0}% Confidence Interval:\n\t{} = {:0.5}\n\t\tIC T-Student:\tL = {:0.5};\
+         \tCenter = {:0.5};\tU = {:0.5};\tPrecision = {:0.5}%",
+        title,
+        100.0 * confidence_level,
+        name,
+        estimate.value,
+        estimate.t_student.lower_bound(),
+        estimate.t_student.center(),
+        estimate.t_student.upper_bound(),
+        100.0 * estimate.t_student.precision(),
+    ));
+    if let Some(chi_square) = &estimate.chi_square {
+        report.println(&format!(
+            "\t\tIC Chi-Square:\tL = {:0.5};\tCenter = {:0.5};\tU = {:0.5};\tPrecision = {:0.5}%",
+            chi_square.lower_bound(),
+            chi_square.center(),
+            chi_square.upper_bound(),
+            100.0 * chi_square.precision(),
+        ));
+    }
 }
 
-// Função interna para extrair um array contendo a estatística em questão e seus dois ICs
-fn extract_statistics_and_2cis_slice(
-    statistic: f64,
-    first_ci: &ConfidenceInterval,
-    second_ci: &ConfidenceInterval,
-) -> [f64; 9] {
-    [
-        statistic,
-        first_ci.lower_bound(),
-        first_ci.center(),
-        first_ci.upper_bound(),
-        first_ci.precision(),
-        second_ci.lower_bound(),
-        second_ci.center(),
-        second_ci.upper_bound(),
-        second_ci.precision(),
-    ]
+// Função que executa a bateria de testes estatísticos em cada um dos geradores disponíveis,
+// para constatar que são aceitáveis antes de experimentos longos. Cada teste é repetido com
+// sementes diferentes e comparamos a quantidade de falhas com a esperada. Retorna se todos passaram
+pub fn check_generators(seed: u64, sample_size: usize, repetitions: usize) -> bool {
+    let maximum_failures = maximum_expected_failures(repetitions);
+    let mut all_passed = true;
+    for &generator_kind in GeneratorKind::ALL.iter() {
+        println!(
+            "\nGerador {:?} com {} repetições de {} amostras:",
+            generator_kind, repetitions, sample_size
+        );
+        // Quantidade de falhas de cada teste e o maior |z| observado
+        let mut failures: Vec<(&str, usize, f64)> = vec![];
+        for repetition in 0..repetitions {
+            let results = run_quality_tests(
+                generator_kind,
+                seed.wrapping_add(repetition as u64),
+                sample_size,
+            );
+            for (index, result) in results.iter().enumerate() {
+                if failures.len() <= index {
+                    failures.push((result.name(), 0, 0.0));
+                }
+                if !result.passed() {
+                    failures[index].1 += 1;
+                }
+                failures[index].2 = f64::max(failures[index].2, result.z_score().abs());
+            }
+        }
+        for (name, failure_count, largest_z_score) in failures {
+            let passed = failure_count <= maximum_failures;
+            println!(
+                "\t{:<28} falhas = {:>3}/{} (máximo aceitável = {})\tmaior |z| = {:>7.4}\t{}",
+                name,
+                failure_count,
+                repetitions,
+                maximum_failures,
+                largest_z_score,
+                if passed { "OK" } else { "FALHOU" }
+            );
+            all_passed &= passed;
+        }
+    }
+    all_passed
 }
@@ -0,0 +1,236 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando o quantil da Normal
+use crate::simulator::statistical_distributions::normal_quantile;
+
+// Quantidade máxima de fregueses consecutivos guardados para a autocorrelação da série de W
+const CLIENT_SERIES_LENGTH: usize = 100_000;
+// Fator da janela automática de Sokal: a soma das autocorrelações para no primeiro lag M com
+// M >= c τ(M), que equilibra o viés do truncamento e a variância dos lags distantes
+const SOKAL_WINDOW_FACTOR: f64 = 5.0;
+// Quantos tempos de autocorrelação integrados cabem numa rodada para que as médias das rodadas
+// possam ser consideradas independentes
+const ROUND_SIZE_FACTOR: f64 = 10.0;
+
+// Função de autocorrelação amostral de uma série, dos lags 0 até max_lag (limitado ao tamanho da
+// série). Uma série constante tem autocorrelação nula em todos os lags maiores que zero
+pub fn autocorrelation_function(series: &[f64], max_lag: usize) -> Vec<f64> {
+    let max_lag = usize::min(max_lag, series.len().saturating_sub(1));
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    let deviations: Vec<f64> = series.iter().map(|&value| value - mean).collect();
+    let denominator = deviations
+        .iter()
+        .map(|deviation| deviation.powi(2))
+        .sum::<f64>();
+    if denominator == 0.0 {
+        let mut autocorrelations = vec![0.0; max_lag + 1];
+        autocorrelations[0] = 1.0;
+        return autocorrelations;
+    }
+    (0..=max_lag)
+        .map(|lag| {
+            deviations
+                .iter()
+                .zip(deviations[lag..].iter())
+                .fold(0.0, |sum, (first, second)| sum + first * second)
+                / denominator
+        })
+        .collect()
+}
+
+// Tempo de autocorrelação integrado τ = 1 + 2 Σ ρ_k, truncado pela janela automática de Sokal.
+// Uma série com τ tem a variância da média τ vezes maior que uma série independente, ou seja,
+// equivale a n / τ observações independentes
+pub fn integrated_autocorrelation_time(autocorrelations: &[f64]) -> f64 {
+    let mut time = 1.0;
+    for (lag, autocorrelation) in autocorrelations.iter().enumerate().skip(1) {
+        time += 2.0 * autocorrelation;
+        if lag as f64 >= SOKAL_WINDOW_FACTOR * time {
+            break;
+        }
+    }
+    time
+}
+
+// Struct que acumula as séries usadas na análise de autocorrelação: os valores de W dos primeiros
+// fregueses e as médias de W de cada rodada de round_size fregueses consecutivos. Nos métodos de
+// uma rodada longa, e no regenerativo, as rodadas são apenas trechos consecutivos da série. Nas
+// replicações a série de W por freguês emenda replicações independentes, o que só afeta os lags
+// que cruzam as emendas
+#[derive(Serialize, Deserialize)]
+pub struct AutocorrelationSeries {
+    max_lag: usize,          // Maior lag calculado
+    round_size: usize,       // Quantidade de fregueses por rodada
+    client_w: Vec<f64>,      // Série de W dos primeiros fregueses
+    round_w_means: Vec<f64>, // Médias de W de cada rodada
+    pending_w_sum: f64,      // Soma de W da rodada incompleta
+    pending_clients: usize,  // Quantidade de fregueses da rodada incompleta
+}
+
+// Resultado da análise de autocorrelação de uma série
+pub struct AutocorrelationAnalysis {
+    pub autocorrelations: Vec<f64>, // Autocorrelações dos lags 0 até o maior lag
+    pub integrated_time: f64,       // Tempo de autocorrelação integrado
+    pub significance_threshold: f64, // Limite de significância ±z / √n das autocorrelações
+}
+
+impl AutocorrelationAnalysis {
+    // Analisa uma série até o lag pedido, com o limite de significância do nível de confiança
+    fn new(series: &[f64], max_lag: usize, confidence_level: f64) -> Self {
+        let autocorrelations = autocorrelation_function(series, max_lag);
+        Self {
+            integrated_time: integrated_autocorrelation_time(&autocorrelations),
+            significance_threshold: normal_quantile(1.0 - (1.0 - confidence_level) / 2.0)
+                / (series.len() as f64).sqrt(),
+            autocorrelations,
+        }
+    }
+
+    // Indica se a autocorrelação com lag 1 é significativa
+    pub fn lag_one_is_significant(&self) -> bool {
+        self.autocorrelations
+            .get(1)
+            .is_some_and(|autocorrelation| autocorrelation.abs() > self.significance_threshold)
+    }
+}
+
+impl AutocorrelationSeries {
+    // Instancia as séries vazias
+    pub fn new(max_lag: usize, round_size: usize) -> Self {
+        Self {
+            max_lag,
+            round_size,
+            client_w: Vec::with_capacity(CLIENT_SERIES_LENGTH),
+            round_w_means: vec![],
+            pending_w_sum: 0.0,
+            pending_clients: 0,
+        }
+    }
+
+    // Acumula os valores de W de fregueses consecutivos, fechando uma rodada a cada round_size
+    pub fn append(&mut self, w_values: &[f64]) {
+        let remaining = CLIENT_SERIES_LENGTH - self.client_w.len();
+        self.client_w
+            .extend_from_slice(&w_values[..usize::min(remaining, w_values.len())]);
+        for &value in w_values {
+            self.pending_w_sum += value;
+            self.pending_clients += 1;
+            if self.pending_clients == self.round_size {
+                self.round_w_means
+                    .push(self.pending_w_sum / self.round_size as f64);
+                self.pending_w_sum = 0.0;
+                self.pending_clients = 0;
+            }
+        }
+    }
+
+    // Emenda as séries de outra fila no fim destas, o que só afeta os lags que cruzam a emenda. As
+    // rodadas incompletas de cada fila são descartadas
+    pub fn merge(&mut self, other: &AutocorrelationSeries) {
+        let remaining = CLIENT_SERIES_LENGTH - self.client_w.len();
+        self.client_w
+            .extend_from_slice(&other.client_w[..usize::min(remaining, other.client_w.len())]);
+        self.round_w_means.extend_from_slice(&other.round_w_means);
+    }
+
+    // Autocorrelação da série de W por freguês
+    pub fn client_analysis(&self, confidence_level: f64) -> AutocorrelationAnalysis {
+        AutocorrelationAnalysis::new(&self.client_w, self.max_lag, confidence_level)
+    }
+
+    // Autocorrelação das médias de W das rodadas, até no máximo metade da quantidade de rodadas
+    pub fn round_analysis(&self, confidence_level: f64) -> AutocorrelationAnalysis {
+        let max_lag = usize::min(self.max_lag, self.round_w_means.len() / 2);
+        AutocorrelationAnalysis::new(&self.round_w_means, max_lag, confidence_level)
+    }
+
+    // Indica se as rodadas são curtas demais para serem independentes: cada rodada deve conter
+    // vários tempos de autocorrelação integrados de W, e as médias das rodadas não podem ter
+    // autocorrelação com lag 1 significativa
+    pub fn rounds_too_short(
+        &self,
+        client_analysis: &AutocorrelationAnalysis,
+        round_analysis: &AutocorrelationAnalysis,
+    ) -> bool {
+        (self.round_size as f64) < ROUND_SIZE_FACTOR * client_analysis.integrated_time
+            || round_analysis.lag_one_is_significant()
+    }
+
+    // Tamanho de cada rodada, em fregueses
+    pub fn round_size(&self) -> usize {
+        self.round_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::test_helpers::{assert_close, assert_close_within};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Série AR(1) Y_k = φ Y_{k-1} + ε_k com ε_k uniforme em [-1/2, 1/2), cuja autocorrelação com
+    // lag k é φ^k e cujo tempo de autocorrelação integrado é (1 + φ) / (1 - φ)
+    fn autoregressive_series(phi: f64, length: usize, seed: u64) -> Vec<f64> {
+        let mut generator = StdRng::seed_from_u64(seed);
+        let mut value = 0.0;
+        (0..length)
+            .map(|_| {
+                value = phi * value + generator.gen::<f64>() - 0.5;
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn autocorrelation_of_a_short_series() {
+        // Desvios -1.5, -0.5, 0.5 e 1.5 em torno da média 2.5, com soma dos quadrados 5
+        let autocorrelations = autocorrelation_function(&[1.0, 2.0, 3.0, 4.0], 10);
+        assert_eq!(autocorrelations.len(), 4);
+        assert_close(autocorrelations[0], 1.0);
+        assert_close(autocorrelations[1], 1.25 / 5.0);
+        assert_close(autocorrelations[2], -1.5 / 5.0);
+        assert_close(autocorrelations[3], -2.25 / 5.0);
+        assert_eq!(autocorrelation_function(&[3.0; 5], 2), vec![1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn autoregressive_autocorrelations_decay_geometrically() {
+        let phi = 0.7;
+        let autocorrelations =
+            autocorrelation_function(&autoregressive_series(phi, CLIENT_SERIES_LENGTH, 3), 10);
+        for (lag, autocorrelation) in autocorrelations.iter().enumerate() {
+            assert_close_within(*autocorrelation, phi.powi(lag as i32), 0.02);
+        }
+    }
+
+    #[test]
+    fn integrated_time_matches_the_autoregressive_value() {
+        for &phi in [0.0, 0.5, 0.8].iter() {
+            let series = autoregressive_series(phi, CLIENT_SERIES_LENGTH, 5);
+            let time = integrated_autocorrelation_time(&autocorrelation_function(&series, 200));
+            let expected = (1.0 + phi) / (1.0 - phi);
+            assert_close_within(time, expected, 0.05 * expected);
+        }
+    }
+
+    #[test]
+    fn short_rounds_are_flagged() {
+        // τ = 5.67, então rodadas de 1000 fregueses cabem mais de 10τ e rodadas de 20 não
+        let series = autoregressive_series(0.7, CLIENT_SERIES_LENGTH, 9);
+        for &(round_size, too_short) in [(1000, false), (20, true)].iter() {
+            let mut autocorrelation_series = AutocorrelationSeries::new(50, round_size);
+            autocorrelation_series.append(&series);
+            let client_analysis = autocorrelation_series.client_analysis(0.95);
+            let round_analysis = autocorrelation_series.round_analysis(0.95);
+            assert_eq!(
+                round_analysis.autocorrelations.len(),
+                usize::min(51, CLIENT_SERIES_LENGTH / round_size / 2 + 1)
+            );
+            assert_eq!(
+                autocorrelation_series.rounds_too_short(&client_analysis, &round_analysis),
+                too_short
+            );
+        }
+    }
+}
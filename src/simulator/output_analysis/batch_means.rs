@@ -0,0 +1,477 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Séries emprestadas ou calculadas a cada estimativa
+use std::borrow::Cow;
+// Importando a representação do nosso intervalo de confiança
+use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando a representação do nossa fila M/M/1 e algumas constantes
+use crate::simulator::queue::{Queue, N, NQ, T, W, X};
+// Importando a representação do nosso acumulador de amostras de variáveis aleatórias
+use crate::simulator::sample_accumulators::sample::Sample;
+// Importando a representação do nosso acumulador de amostras de processos estocásticos
+use crate::simulator::sample_accumulators::stochastic_process_sample::StochasticProcessSample;
+// Importando os quantis da Normal e da T-Student
+use crate::simulator::statistical_distributions::{normal_quantile, student_t_quantile};
+// Importando a função que instancia uma fila já aquecida e os parâmetros de uma execução
+use crate::simulator::{warmed_up_queue, SimulationParameters};
+// Importando a representação das estimativas produzidas por um método de análise
+use super::{select_sample_storages, AnalysisMethod, Estimates, MetricEstimate, OutputAnalysis};
+// Importando a função de autocorrelação e o acumulador das séries da análise de autocorrelação
+use super::autocorrelation::{autocorrelation_function, AutocorrelationSeries};
+// Importando o acumulador das distribuições completas de W, T e N
+use super::distributions::Distributions;
+// Importando a estimação dos quantis por seccionamento
+use super::quantiles::{
+    sectioning_quantile_estimate, QuantileEstimate, QuantileEstimator, QUANTILE_PROBABILITIES,
+};
+
+// Quantidade de lotes com a qual começamos a busca pelo tamanho de lote
+const INITIAL_BATCH_COUNT: usize = 1024;
+// Menor quantidade de lotes aceitável, abaixo disso os ICs ficam largos demais
+const MINIMUM_BATCH_COUNT: usize = 20;
+
+// Calcula as médias de lotes não sobrepostos de tamanho batch_size, descartando o resto final
+pub fn batch_means(series: &[f64], batch_size: usize) -> Vec<f64> {
+    series
+        .chunks_exact(batch_size)
+        .map(|batch| batch.iter().sum::<f64>() / batch_size as f64)
+        .collect()
+}
+
+// Calcula a autocorrelação com lag 1 de uma série
+pub fn lag_one_autocorrelation(values: &[f64]) -> f64 {
+    autocorrelation_function(values, 1)
+        .get(1)
+        .copied()
+        .unwrap_or(0.0)
+}
+
+// Escolhe o tamanho de lote automaticamente. Começamos com muitos lotes pequenos e dobramos o
+// tamanho dos lotes até que a autocorrelação com lag 1 das médias dos lotes não seja mais
+// significativa, ou até atingirmos a quantidade mínima de lotes
+pub fn select_batch_size(series: &[f64], confidence_level: f64) -> usize {
+    let critical_value = normal_quantile(1.0 - (1.0 - confidence_level) / 2.0);
+    let mut batch_size = usize::max(1, series.len() / INITIAL_BATCH_COUNT);
+    loop {
+        let means = batch_means(series, batch_size);
+        let uncorrelated =
+            lag_one_autocorrelation(&means).abs() <= critical_value / (means.len() as f64).sqrt();
+        if uncorrelated || series.len() / (2 * batch_size) < MINIMUM_BATCH_COUNT {
+            break batch_size;
+        }
+        batch_size *= 2;
+    }
+}
+
+// IC pelo método das médias em lotes não sobrepostos, com b - 1 graus de liberdade
+pub fn batch_means_ci(
+    series: &[f64],
+    batch_size: usize,
+    confidence_level: f64,
+) -> (f64, ConfidenceInterval) {
+    let mut sample = Sample::new(series.len() / batch_size);
+    for mean in batch_means(series, batch_size) {
+        sample.append(mean);
+    }
+    (sample.mean(), sample.t_student(confidence_level))
+}
+
+// IC pelo método das médias em lotes sobrepostos. Todos os n - m + 1 lotes de tamanho m são
+// usados, o que reduz a variância do estimador em relação aos lotes não sobrepostos.
+// A distribuição do estimador é aproximada por uma T-Student com 1.5 (n / m - 1) graus de liberdade
+pub fn overlapping_batch_means_ci(
+    series: &[f64],
+    batch_size: usize,
+    confidence_level: f64,
+) -> (f64, ConfidenceInterval) {
+    let count = series.len();
+    let mean = series.iter().sum::<f64>() / count as f64;
+    // Soma móvel para calcular a média de cada lote em O(1)
+    let mut window_sum = series[..batch_size].iter().sum::<f64>();
+    let mut squared_deviations = (window_sum / batch_size as f64 - mean).powi(2);
+    for index in batch_size..count {
+        window_sum += series[index] - series[index - batch_size];
+        squared_deviations += (window_sum / batch_size as f64 - mean).powi(2);
+    }
+    let variance_parameter = count as f64 * batch_size as f64 * squared_deviations
+        / ((count - batch_size + 1) as f64 * (count - batch_size) as f64);
+    let degrees_of_freedom = 1.5 * (count as f64 / batch_size as f64 - 1.0);
+    let half_width = student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, degrees_of_freedom)
+        * (variance_parameter / count as f64).sqrt();
+    (
+        mean,
+        ConfidenceInterval::new(mean - half_width, mean + half_width),
+    )
+}
+
+// IC pelo método das séries temporais padronizadas. Em cada lote calculamos o estimador de área
+// da ponte Browniana formada pelas somas parciais centradas, que tem 1 grau de liberdade por lote,
+// e o combinamos com o estimador das médias em lotes, chegando a 2b - 1 graus de liberdade
+pub fn standardized_time_series_ci(
+    series: &[f64],
+    batch_size: usize,
+    confidence_level: f64,
+) -> (f64, ConfidenceInterval) {
+    let means = batch_means(series, batch_size);
+    let batch_count = means.len();
+    let used_count = batch_count * batch_size;
+    let mean = means.iter().sum::<f64>() / batch_count as f64;
+    let mut area_sum = 0.0;
+    for (batch, &batch_mean) in series.chunks_exact(batch_size).zip(means.iter()) {
+        // Soma de k (Ȳ_m - Ȳ_k) para k = 1, ..., m, onde Ȳ_k é a média dos k primeiros valores
+        let mut partial_sum = 0.0;
+        let mut bridge_sum = 0.0;
+        for (index, &value) in batch.iter().enumerate() {
+            partial_sum += value;
+            bridge_sum += (index + 1) as f64 * batch_mean - partial_sum;
+        }
+        area_sum += 12.0 * bridge_sum.powi(2) / (batch_size as f64).powi(3);
+    }
+    let batch_means_sum = means
+        .iter()
+        .fold(0.0, |sum, &value| sum + (value - mean).powi(2))
+        * batch_size as f64;
+    let degrees_of_freedom = (2 * batch_count - 1) as f64;
+    let variance_parameter = (area_sum + batch_means_sum) / degrees_of_freedom;
+    let half_width = student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, degrees_of_freedom)
+        * (variance_parameter / used_count as f64).sqrt();
+    (
+        mean,
+        ConfidenceInterval::new(mean - half_width, mean + half_width),
+    )
+}
+
+// Métodos de lotes: uma única fila aquecida executa uma rodada longa, que pode ser estendida.
+// Para as variâncias aplicamos o mesmo método sobre a série dos desvios quadráticos em relação
+// à média, e o processo Nq é discretizado em fatias de tempo, uma por freguês atendido
+pub struct BatchMeansAnalysis {
+    queue: Queue,                          // Fila aquecida pela fase transiente
+    method: AnalysisMethod,                // Método de lotes usado para construir os ICs
+    quantile_estimator: QuantileEstimator, // Estimador dos quantis de cada lote
+    transient_phase_size: usize,           // Tamanho da fase transiente descartada
+    w: Sample,                             // Série de W de todos os fregueses
+    t: Sample,                             // Série de T de todos os fregueses
+    x: Sample,                             // Série de X de todos os fregueses
+    nq_slots: Vec<f64>,                    // Médias temporais de Nq em cada fatia de tempo
+    nq_squared_slots: Vec<f64>,            // Médias temporais de Nq² em cada fatia de tempo
+    duration: f64,                         // Duração total observada dos processos N e Nq
+    arrivals: usize,                       // Quantidade de chegadas, contadas pelos aumentos de N
+    busy_time: f64,                        // Tempo total com o servidor ocupado
+    n_integrals: [f64; 2],                 // Integrais de N e N² ao longo do tempo
+    nq_integrals: [f64; 2],                // Integrais de Nq e Nq² ao longo do tempo
+    distributions: Option<Distributions>,  // Distribuições da rodada longa, se pedidas
+    // Séries da análise de autocorrelação, se pedida
+    autocorrelation_series: Option<AutocorrelationSeries>,
+    deferred_output: bool, // Suprime as mensagens de andamento
+}
+
+impl BatchMeansAnalysis {
+    // Séries às quais o método de lotes é aplicado, com as métricas que elas estimam: W, os
+    // desvios quadráticos de W em relação à média, as fatias de Nq e a média temporal de
+    // (Nq - m)² em cada fatia, calculada a partir das médias de Nq e Nq²
+    fn analyzed_series(&self) -> [(&'static str, Cow<'_, [f64]>); 4] {
+        let w_mean = self.w.mean();
+        let w_squared_deviations: Vec<f64> = self
+            .w
+            .values()
+            .iter()
+            .map(|&value| (value - w_mean).powi(2))
+            .collect();
+        let nq_mean = self.nq_integrals[0] / self.duration;
+        let nq_squared_deviations: Vec<f64> = self
+            .nq_slots
+            .iter()
+            .zip(self.nq_squared_slots.iter())
+            .map(|(&mean, &squared_mean)| squared_mean - 2.0 * nq_mean * mean + nq_mean.powi(2))
+            .collect();
+        [
+            ("E[W]", Cow::Borrowed(self.w.values())),
+            ("V(W)", Cow::Owned(w_squared_deviations)),
+            ("E[Nq]", Cow::Borrowed(&self.nq_slots)),
+            ("V(Nq)", Cow::Owned(nq_squared_deviations)),
+        ]
+    }
+
+    // Escolhe o tamanho de lote e aplica o método pedido sobre uma série
+    fn analyze_series(&self, series: &[f64], confidence_level: f64) -> MetricEstimate {
+        let batch_size = select_batch_size(series, confidence_level);
+        let (value, t_student) = match self.method {
+            AnalysisMethod::BatchMeans => batch_means_ci(series, batch_size, confidence_level),
+            AnalysisMethod::OverlappingBatchMeans => {
+                overlapping_batch_means_ci(series, batch_size, confidence_level)
+            }
+            AnalysisMethod::StandardizedTimeSeries => {
+                standardized_time_series_ci(series, batch_size, confidence_level)
+            }
+            AnalysisMethod::Rounds
+            | AnalysisMethod::Replications
+            | AnalysisMethod::Regenerative => {
+                unreachable!(
+                    "Os métodos das rodadas, replicações e regenerativo não usam médias em lotes"
+                )
+            }
+        };
+        MetricEstimate {
+            value,
+            t_student,
+            chi_square: None,
+        }
+    }
+
+    // Instancia a fila e executa sua fase transiente
+    pub fn new(parameters: &SimulationParameters, seed: u64) -> Result<Self, SimulationError> {
+        let (mut queue, transient_phase_size) = warmed_up_queue(parameters, seed, None)?;
+        // As médias em lotes precisam da série de W e das fatias de Nq, e os quantis da série de T
+        select_sample_storages(&mut queue, parameters);
+        let capacity = parameters.round_size * parameters.rounds_count;
+        let x_storage = queue.sample_storage(X);
+        Ok(Self {
+            queue,
+            method: parameters.analysis_method,
+            quantile_estimator: parameters.quantile_estimator,
+            transient_phase_size,
+            w: Sample::new(capacity),
+            t: Sample::new(capacity),
+            x: Sample::with_storage(capacity, x_storage),
+            nq_slots: Vec::with_capacity(capacity),
+            nq_squared_slots: Vec::with_capacity(capacity),
+            duration: 0.0,
+            arrivals: 0,
+            busy_time: 0.0,
+            n_integrals: [0.0; 2],
+            nq_integrals: [0.0; 2],
+            distributions: parameters.export_distributions.then(Distributions::new),
+            autocorrelation_series: parameters
+                .autocorrelation_max_lag
+                .map(|max_lag| AutocorrelationSeries::new(max_lag, parameters.round_size)),
+            deferred_output: parameters.deferred_output,
+        })
+    }
+}
+
+// Integrais de um processo e do seu quadrado ao longo do tempo
+fn first_and_second_integrals(sample: &StochasticProcessSample) -> [f64; 2] {
+    [sample.integral(), sample.squared_integral()]
+}
+
+// Estima todos os quantis de interesse de uma série longa por seccionamento
+pub fn sectioned_quantiles(
+    series: &[f64],
+    estimator: QuantileEstimator,
+    confidence_level: f64,
+) -> Vec<QuantileEstimate> {
+    QUANTILE_PROBABILITIES
+        .iter()
+        .map(|&probability| {
+            sectioning_quantile_estimate(series, probability, estimator, confidence_level)
+        })
+        .collect()
+}
+
+impl OutputAnalysis for BatchMeansAnalysis {
+    // Continua a rodada longa por mais clients fregueses
+    fn extend(&mut self, clients: usize) -> Result<(), SimulationError> {
+        if !self.deferred_output {
+            println!("Executando a rodada longa por mais {} fregueses", clients);
+        }
+        let (samples, stochastic_process_samples) = self.queue.run_one_simulation_round(clients)?;
+        if let Some(distributions) = &mut self.distributions {
+            distributions.append(&samples, &stochastic_process_samples);
+        }
+        if let Some(autocorrelation_series) = &mut self.autocorrelation_series {
+            autocorrelation_series.append(samples[W].values());
+        }
+        self.w.merge(&samples[W]);
+        self.t.merge(&samples[T]);
+        self.x.merge(&samples[X]);
+        let n = &stochastic_process_samples[N];
+        let nq = &stochastic_process_samples[NQ];
+        self.nq_slots
+            .extend(nq.time_slot_averages(clients, |value| value));
+        self.nq_squared_slots
+            .extend(nq.time_slot_averages(clients, |value| value.powi(2)));
+        self.duration += n.duration();
+        self.arrivals += n.upward_jumps();
+        self.busy_time += n.duration() * (1.0 - n.state_probability(0));
+        for (total, integral) in self
+            .n_integrals
+            .iter_mut()
+            .zip(first_and_second_integrals(n).iter())
+        {
+            *total += integral;
+        }
+        for (total, integral) in self
+            .nq_integrals
+            .iter_mut()
+            .zip(first_and_second_integrals(nq).iter())
+        {
+            *total += integral;
+        }
+        Ok(())
+    }
+
+    fn clients(&self) -> usize {
+        self.w.count()
+    }
+
+    fn transient_phase_size(&self) -> usize {
+        self.transient_phase_size
+    }
+
+    fn estimates(&self, confidence_level: f64) -> Estimates {
+        let [(_, w), (_, w_squared_deviations), (_, nq), (_, nq_squared_deviations)] =
+            self.analyzed_series();
+        let n_mean = self.n_integrals[0] / self.duration;
+        Estimates {
+            means_n_t_x: [n_mean, self.t.mean(), self.x.mean()],
+            variances_n_t_x: [
+                self.n_integrals[1] / self.duration - n_mean.powi(2),
+                self.t.variance(),
+                self.x.variance(),
+            ],
+            arrival_rate: self.arrivals as f64 / self.duration,
+            utilization: self.busy_time / self.duration,
+            mean_w: self.analyze_series(&w, confidence_level),
+            variance_w: self.analyze_series(&w_squared_deviations, confidence_level),
+            mean_nq: self.analyze_series(&nq, confidence_level),
+            variance_nq: self.analyze_series(&nq_squared_deviations, confidence_level),
+            quantiles_w: sectioned_quantiles(
+                self.w.values(),
+                self.quantile_estimator,
+                confidence_level,
+            ),
+            quantiles_t: sectioned_quantiles(
+                self.t.values(),
+                self.quantile_estimator,
+                confidence_level,
+            ),
+        }
+    }
+
+    fn distributions(&self) -> Option<&Distributions> {
+        self.distributions.as_ref()
+    }
+
+    fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+        self.autocorrelation_series.as_ref()
+    }
+
+    // Os lotes são escolhidos a cada estimativa, então não há médias de rodadas fixas
+    fn round_means(&self) -> Option<[&[f64]; 2]> {
+        None
+    }
+
+    // Tamanho de lote escolhido para cada série, com a quantidade de lotes e a autocorrelação
+    // lag 1 das médias dos lotes, que deve ser pequena
+    fn diagnostics(&self, confidence_level: f64) -> Vec<String> {
+        self.analyzed_series()
+            .iter()
+            .map(|(name, series)| {
+                let batch_size = select_batch_size(series, confidence_level);
+                format!(
+                    "Tamanho de lote para {} = {} ({} lotes; autocorrelação lag 1 das médias = \
+                     {:0.5})",
+                    name,
+                    batch_size,
+                    series.len() / batch_size,
+                    lag_one_autocorrelation(&batch_means(series, batch_size)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{QueueMode, QueuePolicy};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Assinatura comum dos ICs dos métodos de lotes
+    type BatchEstimator = fn(&[f64], usize, f64) -> (f64, ConfidenceInterval);
+
+    // Tamanho da série e dos lotes usados nos testes
+    const SERIES_LENGTH: usize = 1_000_000;
+    const BATCH_SIZE: usize = 2_000;
+
+    // Série AR(1) Y_k = φ Y_{k-1} + ε_k com ε_k uniforme em [-1/2, 1/2), cuja variância é 1/12.
+    // O parâmetro de variância, lim n V(Ȳ_n), vale V(ε) / (1 - φ)²
+    fn autoregressive_series(phi: f64, seed: u64) -> Vec<f64> {
+        let mut generator = StdRng::seed_from_u64(seed);
+        let mut value = 0.0;
+        (0..SERIES_LENGTH)
+            .map(|_| {
+                value = phi * value + generator.gen::<f64>() - 0.5;
+                value
+            })
+            .collect()
+    }
+
+    // Recupera o parâmetro de variância a partir da meia largura do IC e dos graus de liberdade
+    fn variance_parameter(
+        interval: &ConfidenceInterval,
+        degrees_of_freedom: f64,
+        count: usize,
+    ) -> f64 {
+        let quantile = student_t_quantile(0.975, degrees_of_freedom);
+        count as f64 * (interval.half_width() / quantile).powi(2)
+    }
+
+    // Confere o parâmetro de variância estimado por cada método a menos de 10%
+    fn assert_variance_parameter(phi: f64, expected: f64) {
+        let series = autoregressive_series(phi, 7);
+        let batch_count = SERIES_LENGTH / BATCH_SIZE;
+        let estimators: [(BatchEstimator, f64); 3] = [
+            (batch_means_ci, (batch_count - 1) as f64),
+            (
+                overlapping_batch_means_ci,
+                1.5 * (SERIES_LENGTH as f64 / BATCH_SIZE as f64 - 1.0),
+            ),
+            (standardized_time_series_ci, (2 * batch_count - 1) as f64),
+        ];
+        for (estimator, degrees_of_freedom) in estimators.iter() {
+            let (mean, interval) = estimator(&series, BATCH_SIZE, 0.95);
+            let estimated = variance_parameter(&interval, *degrees_of_freedom, SERIES_LENGTH);
+            assert!(mean.abs() < 0.01, "Média {}", mean);
+            assert!(
+                (estimated / expected - 1.0).abs() < 0.1,
+                "Esperado {}, obtido {}",
+                expected,
+                estimated
+            );
+        }
+    }
+
+    #[test]
+    fn batch_estimators_match_the_iid_variance() {
+        assert_variance_parameter(0.0, 1.0 / 12.0);
+    }
+
+    #[test]
+    fn batch_estimators_match_the_autoregressive_variance_parameter() {
+        assert_variance_parameter(0.5, (1.0 / 12.0) / 0.25);
+    }
+
+    #[test]
+    fn batch_sizes_are_reported_as_diagnostics() {
+        let mut parameters =
+            SimulationParameters::new(0.5, 1000, 10, QueuePolicy::FCFS, QueueMode::ForReal);
+        parameters.analysis_method = AnalysisMethod::BatchMeans;
+        parameters.deferred_output = true;
+        let mut analysis = BatchMeansAnalysis::new(&parameters, 3).unwrap();
+        analysis.extend(10_000).unwrap();
+        let diagnostics = analysis.diagnostics(0.95);
+        assert_eq!(diagnostics.len(), 4);
+        for (line, name) in diagnostics
+            .iter()
+            .zip(["E[W]", "V(W)", "E[Nq]", "V(Nq)"].iter())
+        {
+            assert!(
+                line.starts_with(&format!("Tamanho de lote para {} = ", name)),
+                "{}",
+                line
+            );
+        }
+    }
+}
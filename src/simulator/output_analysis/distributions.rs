@@ -0,0 +1,269 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Estrutura de dados HashMap da biblioteca padrão do Rust
+use std::collections::HashMap;
+
+// Importando algumas constantes da fila
+use crate::simulator::queue::{N, T, W};
+// Importando o histograma e suas classes normalizadas
+use crate::simulator::sample_accumulators::histogram::{Histogram, HistogramBin};
+// Importando a representação do nosso acumulador de amostras de variáveis aleatórias e o modo de
+// armazenamento das amostras
+use crate::simulator::sample_accumulators::sample::{Sample, SampleStorage};
+// Importando a representação do nosso acumulador de amostras de processos estocásticos
+use crate::simulator::sample_accumulators::stochastic_process_sample::StochasticProcessSample;
+
+// Largura das classes dos histogramas de W e T, em unidades do tempo médio de serviço
+const TIME_BIN_WIDTH: f64 = 0.1;
+
+// Distribuições completas coletadas ao longo da simulação: histogramas de W e T por freguês e a
+// distribuição de ocupação P(N = k) ponderada pelo tempo
+#[derive(Serialize, Deserialize)]
+pub struct Distributions {
+    waiting_time: Histogram, // Histograma do tempo de espera W
+    sojourn_time: Histogram, // Histograma do tempo total no sistema T
+    // Trajetória de N de todas as rodadas, acumulada em fluxo
+    occupancy: StochasticProcessSample,
+}
+
+// Uma linha da distribuição exportada, com a classe empírica e os valores analíticos
+pub struct DistributionRow {
+    pub metric: &'static str,      // Nome da métrica
+    pub bin: HistogramBin,         // Classe com sua probabilidade e distribuição empírica
+    pub analytic_probability: f64, // Probabilidade analítica da classe, ou NaN
+    pub analytic_cumulative: f64,  // Distribuição analítica no fim da classe, ou NaN
+}
+
+impl Distributions {
+    // Instancia os histogramas vazios
+    pub fn new() -> Self {
+        Self {
+            waiting_time: Histogram::new(TIME_BIN_WIDTH),
+            sojourn_time: Histogram::new(TIME_BIN_WIDTH),
+            occupancy: StochasticProcessSample::with_storage(0, SampleStorage::Streaming),
+        }
+    }
+
+    // Acumula as amostras de uma rodada, lote ou ciclo
+    pub fn append(
+        &mut self,
+        samples: &HashMap<String, Sample>,
+        stochastic_process_samples: &HashMap<String, StochasticProcessSample>,
+    ) {
+        self.waiting_time
+            .merge(&samples[W].histogram(TIME_BIN_WIDTH));
+        self.sojourn_time
+            .merge(&samples[T].histogram(TIME_BIN_WIDTH));
+        self.occupancy.merge(&stochastic_process_samples[N]);
+    }
+
+    // Acumula as distribuições de outra fila, com a trajetória de N emendada no fim desta
+    pub fn merge(&mut self, other: &Distributions) {
+        self.waiting_time.merge(&other.waiting_time);
+        self.sojourn_time.merge(&other.sojourn_time);
+        self.occupancy.merge(&other.occupancy);
+    }
+
+    // Quantil de N ponderado pelo tempo ao longo de todas as rodadas
+    pub fn occupancy_quantile(&self, probability: f64) -> usize {
+        self.occupancy.quantile(probability)
+    }
+
+    // Monta as linhas das três distribuições. As funções de distribuição analíticas de W e T só
+    // existem para a FCFS, enquanto a de N é a mesma para qualquer política conservativa
+    pub fn rows(
+        &self,
+        rho: f64,
+        analytic_times: bool,
+        analytic_occupancy: bool,
+    ) -> Vec<DistributionRow> {
+        let waiting_time_cdf = |w| mm1_fcfs_waiting_time_cdf(rho, w);
+        let sojourn_time_cdf = |t| mm1_fcfs_sojourn_time_cdf(rho, t);
+        // A classe [k, k + 1) do histograma de N contém só o estado k
+        let occupancy_cdf = |upper_bound: f64| mm1_occupancy_cdf(rho, upper_bound as usize - 1);
+        let mut rows = histogram_rows(
+            W,
+            &self.waiting_time,
+            analytic_times.then_some(&waiting_time_cdf as &dyn Fn(f64) -> f64),
+        );
+        rows.extend(histogram_rows(
+            T,
+            &self.sojourn_time,
+            analytic_times.then_some(&sojourn_time_cdf as &dyn Fn(f64) -> f64),
+        ));
+        rows.extend(histogram_rows(
+            N,
+            &self.occupancy.occupancy_histogram(),
+            analytic_occupancy.then_some(&occupancy_cdf as &dyn Fn(f64) -> f64),
+        ));
+        rows
+    }
+}
+
+// Função interna que monta as linhas de um histograma, com a probabilidade analítica de cada
+// classe e a função de distribuição analítica no seu fim, que ficam como NaN quando não são
+// conhecidas. A probabilidade de cada classe é a diferença para a classe anterior, para que a
+// massa de W em zero fique na primeira classe
+fn histogram_rows(
+    metric: &'static str,
+    histogram: &Histogram,
+    analytic_cdf: Option<&dyn Fn(f64) -> f64>,
+) -> Vec<DistributionRow> {
+    let mut previous_cumulative = 0.0;
+    histogram
+        .bins()
+        .into_iter()
+        .map(|bin| {
+            let (analytic_probability, analytic_cumulative) = match analytic_cdf {
+                Some(cdf) => {
+                    let cumulative = cdf(bin.upper_bound);
+                    let probability = cumulative - previous_cumulative;
+                    previous_cumulative = cumulative;
+                    (probability, cumulative)
+                }
+                None => (f64::NAN, f64::NAN),
+            };
+            DistributionRow {
+                metric,
+                bin,
+                analytic_probability,
+                analytic_cumulative,
+            }
+        })
+        .collect()
+}
+
+// Função de distribuição analítica do tempo de espera W na fila M/M/1 FCFS com μ = 1, que tem
+// massa 1 - ρ em zero: P(W <= w) = 1 - ρ e^(-(1 - ρ) w)
+pub fn mm1_fcfs_waiting_time_cdf(rho: f64, w: f64) -> f64 {
+    if w < 0.0 {
+        0.0
+    } else {
+        1.0 - rho * (-(1.0 - rho) * w).exp()
+    }
+}
+
+// Função de distribuição analítica do tempo total no sistema T na fila M/M/1 FCFS com μ = 1, que é
+// exponencial com taxa 1 - ρ
+pub fn mm1_fcfs_sojourn_time_cdf(rho: f64, t: f64) -> f64 {
+    if t < 0.0 {
+        0.0
+    } else {
+        1.0 - (-(1.0 - rho) * t).exp()
+    }
+}
+
+// Distribuição geométrica do número de fregueses na M/M/1, P(N = k) = (1 - ρ) ρ^k, acumulada até
+// k: P(N <= k) = 1 - ρ^(k + 1)
+pub fn mm1_occupancy_cdf(rho: f64, k: usize) -> f64 {
+    1.0 - rho.powi(k as i32 + 1)
+}
+
+// Quantil da distribuição geométrica de N na M/M/1: o menor k com 1 - ρ^(k + 1) >= p, que só é
+// finito para p < 1
+pub fn mm1_occupancy_quantile(rho: f64, probability: f64) -> usize {
+    assert!(
+        (0.0..1.0).contains(&probability),
+        "A probabilidade {} deve estar em [0, 1)",
+        probability
+    );
+    let mut k = 0;
+    while mm1_occupancy_cdf(rho, k) < probability {
+        k += 1;
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::test_helpers::assert_close;
+
+    // Distribuições com W = 0 e W = 0.25 (T = 1 e T = 1.25), e N = 0 em [0, 1), N = 1 em [1, 3) e
+    // N = 2 em [3, 4)
+    fn distributions() -> Distributions {
+        let mut samples = HashMap::new();
+        let mut waiting_times = Sample::new(2);
+        let mut sojourn_times = Sample::new(2);
+        for &w in [0.0, 0.25].iter() {
+            waiting_times.append(w);
+            sojourn_times.append(w + 1.0);
+        }
+        samples.insert(W.to_string(), waiting_times);
+        samples.insert(T.to_string(), sojourn_times);
+        let mut occupancy = StochasticProcessSample::with_storage(4, SampleStorage::Raw);
+        occupancy.append(0.0, 0);
+        occupancy.append(1.0, 1);
+        occupancy.append(3.0, 2);
+        occupancy.close(4.0);
+        let mut stochastic_process_samples = HashMap::new();
+        stochastic_process_samples.insert(N.to_string(), occupancy);
+        let mut distributions = Distributions::new();
+        distributions.append(&samples, &stochastic_process_samples);
+        distributions
+    }
+
+    // Linhas de uma métrica
+    fn metric_rows<'a>(rows: &'a [DistributionRow], metric: &str) -> Vec<&'a DistributionRow> {
+        rows.iter().filter(|row| row.metric == metric).collect()
+    }
+
+    #[test]
+    fn analytic_probabilities_are_differences_of_the_cdf() {
+        let rho = 0.5;
+        let rows = distributions().rows(rho, true, true);
+        let waiting_time = metric_rows(&rows, W);
+        assert_eq!(waiting_time.len(), 3);
+        // A primeira classe de W leva a massa 1 - ρ em zero junto com a de (0, 0.1]
+        assert_close(
+            waiting_time[0].analytic_probability,
+            mm1_fcfs_waiting_time_cdf(rho, 0.1),
+        );
+        assert!(waiting_time[0].analytic_probability > 1.0 - rho);
+        for pair in waiting_time.windows(2) {
+            assert_close(
+                pair[1].analytic_probability,
+                pair[1].analytic_cumulative - pair[0].analytic_cumulative,
+            );
+        }
+        assert_close(waiting_time[2].bin.probability, 0.5);
+        assert_close(waiting_time[2].bin.cumulative, 1.0);
+    }
+
+    #[test]
+    fn occupancy_bins_hold_a_single_state() {
+        let rho = 0.5;
+        let occupancy = metric_rows(&distributions().rows(rho, false, true), N)
+            .into_iter()
+            .map(|row| (row.bin.probability, row.analytic_probability))
+            .collect::<Vec<_>>();
+        // A classe [k, k + 1) tem a probabilidade geométrica (1 - ρ) ρ^k do estado k
+        assert_eq!(occupancy.len(), 3);
+        for (k, &(probability, analytic_probability)) in occupancy.iter().enumerate() {
+            assert_close(analytic_probability, (1.0 - rho) * rho.powi(k as i32));
+            assert_close(probability, [0.25, 0.5, 0.25][k]);
+        }
+    }
+
+    #[test]
+    fn unknown_distributions_are_nan() {
+        let rows = distributions().rows(0.5, false, false);
+        assert_eq!(metric_rows(&rows, T).len(), 13);
+        assert!(rows
+            .iter()
+            .all(|row| row.analytic_probability.is_nan() && row.analytic_cumulative.is_nan()));
+    }
+
+    #[test]
+    fn analytic_distributions_match_the_mm1() {
+        let rho = 0.8;
+        assert_close(mm1_fcfs_waiting_time_cdf(rho, 0.0), 1.0 - rho);
+        assert_close(mm1_fcfs_waiting_time_cdf(rho, -1.0), 0.0);
+        assert_close(mm1_fcfs_sojourn_time_cdf(rho, 5.0), 1.0 - (-1.0f64).exp());
+        assert_close(mm1_occupancy_cdf(rho, 0), 1.0 - rho);
+        assert_close(mm1_occupancy_cdf(rho, 2), 1.0 - rho.powi(3));
+        // P(N <= 2) = 0.488 e P(N <= 3) = 0.5904
+        assert_eq!(mm1_occupancy_quantile(rho, 0.5), 3);
+        assert_eq!(mm1_occupancy_quantile(rho, 0.1), 0);
+    }
+}
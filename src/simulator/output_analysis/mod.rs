@@ -0,0 +1,173 @@
+// Módulo com a função de autocorrelação e o tempo de autocorrelação integrado das séries de saída
+pub(crate) mod autocorrelation;
+// Módulo com os métodos de médias em lotes sobre uma única rodada longa
+pub(crate) mod batch_means;
+// Módulo com os histogramas de W e T e a distribuição de ocupação de N
+pub(crate) mod distributions;
+// Módulo com a estimação de quantis e seus ICs
+pub(crate) mod quantiles;
+// Módulo com o método regenerativo sobre os ciclos ocupados da fila
+pub(crate) mod regenerative;
+// Módulo com o método das replicações independentes
+pub(crate) mod replications;
+// Módulo com o método das rodadas com fregueses coloridos
+pub(crate) mod rounds;
+// Módulo com a regra de parada sequencial pela precisão dos ICs
+pub(crate) mod sequential;
+
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando a representação do nosso intervalo de confiança
+use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando a representação do nossa fila M/M/1 e os nomes das métricas
+use crate::simulator::queue::{Queue, N, NQ, X};
+// Importando o modo de armazenamento das amostras
+use crate::simulator::sample_accumulators::sample::SampleStorage;
+// Importando a struct com os parâmetros de uma execução do simulador
+use crate::simulator::SimulationParameters;
+// Importando o acumulador das séries usadas na análise de autocorrelação
+use autocorrelation::AutocorrelationSeries;
+// Importando o acumulador das distribuições completas de W, T e N
+use distributions::Distributions;
+// Importando a representação da estimativa de um quantil
+use quantiles::QuantileEstimate;
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
+
+// Enum para selecionar o método de análise de saída usado para construir os intervalos de confiança
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnalysisMethod {
+    // Rodadas com fregueses coloridos, uma estimativa por rodada
+    Rounds,
+    // Médias em lotes não sobrepostos sobre uma única rodada longa
+    BatchMeans,
+    // Médias em lotes sobrepostos sobre uma única rodada longa
+    OverlappingBatchMeans,
+    // Séries temporais padronizadas (estimador de área) combinadas com as médias em lotes
+    StandardizedTimeSeries,
+    // Replicações independentes da fila, cada uma com sua semente e fase transiente
+    Replications,
+    // Ciclos regenerativos que terminam quando o sistema esvazia, sem fase transiente
+    Regenerative,
+}
+
+impl FromStr for AnalysisMethod {
+    type Err = String;
+
+    // Converte o nome do método, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "rounds" => Ok(AnalysisMethod::Rounds),
+            "batch-means" => Ok(AnalysisMethod::BatchMeans),
+            "overlapping-batch-means" => Ok(AnalysisMethod::OverlappingBatchMeans),
+            "standardized-time-series" => Ok(AnalysisMethod::StandardizedTimeSeries),
+            "replications" => Ok(AnalysisMethod::Replications),
+            "regenerative" => Ok(AnalysisMethod::Regenerative),
+            _ => Err(format!("Método de análise desconhecido: {}", name)),
+        }
+    }
+}
+
+impl AnalysisMethod {
+    // Métricas das quais o método só precisa da média e da variância de cada rodada, lote ou
+    // ciclo, e que podem portanto usar acumuladores em fluxo. W e T não entram, pois os quantis
+    // exatos precisam dos seus valores, e as médias em lotes dividem a trajetória de Nq em fatias
+    pub fn streamable_metrics(self) -> &'static [&'static str] {
+        match self {
+            AnalysisMethod::BatchMeans
+            | AnalysisMethod::OverlappingBatchMeans
+            | AnalysisMethod::StandardizedTimeSeries => &[X, N],
+            AnalysisMethod::Rounds
+            | AnalysisMethod::Replications
+            | AnalysisMethod::Regenerative => &[X, N, NQ],
+        }
+    }
+}
+
+// Estimativa pontual de uma métrica e seus intervalos de confiança. O IC pela Chi² só existe
+// quando temos estimativas independentes da variância, como no método das rodadas
+pub struct MetricEstimate {
+    pub value: f64,
+    pub t_student: ConfidenceInterval,
+    pub chi_square: Option<ConfidenceInterval>,
+}
+
+// Estimativas produzidas por um método de análise para todas as métricas de interesse
+pub struct Estimates {
+    pub means_n_t_x: [f64; 3],              // Médias de N, T e X
+    pub variances_n_t_x: [f64; 3],          // Variâncias de N, T e X
+    pub arrival_rate: f64,                  // Taxa de chegadas observada
+    pub utilization: f64,                   // Fração do tempo com o servidor ocupado
+    pub mean_w: MetricEstimate,             // E[W] com seu IC
+    pub variance_w: MetricEstimate,         // V(W) com seus ICs
+    pub mean_nq: MetricEstimate,            // E[Nq] com seu IC
+    pub variance_nq: MetricEstimate,        // V(Nq) com seus ICs
+    pub quantiles_w: Vec<QuantileEstimate>, // Quantis de W com seus ICs
+    pub quantiles_t: Vec<QuantileEstimate>, // Quantis de T com seus ICs
+}
+
+impl Estimates {
+    // Estimativas com ICs, na ordem dos itens a), b), c) e d) do relatório, junto com seus nomes
+    pub fn metrics(&self) -> [(&'static str, &MetricEstimate); 4] {
+        [
+            ("E[W]", &self.mean_w),
+            ("V(W)", &self.variance_w),
+            ("E[Nq]", &self.mean_nq),
+            ("V(Nq)", &self.variance_nq),
+        ]
+    }
+}
+
+// Trait implementada por cada método de análise de saída. O método guarda sua fila (ou filas) e as
+// amostras já coletadas, de forma que a simulação pode ser estendida sem descartar nada
+pub trait OutputAnalysis {
+    // Executa pelo menos mais clients fregueses da simulação, acumulando suas amostras
+    fn extend(&mut self, clients: usize) -> Result<(), SimulationError>;
+    // Quantidade de fregueses já usados nas estimativas
+    fn clients(&self) -> usize;
+    // Tamanho da fase transiente descartada, em média no caso das replicações
+    fn transient_phase_size(&self) -> usize;
+    // Constrói as estimativas e seus ICs a partir de todas as amostras coletadas até agora
+    fn estimates(&self, confidence_level: f64) -> Estimates;
+    // Distribuições completas de W, T e N, quando sua exportação foi pedida nos parâmetros
+    fn distributions(&self) -> Option<&Distributions>;
+    // Séries de W por freguês e por rodada, quando a análise de autocorrelação foi pedida
+    fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries>;
+    // Médias de W e Nq de cada rodada, na ordem em que entram nas estimativas, nos métodos que
+    // executam rodadas ou replicações independentes
+    fn round_means(&self) -> Option<[&[f64]; 2]>;
+    // Linhas que descrevem as escolhas feitas pelo método ao construir as estimativas com o nível
+    // de confiança dado, como os tamanhos de lote, para o relatório da execução
+    fn diagnostics(&self, confidence_level: f64) -> Vec<String>;
+}
+
+// Coloca em modo em fluxo as métricas pedidas nos parâmetros, que a validação já garantiu que o
+// método de análise não precisa dos valores
+pub fn select_sample_storages(queue: &mut Queue, parameters: &SimulationParameters) {
+    for name in parameters.streaming_metrics.iter() {
+        queue.set_sample_storage(name, SampleStorage::Streaming);
+    }
+}
+
+// Instancia o método de análise escolhido nos parâmetros, já com sua fase transiente executada
+pub fn new_analysis(
+    parameters: &SimulationParameters,
+    seed: u64,
+) -> Result<Box<dyn OutputAnalysis>, SimulationError> {
+    Ok(match parameters.analysis_method {
+        AnalysisMethod::Rounds => Box::new(rounds::RoundsAnalysis::new(parameters, seed)?),
+        AnalysisMethod::BatchMeans
+        | AnalysisMethod::OverlappingBatchMeans
+        | AnalysisMethod::StandardizedTimeSeries => {
+            Box::new(batch_means::BatchMeansAnalysis::new(parameters, seed)?)
+        }
+        AnalysisMethod::Replications => {
+            Box::new(replications::ReplicationsAnalysis::new(parameters, seed)?)
+        }
+        AnalysisMethod::Regenerative => {
+            Box::new(regenerative::RegenerativeAnalysis::new(parameters, seed))
+        }
+    })
+}
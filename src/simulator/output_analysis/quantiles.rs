@@ -0,0 +1,161 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando a representação do nosso intervalo de confiança
+use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando o estimador de quantis em fluxo
+use crate::simulator::sample_accumulators::p_square_quantile::PSquareQuantile;
+// Importando as constantes das métricas W e T
+use crate::simulator::queue::{T, W};
+// Importando o quantil exato e o acumulador de amostras de variáveis aleatórias
+use crate::simulator::sample_accumulators::sample::{exact_quantile, Sample, SampleStorage};
+// Importando o quantil da T-Student
+use crate::simulator::statistical_distributions::student_t_quantile;
+// Importando a representação da estimativa de uma métrica
+use super::MetricEstimate;
+// Estrutura de dados HashMap da biblioteca padrão do Rust
+use std::collections::HashMap;
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
+
+// Probabilidades dos quantis estimados para W e T: mediana, p95 e p99
+pub const QUANTILE_PROBABILITIES: [f64; 3] = [0.5, 0.95, 0.99];
+// Quantidade de lotes usada no seccionamento dos quantis de uma série longa
+const SECTIONING_BATCH_COUNT: usize = 30;
+
+// Enum para selecionar como os quantis de cada rodada ou lote são estimados
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QuantileEstimator {
+    // Quantil exato, ordenando os valores guardados
+    Exact,
+    // Estimador em fluxo P². Nas rodadas e replicações seus marcadores são alimentados pelos
+    // coletores da fila a cada freguês, e W e T podem deixar de guardar seus valores. Nos métodos
+    // de uma única rodada longa, que guardam a série de W e T, cada lote é percorrido uma vez
+    PSquare,
+}
+
+impl FromStr for QuantileEstimator {
+    type Err = String;
+
+    // Converte o nome do estimador, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "exact" => Ok(QuantileEstimator::Exact),
+            "p-square" => Ok(QuantileEstimator::PSquare),
+            _ => Err(format!("Estimador de quantis desconhecido: {}", name)),
+        }
+    }
+}
+
+// Estimativa de um quantil com seu IC
+pub struct QuantileEstimate {
+    pub probability: f64,         // Probabilidade do quantil
+    pub estimate: MetricEstimate, // Estimativa pontual e IC pela T-Student
+}
+
+// Calcula o quantil de uma sequência de valores pelo estimador escolhido
+pub fn quantile_of(values: &[f64], probability: f64, estimator: QuantileEstimator) -> f64 {
+    match estimator {
+        QuantileEstimator::Exact => exact_quantile(values, probability),
+        QuantileEstimator::PSquare => PSquareQuantile::from_values(values, probability).quantile(),
+    }
+}
+
+// Acumuladores em fluxo de W e T de uma execução inteira, que acompanham os quantis de
+// QUANTILE_PROBABILITIES pelo P² sem guardar os valores, para as estimativas pontuais
+pub fn pooled_quantile_samples() -> HashMap<String, Sample> {
+    [W, T]
+        .iter()
+        .map(|&name| {
+            let mut sample = Sample::with_storage(0, SampleStorage::Streaming);
+            sample.track_quantiles(&QUANTILE_PROBABILITIES);
+            (name.to_string(), sample)
+        })
+        .collect()
+}
+
+// Quantil de todos os fregueses de várias filas, cada uma com seu acumulador em fluxo. Como os
+// marcadores P² não podem ser combinados, é a média dos quantis de cada fila ponderada pela
+// quantidade de fregueses, que é grande em cada uma
+pub fn pooled_quantile(samples: &[&Sample], probability: f64) -> f64 {
+    let count: usize = samples.iter().map(|sample| sample.count()).sum();
+    samples
+        .iter()
+        .filter(|sample| sample.count() > 0)
+        .map(|sample| sample.streaming_quantile(probability) * sample.count() as f64)
+        .sum::<f64>()
+        / count as f64
+}
+
+// Estimativa de um quantil a partir de rodadas independentes. Como no seccionamento, a estimativa
+// pontual é o quantil de todas as rodadas juntas, já que a média dos quantis de rodadas curtas é
+// enviesada para baixo nas caudas, e a meia largura do IC pela T-Student vem da dispersão dos
+// quantis de cada rodada. O quantil de todas as rodadas vem de acumuladores em fluxo, e não dos
+// valores, que ocupariam memória proporcional ao total de fregueses
+pub fn independent_quantile_estimate(
+    probability: f64,
+    pooled_quantile: f64,
+    round_quantiles: &Sample,
+    confidence_level: f64,
+) -> QuantileEstimate {
+    let half_width = round_quantiles.t_student(confidence_level).half_width();
+    QuantileEstimate {
+        probability,
+        estimate: MetricEstimate {
+            value: pooled_quantile,
+            t_student: ConfidenceInterval::new(
+                pooled_quantile - half_width,
+                pooled_quantile + half_width,
+            ),
+            chi_square: None,
+        },
+    }
+}
+
+// Estimativa de um quantil de uma série longa por seccionamento: a estimativa pontual usa a série
+// inteira, que tem menos viés, e o erro padrão vem da dispersão dos quantis de lotes contíguos,
+// com b - 1 graus de liberdade
+pub fn sectioning_quantile_estimate(
+    series: &[f64],
+    probability: f64,
+    estimator: QuantileEstimator,
+    confidence_level: f64,
+) -> QuantileEstimate {
+    let value = quantile_of(series, probability, estimator);
+    let batch_size = usize::max(1, series.len() / SECTIONING_BATCH_COUNT);
+    let batch_quantiles: Vec<f64> = series
+        .chunks_exact(batch_size)
+        .map(|batch| quantile_of(batch, probability, estimator))
+        .collect();
+    let batch_count = batch_quantiles.len() as f64;
+    let batch_mean = batch_quantiles.iter().sum::<f64>() / batch_count;
+    let variance = batch_quantiles
+        .iter()
+        .fold(0.0, |sum, &quantile| sum + (quantile - batch_mean).powi(2))
+        / (batch_count - 1.0);
+    let half_width = student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, batch_count - 1.0)
+        * (variance / batch_count).sqrt();
+    QuantileEstimate {
+        probability,
+        estimate: MetricEstimate {
+            value,
+            t_student: ConfidenceInterval::new(value - half_width, value + half_width),
+            chi_square: None,
+        },
+    }
+}
+
+// Quantil analítico do tempo de espera W na fila M/M/1 FCFS com μ = 1. Como
+// P(W <= w) = 1 - ρ e^(-(1 - ρ) w), o quantil é zero quando p <= 1 - ρ
+pub fn mm1_fcfs_waiting_time_quantile(rho: f64, probability: f64) -> f64 {
+    if probability <= 1.0 - rho {
+        0.0
+    } else {
+        (rho / (1.0 - probability)).ln() / (1.0 - rho)
+    }
+}
+
+// Quantil analítico do tempo total no sistema T na fila M/M/1 FCFS com μ = 1, que é exponencial
+// com taxa 1 - ρ
+pub fn mm1_fcfs_sojourn_time_quantile(rho: f64, probability: f64) -> f64 {
+    -(1.0 - probability).ln() / (1.0 - rho)
+}
@@ -0,0 +1,450 @@
+// Estrutura de dados HashMap da biblioteca padrão do Rust
+use std::collections::HashMap;
+
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a representação do nosso intervalo de confiança
+use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando a representação do nossa fila M/M/1 e algumas constantes
+use crate::simulator::queue::{Queue, N, NQ, T, W, X};
+// Importando a representação do nosso acumulador de amostras de variáveis aleatórias
+use crate::simulator::sample_accumulators::sample::Sample;
+// Importando a representação do nosso acumulador de amostras de processos estocásticos
+use crate::simulator::sample_accumulators::stochastic_process_sample::StochasticProcessSample;
+// Importando a função que instancia uma fila vazia e os parâmetros de uma execução
+use crate::simulator::{observed_queue, SimulationParameters};
+// Importando o quantil da T-Student
+use crate::simulator::statistical_distributions::student_t_quantile;
+// Importando a barra de progresso das rodadas
+use super::rounds::progress_bar;
+// Importando a representação das estimativas produzidas por um método de análise
+use super::{select_sample_storages, Estimates, MetricEstimate, OutputAnalysis};
+// Importando a estimação dos quantis por seccionamento
+use super::batch_means::sectioned_quantiles;
+use super::quantiles::QuantileEstimator;
+// Importando o acumulador das séries usadas na análise de autocorrelação
+use super::autocorrelation::AutocorrelationSeries;
+// Importando o acumulador das distribuições completas de W, T e N
+use super::distributions::Distributions;
+
+// Quantidade mínima de ciclos para construir os ICs, que têm n - 1 graus de liberdade
+const MINIMUM_CYCLES: usize = 2;
+
+// Função interna que constrói um HashMap para coleta das somas por ciclo de algumas métricas
+fn cycle_sums_hash_map(names: &[&str]) -> HashMap<String, Sample> {
+    names
+        .iter()
+        .map(|name| (name.to_string(), Sample::new(0)))
+        .collect()
+}
+
+// Média simples de um vetor de valores
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Constrói o IC de um estimador a partir dos seus termos linearizados por ciclo, que têm média
+// zero e são independentes entre si por causa da regeneração, com n - 1 graus de liberdade
+fn linearized_ci(
+    value: f64,
+    linearized_terms: &[f64],
+    confidence_level: f64,
+) -> ConfidenceInterval {
+    let cycles_count = linearized_terms.len() as f64;
+    let variance = linearized_terms
+        .iter()
+        .fold(0.0, |sum, &term| sum + term.powi(2))
+        / (cycles_count - 1.0);
+    let half_width = student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, cycles_count - 1.0)
+        * (variance / cycles_count).sqrt();
+    ConfidenceInterval::new(value - half_width, value + half_width)
+}
+
+// Estimador da razão E[Y] / E[L], onde Y é a soma de uma métrica e L o tamanho de cada ciclo.
+// O IC vem do teorema central do limite para Y - r L, cuja variância é
+// V(Y) - 2 r Cov(Y, L) + r² V(L)
+pub fn ratio_estimate(sums: &[f64], lengths: &[f64], confidence_level: f64) -> MetricEstimate {
+    let mean_length = mean(lengths);
+    let ratio = mean(sums) / mean_length;
+    let linearized_terms: Vec<f64> = sums
+        .iter()
+        .zip(lengths)
+        .map(|(&sum, &length)| (sum - ratio * length) / mean_length)
+        .collect();
+    MetricEstimate {
+        value: ratio,
+        t_student: linearized_ci(ratio, &linearized_terms, confidence_level),
+        chi_square: None,
+    }
+}
+
+// Estimador da variância E[Y²] / E[L] - (E[Y] / E[L])², onde Y² é a soma dos quadrados de uma
+// métrica em cada ciclo. O IC vem do método delta aplicado às três somas por ciclo
+pub fn ratio_variance_estimate(
+    squared_sums: &[f64],
+    sums: &[f64],
+    lengths: &[f64],
+    confidence_level: f64,
+) -> MetricEstimate {
+    let mean_length = mean(lengths);
+    let first_moment = mean(sums) / mean_length;
+    let second_moment = mean(squared_sums) / mean_length;
+    let variance = second_moment - first_moment.powi(2);
+    let linearized_terms: Vec<f64> = squared_sums
+        .iter()
+        .zip(sums)
+        .zip(lengths)
+        .map(|((&squared_sum, &sum), &length)| {
+            (squared_sum - 2.0 * first_moment * sum
+                + (2.0 * first_moment.powi(2) - second_moment) * length)
+                / mean_length
+        })
+        .collect();
+    MetricEstimate {
+        value: variance,
+        t_student: linearized_ci(variance, &linearized_terms, confidence_level),
+        chi_square: None,
+    }
+}
+
+// Struct que acumula as somas e tamanhos de cada ciclo regenerativo
+struct CycleStatistics {
+    clients: Sample,   // Quantidade de fregueses atendidos em cada ciclo
+    durations: Sample, // Duração de cada ciclo
+    // Tempo com o servidor ocupado em cada ciclo
+    busy_times: Sample,
+    // Somas de W, T e X dos fregueses de cada ciclo
+    sums: HashMap<String, Sample>,
+    // Somas dos quadrados de W, T e X dos fregueses de cada ciclo
+    squared_sums: HashMap<String, Sample>,
+    // Integrais de N e Nq ao longo de cada ciclo
+    integrals: HashMap<String, Sample>,
+    // Integrais dos quadrados de N e Nq ao longo de cada ciclo
+    squared_integrals: HashMap<String, Sample>,
+    // Valores de W e T de todos os fregueses, usados nos quantis por seccionamento
+    w_values: Sample,
+    t_values: Sample,
+    quantile_estimator: QuantileEstimator, // Estimador dos quantis de cada lote
+}
+
+impl CycleStatistics {
+    // Instancia os acumuladores vazios, a quantidade de ciclos não é conhecida de antemão
+    fn new(quantile_estimator: QuantileEstimator) -> Self {
+        Self {
+            clients: Sample::new(0),
+            durations: Sample::new(0),
+            busy_times: Sample::new(0),
+            sums: cycle_sums_hash_map(&[W, T, X]),
+            squared_sums: cycle_sums_hash_map(&[W, T, X]),
+            integrals: cycle_sums_hash_map(&[N, NQ]),
+            squared_integrals: cycle_sums_hash_map(&[N, NQ]),
+            w_values: Sample::new(0),
+            t_values: Sample::new(0),
+            quantile_estimator,
+        }
+    }
+
+    // Quantidade de ciclos coletados
+    fn cycles_count(&self) -> usize {
+        self.clients.values().len()
+    }
+
+    // Coleta as somas e tamanhos de um ciclo
+    fn append(
+        &mut self,
+        samples: HashMap<String, Sample>,
+        stochastic_process_samples: HashMap<String, StochasticProcessSample>,
+    ) {
+        self.clients.append(samples[W].count() as f64);
+        let n = &stochastic_process_samples[N];
+        self.durations.append(n.duration());
+        self.busy_times
+            .append(n.duration() * (1.0 - n.state_probability(0)));
+        for &value in samples[W].values() {
+            self.w_values.append(value);
+        }
+        for &value in samples[T].values() {
+            self.t_values.append(value);
+        }
+        for (name, sample) in samples {
+            self.sums.get_mut(&name).unwrap().append(sample.sum());
+            self.squared_sums
+                .get_mut(&name)
+                .unwrap()
+                .append(sample.sum_of_squares());
+        }
+        for (name, sample) in stochastic_process_samples {
+            self.integrals
+                .get_mut(&name)
+                .unwrap()
+                .append(sample.integral());
+            self.squared_integrals
+                .get_mut(&name)
+                .unwrap()
+                .append(sample.squared_integral());
+        }
+    }
+
+    // Estimador por freguês de uma métrica de W, T ou X
+    fn client_mean(&self, name: &str, confidence_level: f64) -> MetricEstimate {
+        ratio_estimate(
+            self.sums[name].values(),
+            self.clients.values(),
+            confidence_level,
+        )
+    }
+
+    // Estimador por freguês da variância de uma métrica de W, T ou X
+    fn client_variance(&self, name: &str, confidence_level: f64) -> MetricEstimate {
+        ratio_variance_estimate(
+            self.squared_sums[name].values(),
+            self.sums[name].values(),
+            self.clients.values(),
+            confidence_level,
+        )
+    }
+
+    // Estimador da média temporal de N ou Nq
+    fn time_mean(&self, name: &str, confidence_level: f64) -> MetricEstimate {
+        ratio_estimate(
+            self.integrals[name].values(),
+            self.durations.values(),
+            confidence_level,
+        )
+    }
+
+    // Estimador da variância temporal de N ou Nq
+    fn time_variance(&self, name: &str, confidence_level: f64) -> MetricEstimate {
+        ratio_variance_estimate(
+            self.squared_integrals[name].values(),
+            self.integrals[name].values(),
+            self.durations.values(),
+            confidence_level,
+        )
+    }
+
+    // Constrói as estimativas e seus ICs pelos estimadores da razão
+    fn estimates(&self, confidence_level: f64) -> Estimates {
+        Estimates {
+            means_n_t_x: [
+                self.time_mean(N, confidence_level).value,
+                self.client_mean(T, confidence_level).value,
+                self.client_mean(X, confidence_level).value,
+            ],
+            variances_n_t_x: [
+                self.time_variance(N, confidence_level).value,
+                self.client_variance(T, confidence_level).value,
+                self.client_variance(X, confidence_level).value,
+            ],
+            // Cada freguês atendido num ciclo também chegou nele
+            arrival_rate: self.clients.sum() / self.durations.sum(),
+            utilization: self.busy_times.sum() / self.durations.sum(),
+            mean_w: self.client_mean(W, confidence_level),
+            variance_w: self.client_variance(W, confidence_level),
+            mean_nq: self.time_mean(NQ, confidence_level),
+            variance_nq: self.time_variance(NQ, confidence_level),
+            // Os quantis não são razões de somas por ciclo, então usamos o seccionamento
+            quantiles_w: sectioned_quantiles(
+                self.w_values.values(),
+                self.quantile_estimator,
+                confidence_level,
+            ),
+            quantiles_t: sectioned_quantiles(
+                self.t_values.values(),
+                self.quantile_estimator,
+                confidence_level,
+            ),
+        }
+    }
+}
+
+// Método regenerativo: a fila começa vazia, que já é um ponto de regeneração, e é dividida em
+// ciclos que terminam quando o sistema esvazia. Os ICs vêm dos estimadores da razão entre as
+// somas por ciclo e os tamanhos dos ciclos, sem precisar de fase transiente
+pub struct RegenerativeAnalysis {
+    queue: Queue,                         // Fila vazia no início de cada ciclo
+    statistics: CycleStatistics,          // Somas e tamanhos de cada ciclo
+    served_clients: usize,                // Quantidade de fregueses atendidos nos ciclos
+    distributions: Option<Distributions>, // Distribuições de todos os ciclos, se pedidas
+    // Séries da análise de autocorrelação, se pedida
+    autocorrelation_series: Option<AutocorrelationSeries>,
+    deferred_output: bool, // Esconde a barra de progresso e as mensagens de andamento
+}
+
+impl RegenerativeAnalysis {
+    // Instancia a fila vazia, sem fase transiente
+    pub fn new(parameters: &SimulationParameters, seed: u64) -> Self {
+        let mut queue = observed_queue(parameters, seed);
+        // Os observadores são avisados de uma fase transiente vazia
+        queue.end_warmup(0);
+        // Os quantis por seccionamento precisam dos valores de W e T de cada ciclo
+        select_sample_storages(&mut queue, parameters);
+        Self {
+            queue,
+            statistics: CycleStatistics::new(parameters.quantile_estimator),
+            served_clients: 0,
+            distributions: parameters.export_distributions.then(Distributions::new),
+            autocorrelation_series: parameters
+                .autocorrelation_max_lag
+                .map(|max_lag| AutocorrelationSeries::new(max_lag, parameters.round_size)),
+            deferred_output: parameters.deferred_output,
+        }
+    }
+}
+
+impl OutputAnalysis for RegenerativeAnalysis {
+    // Executa ciclos regenerativos até atender pelo menos mais clients fregueses e fechar pelo
+    // menos 2 ciclos, o mínimo para construir os ICs. Com poucos fregueses e ρ alto um único ciclo
+    // pode atender todos os fregueses pedidos
+    fn extend(&mut self, clients: usize) -> Result<(), SimulationError> {
+        let progress_bar = progress_bar(clients, self.deferred_output);
+        let target = self.served_clients + clients;
+        while self.served_clients < target || self.statistics.cycles_count() < MINIMUM_CYCLES {
+            // Executa um ciclo regenerativo e coleta suas somas
+            let (samples, stochastic_process_samples) = self.queue.run_one_regeneration_cycle()?;
+            let cycle_clients = samples[W].count();
+            self.served_clients += cycle_clients;
+            progress_bar.inc(cycle_clients as u64); // Incremento da barra de progresso
+            if let Some(distributions) = &mut self.distributions {
+                distributions.append(&samples, &stochastic_process_samples);
+            }
+            if let Some(autocorrelation_series) = &mut self.autocorrelation_series {
+                autocorrelation_series.append(samples[W].values());
+            }
+            self.statistics.append(samples, stochastic_process_samples);
+        }
+        progress_bar.finish_with_message("Finalizado"); // Finaliza a barra de progresso
+        if !self.deferred_output {
+            println!(
+                "Ciclos regenerativos = {}; Fregueses por ciclo em média = {:0.5}; \
+                 Duração média = {:0.5}",
+                self.statistics.cycles_count(),
+                self.statistics.clients.mean(),
+                self.statistics.durations.mean()
+            );
+        }
+        Ok(())
+    }
+
+    fn clients(&self) -> usize {
+        self.served_clients
+    }
+
+    fn transient_phase_size(&self) -> usize {
+        0
+    }
+
+    // A extensão garante pelo menos MINIMUM_CYCLES ciclos
+    fn estimates(&self, confidence_level: f64) -> Estimates {
+        self.statistics.estimates(confidence_level)
+    }
+
+    fn distributions(&self) -> Option<&Distributions> {
+        self.distributions.as_ref()
+    }
+
+    fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+        self.autocorrelation_series.as_ref()
+    }
+
+    // Os ciclos têm tamanhos aleatórios e suas estimativas são razões, não médias de rodadas
+    fn round_means(&self) -> Option<[&[f64]; 2]> {
+        None
+    }
+
+    fn diagnostics(&self, _confidence_level: f64) -> Vec<String> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::queue::{QueueMode, QueuePolicy};
+    use crate::simulator::test_helpers::assert_close;
+    use crate::simulator::{AnalysisMethod, GeneratorKind};
+
+    #[test]
+    fn ratio_estimate_matches_hand_computed_interval() {
+        // Razão (1 + 5) / (1 + 1) = 3, com termos linearizados -2 e 2, variância 8 e um grau de
+        // liberdade, então a meia largura é t(0.975, 1) √(8 / 2)
+        let estimate = ratio_estimate(&[1.0, 5.0], &[1.0, 1.0], 0.95);
+        assert_close(estimate.value, 3.0);
+        assert_close(
+            estimate.t_student.half_width(),
+            student_t_quantile(0.975, 1.0) * 2.0,
+        );
+        // Ciclos proporcionais têm todos a mesma razão, e o IC se reduz a um ponto
+        let estimate = ratio_estimate(&[2.0, 4.0, 6.0], &[1.0, 2.0, 3.0], 0.95);
+        assert_close(estimate.value, 2.0);
+        assert_close(estimate.t_student.half_width(), 0.0);
+    }
+
+    #[test]
+    fn ratio_variance_with_unit_cycles_is_the_population_variance() {
+        // Com um valor por ciclo a razão E[Y²] / E[L] - (E[Y] / E[L])² é a variância populacional
+        let values = [1.0, 3.0, 4.0, 8.0];
+        let squares: Vec<f64> = values.iter().map(|value| value * value).collect();
+        let estimate = ratio_variance_estimate(&squares, &values, &[1.0; 4], 0.95);
+        assert_close(estimate.value, 90.0 / 4.0 - 4.0_f64.powi(2));
+    }
+
+    #[test]
+    fn deterministic_cycles_give_exact_estimates() {
+        // Cada ciclo de 9 segundos atende 4 fregueses com W = 0, 1, 2 e 3 e X = 2, e tem
+        // E[N] = 14 / 9 e E[Nq] = 6 / 9. Ciclos idênticos produzem ICs de largura nula
+        let mut queue = Queue::check_correctness(QueuePolicy::FCFS);
+        let mut statistics = CycleStatistics::new(QuantileEstimator::Exact);
+        for _ in 0..10 {
+            let (samples, stochastic_process_samples) = queue.run_one_regeneration_cycle().unwrap();
+            statistics.append(samples, stochastic_process_samples);
+        }
+        assert_eq!(statistics.cycles_count(), 10);
+        assert_close(statistics.clients.mean(), 4.0);
+        assert_close(statistics.durations.mean(), 9.0);
+        let estimates = statistics.estimates(0.95);
+        assert_close(estimates.mean_w.value, 1.5);
+        assert_close(estimates.mean_w.t_student.half_width(), 0.0);
+        assert_close(estimates.variance_w.value, 1.25);
+        assert_close(estimates.mean_nq.value, 6.0 / 9.0);
+        assert_close(estimates.means_n_t_x[0], 14.0 / 9.0);
+        assert_close(estimates.means_n_t_x[2], 2.0);
+        assert_close(estimates.arrival_rate, 4.0 / 9.0);
+        assert_close(estimates.utilization, 8.0 / 9.0);
+    }
+
+    #[test]
+    fn cycles_start_and_end_with_an_empty_system() {
+        // Todo freguês que chega num ciclo também sai nele, e o primeiro encontra o sistema vazio
+        let mut queue = Queue::new(0.8, QueuePolicy::FCFS, 3, GeneratorKind::Std);
+        for _ in 0..200 {
+            let (samples, stochastic_process_samples) = queue.run_one_regeneration_cycle().unwrap();
+            let n = &stochastic_process_samples[N];
+            assert_eq!(n.upward_jumps(), samples[W].count());
+            assert!(samples[W].count() >= 1);
+            assert_close(samples[W].values()[0], 0.0);
+        }
+    }
+
+    #[test]
+    fn few_clients_still_close_two_cycles() {
+        // Com ρ = 0.95 o primeiro ciclo costuma atender bem mais que os 6 fregueses pedidos, e
+        // no modo de corretude cada ciclo atende 4 fregueses, mais que os 2 pedidos
+        let mut high_load =
+            SimulationParameters::new(0.95, 3, 2, QueuePolicy::FCFS, QueueMode::ForReal);
+        high_load.analysis_method = AnalysisMethod::Regenerative;
+        let mut correctness =
+            SimulationParameters::new(0.0, 1, 2, QueuePolicy::FCFS, QueueMode::CheckCorrectness);
+        correctness.analysis_method = AnalysisMethod::Regenerative;
+        for parameters in [high_load, correctness].iter_mut() {
+            parameters.deferred_output = true;
+            parameters.seed = Some(5);
+            let mut analysis = RegenerativeAnalysis::new(parameters, 5);
+            analysis
+                .extend(parameters.round_size * parameters.rounds_count)
+                .unwrap();
+            assert!(analysis.statistics.cycles_count() >= 2);
+            assert!(analysis.estimates(0.95).mean_w.value.is_finite());
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a função que instancia uma fila já aquecida pela fase transiente
+use crate::simulator::warmed_up_queue;
+// Importando a struct com os parâmetros de uma execução do simulador
+use crate::simulator::SimulationParameters;
+// Importando a derivação das sementes de cada replicação
+use crate::simulator::random_number_generator::derived_seed;
+// Importando o aquecimento executado uma única vez para todas as replicações
+use crate::simulator::warmup_detection::execution_truncation;
+// Importando o acumulador das estimativas de cada rodada e a barra de progresso
+use super::rounds::{prepare_round_queue, progress_bar, RoundStatistics};
+// Importando o acumulador das séries usadas na análise de autocorrelação
+use super::autocorrelation::AutocorrelationSeries;
+// Importando o acumulador das distribuições completas de W, T e N
+use super::distributions::Distributions;
+// Importando a representação das estimativas produzidas por um método de análise
+use super::{Estimates, OutputAnalysis};
+
+// Método das replicações independentes: cada replicação instancia sua própria fila, com sua
+// própria semente e fase transiente, e atende round_size fregueses após a fase transiente
+pub struct ReplicationsAnalysis {
+    parameters: SimulationParameters, // Parâmetros usados para instanciar cada fila
+    seed: u64,                        // Semente da qual as replicações são derivadas
+    replications_count: usize,        // Quantidade de replicações já executadas
+    transient_phase_total: usize,     // Soma dos tamanhos das fases transientes
+    // Truncamento das rodadas piloto de Welch, feitas uma única vez com a semente base
+    truncation: Option<usize>,
+    statistics: RoundStatistics, // Estimativas de cada replicação
+}
+
+impl ReplicationsAnalysis {
+    // Instancia o acumulador, as filas são criadas conforme as replicações são executadas
+    pub fn new(parameters: &SimulationParameters, seed: u64) -> Result<Self, SimulationError> {
+        Ok(Self {
+            parameters: parameters.clone(),
+            seed,
+            replications_count: 0,
+            transient_phase_total: 0,
+            truncation: execution_truncation(parameters, seed)?,
+            statistics: RoundStatistics::new(parameters),
+        })
+    }
+}
+
+impl OutputAnalysis for ReplicationsAnalysis {
+    // Executa quantas replicações forem necessárias para atender pelo menos clients fregueses
+    fn extend(&mut self, clients: usize) -> Result<(), SimulationError> {
+        let round_size = self.parameters.round_size;
+        let replications_count = clients.div_ceil(round_size);
+        let progress_bar = progress_bar(replications_count, self.parameters.deferred_output);
+        for _ in 0..replications_count {
+            progress_bar.inc(1); // Incremento da barra de progresso
+
+            // Cada replicação usa uma semente derivada diferente, para que sejam independentes
+            // entre si e das filas piloto da detecção do aquecimento
+            let replication_seed = derived_seed(self.seed, self.replications_count);
+            let (mut queue, transient_phase_size) =
+                warmed_up_queue(&self.parameters, replication_seed, self.truncation)?;
+            prepare_round_queue(&mut queue, &self.parameters);
+            self.transient_phase_total += transient_phase_size;
+            self.replications_count += 1;
+            // Os acumuladores de todas as replicações passam de uma fila para a seguinte
+            self.statistics.lend_pooled_samples(&mut queue);
+            let collectors = queue.run_one_simulation_round(round_size);
+            self.statistics.return_pooled_samples(&mut queue);
+            let (samples, stochastic_process_samples) = collectors?;
+            self.statistics.append(samples, stochastic_process_samples);
+        }
+        progress_bar.finish_with_message("Finalizado"); // Finaliza a barra de progresso
+        if !self.parameters.deferred_output {
+            println!(
+                "Tamanho médio da fase transiente das replicações = {}",
+                self.transient_phase_size()
+            );
+        }
+        Ok(())
+    }
+
+    fn clients(&self) -> usize {
+        self.replications_count * self.parameters.round_size
+    }
+
+    fn transient_phase_size(&self) -> usize {
+        self.transient_phase_total
+            .checked_div(self.replications_count)
+            .unwrap_or(0)
+    }
+
+    fn estimates(&self, confidence_level: f64) -> Estimates {
+        self.statistics.estimates(confidence_level)
+    }
+
+    fn distributions(&self) -> Option<&Distributions> {
+        self.statistics.distributions()
+    }
+
+    fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+        self.statistics.autocorrelation_series()
+    }
+
+    fn round_means(&self) -> Option<[&[f64]; 2]> {
+        Some(self.statistics.round_means())
+    }
+
+    fn diagnostics(&self, _confidence_level: f64) -> Vec<String> {
+        vec![]
+    }
+}
@@ -0,0 +1,692 @@
+// Biblioteca externa que renderiza uma progress bar no terminal conforme as rodadas acontecem
+use indicatif::{ProgressBar, ProgressStyle};
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Estrutura de dados HashMap e threads da biblioteca padrão do Rust
+use std::collections::HashMap;
+use std::mem::take;
+use std::panic;
+use std::path::Path;
+use std::thread;
+
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a representação do nossa fila M/M/1 e algumas constantes
+use crate::simulator::queue::{Queue, N, NQ, T, W, X};
+// Importando a função que instancia uma fila já aquecida e os parâmetros de uma execução
+use crate::simulator::{warmed_up_queue, SimulationParameters};
+// Importando o aquecimento executado uma única vez para todas as filas
+use crate::simulator::warmup_detection::execution_truncation;
+// Importando a derivação das sementes das filas paralelas
+use crate::simulator::random_number_generator::derived_seed;
+// Importando a escrita e a leitura dos checkpoints
+use crate::simulator::checkpoint::{read_checkpoint, write_checkpoint};
+// Importando o acumulador das séries usadas na análise de autocorrelação
+use super::autocorrelation::AutocorrelationSeries;
+// Importando o acumulador das distribuições completas de W, T e N
+use super::distributions::Distributions;
+// Importando a representação do nosso acumulador de amostras de variáveis aleatórias
+use crate::simulator::sample_accumulators::sample::Sample;
+// Importando a representação do nosso acumulador de amostras de processos estocásticos
+use crate::simulator::sample_accumulators::stochastic_process_sample::StochasticProcessSample;
+// Importando a representação das estimativas produzidas por um método de análise
+use super::{select_sample_storages, Estimates, MetricEstimate, OutputAnalysis};
+// Importando a estimação dos quantis
+use super::quantiles::{
+    independent_quantile_estimate, pooled_quantile, pooled_quantile_samples, QuantileEstimate,
+    QuantileEstimator, QUANTILE_PROBABILITIES,
+};
+
+// Função interna que constrói um HashMap para coleta de amostras das métricas N, T e X
+fn statistics_hash_map(rounds_count: usize) -> HashMap<String, Sample> {
+    let mut statistics = HashMap::new();
+    statistics.insert(N.to_string(), Sample::new(rounds_count));
+    statistics.insert(T.to_string(), Sample::new(rounds_count));
+    statistics.insert(X.to_string(), Sample::new(rounds_count));
+    statistics
+}
+
+// Struct que acumula as estimativas de cada rodada (ou replicação) independente, para depois
+// construir os ICs a partir delas
+#[derive(Serialize, Deserialize)]
+pub struct RoundStatistics {
+    // HashMap para coletar médias amostrais de N, T e X por rodada
+    means_statistics: HashMap<String, Sample>,
+    // HashMap para coletar variâncias amostrais de N, T e X por rodada
+    variances_statistics: HashMap<String, Sample>,
+    w_mean_statistics: Sample,      // Acumulador de médias amostrais de W
+    w_variance_statistics: Sample,  // Acumulador de variâncias amostrais de W
+    nq_mean_statistics: Sample,     // Acumulador de médias amostrais de Nq
+    nq_variance_statistics: Sample, // Acumulador de variâncias amostrais de Nq
+    arrival_rates: Sample,          // Acumulador das taxas de chegada observadas
+    utilizations: Sample,           // Acumulador das utilizações observadas
+    // Acumuladores dos quantis amostrais de W e T, um para cada probabilidade
+    w_quantile_statistics: Vec<Sample>,
+    t_quantile_statistics: Vec<Sample>,
+    // Acumuladores em fluxo de W e T de todas as rodadas, um para cada fila, cujos quantis P² são
+    // as estimativas pontuais. O desta fila é o primeiro, e os das outras entram na junção
+    pooled_samples: Vec<HashMap<String, Sample>>,
+    quantile_estimator: QuantileEstimator, // Estimador dos quantis de cada rodada
+    distributions: Option<Distributions>,  // Distribuições de todas as rodadas, se pedidas
+    // Séries da análise de autocorrelação, se pedida
+    autocorrelation_series: Option<AutocorrelationSeries>,
+}
+
+impl RoundStatistics {
+    // Instancia os acumuladores, já sabendo quantas rodadas serão executadas
+    pub fn new(parameters: &SimulationParameters) -> Self {
+        let rounds_count = parameters.rounds_count;
+        let quantile_statistics = || {
+            QUANTILE_PROBABILITIES
+                .iter()
+                .map(|_| Sample::new(rounds_count))
+                .collect()
+        };
+        Self {
+            means_statistics: statistics_hash_map(rounds_count),
+            variances_statistics: statistics_hash_map(rounds_count),
+            w_mean_statistics: Sample::new(rounds_count),
+            w_variance_statistics: Sample::new(rounds_count),
+            nq_mean_statistics: Sample::new(rounds_count),
+            nq_variance_statistics: Sample::new(rounds_count),
+            arrival_rates: Sample::new(rounds_count),
+            utilizations: Sample::new(rounds_count),
+            w_quantile_statistics: quantile_statistics(),
+            t_quantile_statistics: quantile_statistics(),
+            pooled_samples: vec![pooled_quantile_samples()],
+            quantile_estimator: parameters.quantile_estimator,
+            distributions: parameters.export_distributions.then(Distributions::new),
+            autocorrelation_series: parameters
+                .autocorrelation_max_lag
+                .map(|max_lag| AutocorrelationSeries::new(max_lag, parameters.round_size)),
+        }
+    }
+
+    // Empresta à fila os acumuladores de todas as rodadas, para que ela os alimente a cada freguês
+    // das próximas rodadas
+    pub fn lend_pooled_samples(&mut self, queue: &mut Queue) {
+        queue.lend_pooled_samples(take(&mut self.pooled_samples[0]));
+    }
+
+    // Recebe de volta os acumuladores emprestados à fila
+    pub fn return_pooled_samples(&mut self, queue: &mut Queue) {
+        self.pooled_samples[0] = queue.return_pooled_samples();
+    }
+
+    // Coleta as médias e variâncias amostrais das métricas de uma rodada
+    pub fn append(
+        &mut self,
+        samples: HashMap<String, Sample>,
+        stochastic_process_samples: HashMap<String, StochasticProcessSample>,
+    ) {
+        if let Some(distributions) = &mut self.distributions {
+            distributions.append(&samples, &stochastic_process_samples);
+        }
+        if let Some(autocorrelation_series) = &mut self.autocorrelation_series {
+            autocorrelation_series.append(samples[W].values());
+        }
+        // Coleta os quantis amostrais de W e T, exatos a partir dos valores ou pelos marcadores P²
+        // alimentados pelos coletores da fila
+        for (probability_index, &probability) in QUANTILE_PROBABILITIES.iter().enumerate() {
+            let quantile = |sample: &Sample| match self.quantile_estimator {
+                QuantileEstimator::Exact => sample.quantile(probability),
+                QuantileEstimator::PSquare => sample.streaming_quantile(probability),
+            };
+            let w_quantile = quantile(&samples[W]);
+            let t_quantile = quantile(&samples[T]);
+            self.w_quantile_statistics[probability_index].append(w_quantile);
+            self.t_quantile_statistics[probability_index].append(t_quantile);
+        }
+        // Coleta as médias e variâncias amostrais de W, X e T
+        for (name, sample) in samples {
+            if W == name {
+                self.w_mean_statistics.append(sample.mean());
+                self.w_variance_statistics.append(sample.variance());
+            } else {
+                self.means_statistics
+                    .get_mut(&name)
+                    .unwrap()
+                    .append(sample.mean());
+                self.variances_statistics
+                    .get_mut(&name)
+                    .unwrap()
+                    .append(sample.variance());
+            }
+        }
+        // Coleta a taxa de chegadas, contada pelos aumentos de N, e a utilização
+        let n = &stochastic_process_samples[N];
+        self.arrival_rates
+            .append(n.upward_jumps() as f64 / n.duration());
+        self.utilizations.append(1.0 - n.state_probability(0));
+        // Coleta as médias e variâncias amostrais de N e Nq
+        for (name, sample) in stochastic_process_samples {
+            if NQ == name {
+                self.nq_mean_statistics.append(sample.mean());
+                self.nq_variance_statistics.append(sample.variance());
+            } else {
+                self.means_statistics
+                    .get_mut(&name)
+                    .unwrap()
+                    .append(sample.mean());
+                self.variances_statistics
+                    .get_mut(&name)
+                    .unwrap()
+                    .append(sample.variance());
+            }
+        }
+    }
+
+    // Acumula as estimativas das rodadas de outra fila, depois das rodadas desta
+    pub fn merge(&mut self, other: &RoundStatistics) {
+        for (name, sample) in self.means_statistics.iter_mut() {
+            sample.merge(&other.means_statistics[name]);
+        }
+        for (name, sample) in self.variances_statistics.iter_mut() {
+            sample.merge(&other.variances_statistics[name]);
+        }
+        self.w_mean_statistics.merge(&other.w_mean_statistics);
+        self.w_variance_statistics
+            .merge(&other.w_variance_statistics);
+        self.nq_mean_statistics.merge(&other.nq_mean_statistics);
+        self.nq_variance_statistics
+            .merge(&other.nq_variance_statistics);
+        self.arrival_rates.merge(&other.arrival_rates);
+        self.utilizations.merge(&other.utilizations);
+        for (sample, other_sample) in self
+            .w_quantile_statistics
+            .iter_mut()
+            .chain(self.t_quantile_statistics.iter_mut())
+            .zip(
+                other
+                    .w_quantile_statistics
+                    .iter()
+                    .chain(other.t_quantile_statistics.iter()),
+            )
+        {
+            sample.merge(other_sample);
+        }
+        self.pooled_samples
+            .extend(other.pooled_samples.iter().cloned());
+        if let (Some(distributions), Some(other_distributions)) =
+            (&mut self.distributions, &other.distributions)
+        {
+            distributions.merge(other_distributions);
+        }
+        if let (Some(autocorrelation_series), Some(other_series)) = (
+            &mut self.autocorrelation_series,
+            &other.autocorrelation_series,
+        ) {
+            autocorrelation_series.merge(other_series);
+        }
+    }
+
+    // Distribuições acumuladas de todas as rodadas, quando pedidas
+    pub fn distributions(&self) -> Option<&Distributions> {
+        self.distributions.as_ref()
+    }
+
+    // Séries da análise de autocorrelação de todas as rodadas, quando pedida
+    pub fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+        self.autocorrelation_series.as_ref()
+    }
+
+    // Estimativas dos quantis de uma métrica, a partir dos seus acumuladores de todas as rodadas e
+    // dos quantis de cada rodada
+    fn quantiles(
+        &self,
+        name: &str,
+        round_quantiles: &[Sample],
+        confidence_level: f64,
+    ) -> Vec<QuantileEstimate> {
+        let pooled_samples: Vec<&Sample> = self
+            .pooled_samples
+            .iter()
+            .map(|samples| &samples[name])
+            .collect();
+        QUANTILE_PROBABILITIES
+            .iter()
+            .zip(round_quantiles.iter())
+            .map(|(&probability, statistics)| {
+                independent_quantile_estimate(
+                    probability,
+                    pooled_quantile(&pooled_samples, probability),
+                    statistics,
+                    confidence_level,
+                )
+            })
+            .collect()
+    }
+
+    // Médias de W e Nq de cada rodada, na ordem em que foram coletadas
+    pub fn round_means(&self) -> [&[f64]; 2] {
+        [
+            self.w_mean_statistics.values(),
+            self.nq_mean_statistics.values(),
+        ]
+    }
+
+    // Constrói as estimativas e seus ICs a partir das estimativas de cada rodada
+    pub fn estimates(&self, confidence_level: f64) -> Estimates {
+        let w_variance = self.w_variance_statistics.mean();
+        let nq_variance = self.nq_variance_statistics.mean();
+        Estimates {
+            // Média das médias amostrais de cada rodada de N, T e X
+            means_n_t_x: [
+                self.means_statistics[N].mean(),
+                self.means_statistics[T].mean(),
+                self.means_statistics[X].mean(),
+            ],
+            // Média das variâncias amostrais de cada rodada de N, T e X. A variância dessas
+            // variâncias mede só a dispersão entre as rodadas, e não a variância das métricas
+            variances_n_t_x: [
+                self.variances_statistics[N].mean(),
+                self.variances_statistics[T].mean(),
+                self.variances_statistics[X].mean(),
+            ],
+            arrival_rate: self.arrival_rates.mean(),
+            utilization: self.utilizations.mean(),
+            // Item a) do relatório
+            mean_w: MetricEstimate {
+                value: self.w_mean_statistics.mean(),
+                t_student: self.w_mean_statistics.t_student(confidence_level),
+                chi_square: None,
+            },
+            // Item b) do relatório
+            variance_w: MetricEstimate {
+                value: w_variance,
+                t_student: self.w_variance_statistics.t_student(confidence_level),
+                chi_square: Some(
+                    self.w_variance_statistics
+                        .chi_square(w_variance, confidence_level),
+                ),
+            },
+            // Item c) do relatório
+            mean_nq: MetricEstimate {
+                value: self.nq_mean_statistics.mean(),
+                t_student: self.nq_mean_statistics.t_student(confidence_level),
+                chi_square: None,
+            },
+            // Item d) do relatório
+            variance_nq: MetricEstimate {
+                value: nq_variance,
+                t_student: self.nq_variance_statistics.t_student(confidence_level),
+                chi_square: Some(
+                    self.nq_variance_statistics
+                        .chi_square(nq_variance, confidence_level),
+                ),
+            },
+            // Quantis de W e T de todas as rodadas, com ICs a partir dos quantis de cada rodada
+            quantiles_w: self.quantiles(W, &self.w_quantile_statistics, confidence_level),
+            quantiles_t: self.quantiles(T, &self.t_quantile_statistics, confidence_level),
+        }
+    }
+}
+
+// Prepara uma fila já aquecida para as rodadas ou replicações: escolhe o armazenamento das
+// amostras e, com o estimador P², faz os coletores de W e T acompanharem os quantis em fluxo
+pub fn prepare_round_queue(queue: &mut Queue, parameters: &SimulationParameters) {
+    select_sample_storages(queue, parameters);
+    if parameters.quantile_estimator == QuantileEstimator::PSquare {
+        queue.track_quantiles(&QUANTILE_PROBABILITIES);
+    }
+}
+
+// Instancia a barra de progresso que informa o andamento das rodadas ou replicações, escondida
+// quando a saída da execução é adiada
+pub fn progress_bar(length: usize, hidden: bool) -> ProgressBar {
+    if hidden {
+        return ProgressBar::hidden();
+    }
+    let progress_bar = ProgressBar::new(length as u64);
+    progress_bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.white} [{elapsed_precise}] [{bar:40.red/green}] {percent:>3}% {pos:>4}/{len} ({eta_precise})")
+            .progress_chars("🔥💧"),
+    );
+    progress_bar
+}
+
+// Método das rodadas: uma fila aquecida é dividida em rodadas de round_size fregueses coloridos, e
+// cada rodada contribui com uma estimativa de cada métrica. Com parallel_queues filas, cada uma tem
+// sua própria semente derivada e sua própria fase transiente, as rodadas são distribuídas entre
+// elas de forma alternada e cada fila executa as suas numa thread. As estimativas das filas são
+// juntadas sempre na mesma ordem, então o resultado só depende da semente e da quantidade de filas.
+// Todo o estado do método pode ser guardado num checkpoint entre duas rodadas
+#[derive(Serialize, Deserialize)]
+pub struct RoundsAnalysis {
+    parameters: SimulationParameters, // Parâmetros usados para instanciar os acumuladores
+    seed: u64,                        // Semente da execução
+    queues: Vec<Queue>,               // Filas aquecidas pela fase transiente
+    rounds_count: usize,              // Quantidade de rodadas já executadas
+    pending_rounds: usize,            // Rodadas pedidas que ainda não foram executadas
+    transient_phase_size: usize,      // Tamanho médio das fases transientes descartadas
+    statistics: Vec<RoundStatistics>, // Estimativas das rodadas de cada fila
+    // Estimativas de todas as filas juntas, quando há mais de uma, refeitas ao fim de cada extensão
+    #[serde(skip)]
+    merged_statistics: Option<RoundStatistics>,
+}
+
+impl RoundsAnalysis {
+    // Instancia as filas e executa suas fases transientes, cada uma na sua thread. Os parâmetros
+    // já foram validados, então há pelo menos uma fila e um intervalo positivo entre checkpoints
+    pub fn new(parameters: &SimulationParameters, seed: u64) -> Result<Self, SimulationError> {
+        let queues_count = parameters.parallel_queues;
+        // Com uma única fila a semente é usada diretamente, como antes das filas paralelas
+        let seeds: Vec<u64> = if queues_count == 1 {
+            vec![seed]
+        } else {
+            (0..queues_count)
+                .map(|index| derived_seed(seed, index))
+                .collect()
+        };
+        // As filas paralelas compartilham as rodadas piloto de Welch, feitas com a semente base
+        let truncation = execution_truncation(parameters, seed)?;
+        let warmed_up_queues: Vec<(Queue, usize)> = thread::scope(|scope| {
+            let handles: Vec<_> = seeds
+                .iter()
+                .map(|&seed| scope.spawn(move || warmed_up_queue(parameters, seed, truncation)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|error| panic::resume_unwind(error))
+                })
+                .collect::<Result<_, _>>()
+        })?;
+        let transient_phase_total: usize = warmed_up_queues.iter().map(|(_, size)| size).sum();
+        let queues = warmed_up_queues
+            .into_iter()
+            .map(|(mut queue, _)| {
+                prepare_round_queue(&mut queue, parameters);
+                queue
+            })
+            .collect();
+        Ok(Self {
+            parameters: parameters.clone(),
+            seed,
+            queues,
+            rounds_count: 0,
+            pending_rounds: 0,
+            transient_phase_size: transient_phase_total / queues_count,
+            statistics: (0..queues_count)
+                .map(|_| RoundStatistics::new(parameters))
+                .collect(),
+            merged_statistics: None,
+        })
+    }
+
+    // Retoma uma execução a partir do seu checkpoint, com as rodadas que ainda estavam pendentes
+    pub fn from_checkpoint(path: &Path) -> Result<Self, SimulationError> {
+        read_checkpoint(path)
+    }
+
+    // Parâmetros da execução
+    pub fn parameters(&self) -> &SimulationParameters {
+        &self.parameters
+    }
+
+    // Semente da execução
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Quantidade de rodadas já executadas
+    pub fn rounds_count(&self) -> usize {
+        self.rounds_count
+    }
+
+    // Estimativas de todas as rodadas executadas, de todas as filas
+    fn all_statistics(&self) -> &RoundStatistics {
+        self.merged_statistics
+            .as_ref()
+            .unwrap_or(&self.statistics[0])
+    }
+
+    // Executa as próximas rounds_count rodadas, com cada fila executando as suas numa thread.
+    // Quando alguma fila falha, o erro da primeira delas é devolvido
+    fn run_rounds(
+        &mut self,
+        rounds_count: usize,
+        progress_bar: &ProgressBar,
+    ) -> Result<(), SimulationError> {
+        let round_size = self.parameters.round_size;
+        let queues_count = self.queues.len();
+        let rounds = self.rounds_count..self.rounds_count + rounds_count;
+        thread::scope(|scope| {
+            let mut handles = vec![];
+            for (index, (queue, statistics)) in self
+                .queues
+                .iter_mut()
+                .zip(self.statistics.iter_mut())
+                .enumerate()
+            {
+                // A rodada de índice r, contando desde a primeira, é executada pela fila
+                // r % queues_count
+                let queue_rounds = rounds
+                    .clone()
+                    .filter(|round| round % queues_count == index)
+                    .count();
+                // For que executa as rodadas da fila, que alimenta os acumuladores de todas as
+                // rodadas enquanto eles estão emprestados
+                handles.push(scope.spawn(move || {
+                    statistics.lend_pooled_samples(queue);
+                    let rounds = (0..queue_rounds).try_for_each(|_| {
+                        progress_bar.inc(1); // Incremento da barra de progresso
+
+                        // Executa uma rodada da simulação e coleta suas estimativas
+                        let (samples, stochastic_process_samples) =
+                            queue.run_one_simulation_round(round_size)?;
+                        statistics.append(samples, stochastic_process_samples);
+                        Ok(())
+                    });
+                    statistics.return_pooled_samples(queue);
+                    rounds
+                }));
+            }
+            handles.into_iter().try_for_each(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|error| panic::resume_unwind(error))
+            })
+        })?;
+        self.rounds_count += rounds_count;
+        self.pending_rounds -= rounds_count;
+        Ok(())
+    }
+}
+
+impl OutputAnalysis for RoundsAnalysis {
+    // Executa quantas rodadas forem necessárias para atender pelo menos clients fregueses, além
+    // das que ficaram pendentes numa execução retomada. Com checkpoints, as rodadas são executadas
+    // em blocos de checkpoint_interval, e o estado é guardado ao fim de cada bloco
+    fn extend(&mut self, clients: usize) -> Result<(), SimulationError> {
+        self.pending_rounds += clients.div_ceil(self.parameters.round_size);
+        let progress_bar = progress_bar(self.pending_rounds, self.parameters.deferred_output);
+        while self.pending_rounds > 0 {
+            let rounds_count = match self.parameters.checkpoint_path {
+                Some(_) => self.pending_rounds.min(self.parameters.checkpoint_interval),
+                None => self.pending_rounds,
+            };
+            self.run_rounds(rounds_count, &progress_bar)?;
+            if let Some(checkpoint_path) = &self.parameters.checkpoint_path {
+                write_checkpoint(checkpoint_path, self)?;
+            }
+        }
+        progress_bar.finish_with_message("Finalizado"); // Finaliza a barra de progresso
+
+        // Junta as estimativas das filas na ordem das filas
+        if self.queues.len() > 1 {
+            let mut merged_statistics = RoundStatistics::new(&self.parameters);
+            for statistics in self.statistics.iter() {
+                merged_statistics.merge(statistics);
+            }
+            self.merged_statistics = Some(merged_statistics);
+        }
+        Ok(())
+    }
+
+    fn clients(&self) -> usize {
+        self.rounds_count * self.parameters.round_size
+    }
+
+    fn transient_phase_size(&self) -> usize {
+        self.transient_phase_size
+    }
+
+    fn estimates(&self, confidence_level: f64) -> Estimates {
+        self.all_statistics().estimates(confidence_level)
+    }
+
+    fn distributions(&self) -> Option<&Distributions> {
+        self.all_statistics().distributions()
+    }
+
+    fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+        self.all_statistics().autocorrelation_series()
+    }
+
+    // Com filas paralelas, as rodadas de cada fila vêm em sequência, na ordem das filas
+    fn round_means(&self) -> Option<[&[f64]; 2]> {
+        Some(self.all_statistics().round_means())
+    }
+
+    fn diagnostics(&self, _confidence_level: f64) -> Vec<String> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::output_analysis::quantiles::{
+        mm1_fcfs_sojourn_time_quantile, mm1_fcfs_waiting_time_quantile,
+    };
+    use crate::simulator::output_analysis::replications::ReplicationsAnalysis;
+    use crate::simulator::{QueueMode, QueuePolicy};
+
+    // Parâmetros de uma fila M/M/1 FCFS com rodadas curtas, que enviesariam a média dos quantis
+    fn parameters(rho: f64, round_size: usize, rounds_count: usize) -> SimulationParameters {
+        let mut parameters = SimulationParameters::new(
+            rho,
+            round_size,
+            rounds_count,
+            QueuePolicy::FCFS,
+            QueueMode::ForReal,
+        );
+        parameters.deferred_output = true;
+        parameters
+    }
+
+    // Executa as rodadas pedidas nos parâmetros e confere que os ICs dos p99 de W e T contêm os
+    // valores analíticos
+    fn assert_tail_quantiles_cover_the_analytic_values(
+        analysis: &mut dyn OutputAnalysis,
+        parameters: &SimulationParameters,
+    ) {
+        let rho = parameters.rho;
+        analysis
+            .extend(parameters.round_size * parameters.rounds_count)
+            .unwrap();
+        let estimates = analysis.estimates(0.95);
+        let p99_w = estimates.quantiles_w.last().unwrap();
+        let p99_t = estimates.quantiles_t.last().unwrap();
+        assert_eq!(p99_w.probability, 0.99);
+        for (estimate, analytic_value) in [
+            (p99_w, mm1_fcfs_waiting_time_quantile(rho, 0.99)),
+            (p99_t, mm1_fcfs_sojourn_time_quantile(rho, 0.99)),
+        ]
+        .iter()
+        {
+            let interval = estimate.estimate.t_student;
+            assert!(
+                interval.lower_bound() <= *analytic_value
+                    && *analytic_value <= interval.upper_bound(),
+                "p99 analítico = {} fora do IC [{}, {}]",
+                analytic_value,
+                interval.lower_bound(),
+                interval.upper_bound()
+            );
+        }
+    }
+
+    #[test]
+    fn round_quantiles_cover_the_analytic_tail_quantiles() {
+        // Com rodadas de 200 fregueses a média dos p99 de cada rodada ficava em 6.63, com IC
+        // [6.50, 6.76], bem abaixo do p99 real de W, 7.82
+        let parameters = parameters(0.5, 200, 50);
+        let mut analysis = RoundsAnalysis::new(&parameters, 1).unwrap();
+        assert_tail_quantiles_cover_the_analytic_values(&mut analysis, &parameters);
+    }
+
+    // Bits das estimativas de uma análise, para compará-las exatamente
+    fn estimate_bits(analysis: &RoundsAnalysis) -> Vec<u64> {
+        let estimates = analysis.estimates(0.95);
+        let mut values = vec![estimates.arrival_rate, estimates.utilization];
+        values.extend(estimates.means_n_t_x.iter());
+        values.extend(estimates.variances_n_t_x.iter());
+        for (_, metric) in estimates.metrics().iter() {
+            let interval = metric.t_student;
+            values.extend([metric.value, interval.lower_bound(), interval.upper_bound()].iter());
+        }
+        for quantile in estimates.quantiles_w.iter() {
+            values.push(quantile.estimate.value);
+        }
+        for quantile in estimates.quantiles_t.iter() {
+            values.push(quantile.estimate.value);
+        }
+        let [means_w, means_nq] = analysis.all_statistics().round_means();
+        values.extend(means_w.iter().chain(means_nq.iter()));
+        values.iter().map(|value| value.to_bits()).collect()
+    }
+
+    #[test]
+    fn same_seed_and_queues_give_identical_estimates() {
+        for &parallel_queues in [1, 3].iter() {
+            let mut parameters = parameters(0.5, 100, 10);
+            parameters.parallel_queues = parallel_queues;
+            let run = || {
+                let mut analysis = RoundsAnalysis::new(&parameters, 7).unwrap();
+                // Duas extensões, a segunda com rodadas que não se dividem igualmente pelas filas
+                analysis.extend(1000).unwrap();
+                analysis.extend(700).unwrap();
+                estimate_bits(&analysis)
+            };
+            assert_eq!(run(), run(), "{} filas paralelas", parallel_queues);
+        }
+    }
+
+    #[test]
+    fn streamed_quantiles_keep_the_checkpoint_small() {
+        let mut parameters = parameters(0.5, 1000, 10);
+        parameters.quantile_estimator = QuantileEstimator::PSquare;
+        parameters.streaming_metrics = parameters
+            .streamable_metrics()
+            .iter()
+            .map(|metric| metric.to_string())
+            .collect();
+        assert!(parameters.streaming_metrics.contains(&W.to_string()));
+        assert!(parameters.validate().is_ok());
+        let mut analysis = RoundsAnalysis::new(&parameters, 1).unwrap();
+        let mut sizes = vec![];
+        for _ in 0..2 {
+            analysis.extend(10000).unwrap();
+            sizes.push(serde_json::to_string(&analysis).unwrap().len());
+        }
+        // Só as estatísticas de cada rodada crescem, e não os valores de cada freguês
+        assert!(sizes[1] < sizes[0] * 2, "checkpoints de {:?} bytes", sizes);
+        assert!(sizes[1] < 100_000, "checkpoints de {:?} bytes", sizes);
+        let estimates = analysis.estimates(0.95);
+        let median_w = &estimates.quantiles_w[0];
+        assert_eq!(median_w.probability, 0.5);
+        assert!(median_w.estimate.value.is_finite() && median_w.estimate.value > 0.0);
+    }
+
+    #[test]
+    fn replication_quantiles_cover_the_analytic_tail_quantiles() {
+        let parameters = parameters(0.7, 1000, 50);
+        let mut analysis = ReplicationsAnalysis::new(&parameters, 1).unwrap();
+        assert_tail_quantiles_cover_the_analytic_values(&mut analysis, &parameters);
+    }
+}
@@ -0,0 +1,288 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a representação do nosso intervalo de confiança
+use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando o relatório textual de uma execução
+use crate::simulator::report::Report;
+// Importando o modo de simulação da fila e os parâmetros de uma execução
+use crate::simulator::{QueueMode, SimulationParameters};
+// Importando a representação das estimativas e a trait dos métodos de análise
+use super::{Estimates, OutputAnalysis};
+
+// Margem de segurança aplicada à estimativa de quantos fregueses ainda faltam
+const GROWTH_SAFETY_FACTOR: f64 = 1.1;
+// Crescimento da amostra quando os ICs pela T-Student e pela Chi² não convergem
+const CONVERGENCE_GROWTH: f64 = 1.5;
+// Crescimento da amostra quando a precisão não é definida, como num IC de largura nula em torno de
+// uma estimativa nula, ou é negativa, e não dá para estimar quantos fregueses faltam
+const UNDEFINED_PRECISION_GROWTH: f64 = 2.0;
+
+// Enum com o motivo pelo qual a simulação parou de ser estendida
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StopReason {
+    // Todas as métricas atingiram a precisão alvo
+    PrecisionReached,
+    // A quantidade máxima de fregueses foi atingida antes da precisão alvo
+    BudgetExhausted,
+    // No modo de corretude os resultados são determinísticos e não há o que estender
+    CorrectnessCheck,
+}
+
+// Fator pelo qual a amostra precisa crescer para que todas as métricas atinjam a precisão alvo,
+// ou None caso já tenham atingido. Como a meia largura dos ICs decresce com a raiz do tamanho da
+// amostra, uma precisão p exige uma amostra (p / alvo)² vezes maior. Uma precisão que não é um
+// número finito e não negativo nunca é suficiente. Os motivos são escritos no relatório da execução
+fn required_growth(
+    estimates: &Estimates,
+    target_precision: f64,
+    report: &mut Report,
+) -> Option<f64> {
+    let mut growth: f64 = 1.0;
+    for (name, estimate) in estimates.metrics().iter() {
+        let precision = estimate.t_student.precision();
+        if !precision.is_finite() || precision < 0.0 {
+            report.println(&format!(
+                "Precisão do IC pela T-Student de {} = {:0.5}% não é definida",
+                name,
+                100.0 * precision
+            ));
+            growth = growth.max(UNDEFINED_PRECISION_GROWTH);
+        } else if precision > target_precision {
+            report.println(&format!(
+                "Precisão do IC pela T-Student de {} = {:0.5}% não é suficiente",
+                name,
+                100.0 * precision
+            ));
+            growth = growth.max((precision / target_precision).powi(2));
+        }
+        if let Some(chi_square) = estimate.chi_square {
+            if !ConfidenceInterval::check_convergence(chi_square, estimate.t_student) {
+                report.println(&format!(
+                    "Os intervalos de confiança para {} com T-Student e Chi-Square não convergem",
+                    name
+                ));
+                growth = growth.max(CONVERGENCE_GROWTH);
+            }
+        }
+    }
+    if growth > 1.0 {
+        Some(growth)
+    } else {
+        None
+    }
+}
+
+// Regra de parada sequencial: executa round_size * rounds_count fregueses e continua estendendo a
+// simulação, sem descartar as amostras já coletadas, até que todas as métricas atinjam a precisão
+// alvo ou até a quantidade máxima de fregueses. Retorna as estimativas finais e o motivo da parada.
+// Numa análise retomada de um checkpoint, as decisões já tomadas estão nas rodadas executadas e
+// pendentes, então só as pendentes são executadas antes de voltar às verificações. Cada decisão
+// é escrita no relatório da execução
+pub fn run_sequentially(
+    analysis: &mut dyn OutputAnalysis,
+    parameters: &SimulationParameters,
+    resumed: bool,
+    report: &mut Report,
+) -> Result<(Estimates, StopReason), SimulationError> {
+    if resumed {
+        analysis.extend(0)?;
+    } else {
+        analysis.extend(parameters.round_size * parameters.rounds_count)?;
+    }
+    loop {
+        let estimates = analysis.estimates(parameters.confidence_level);
+        if parameters.queue_mode == QueueMode::CheckCorrectness {
+            break Ok((estimates, StopReason::CorrectnessCheck));
+        }
+        let growth = match required_growth(&estimates, parameters.target_precision, report) {
+            Some(growth) => growth,
+            None => break Ok((estimates, StopReason::PrecisionReached)),
+        };
+        let clients = analysis.clients();
+        if clients >= parameters.maximum_clients {
+            break Ok((estimates, StopReason::BudgetExhausted));
+        }
+        // Estimamos quantos fregueses faltam, respeitando o mínimo de uma rodada e o orçamento
+        let additional_clients = ((growth - 1.0) * GROWTH_SAFETY_FACTOR * clients as f64) as usize;
+        let additional_clients = additional_clients
+            .max(parameters.round_size)
+            .min(parameters.maximum_clients - clients);
+        report.println(&format!(
+            "Estendendo a simulação com mais {} fregueses ({} até agora)",
+            additional_clients, clients
+        ));
+        analysis.extend(additional_clients)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::output_analysis::autocorrelation::AutocorrelationSeries;
+    use crate::simulator::output_analysis::distributions::Distributions;
+    use crate::simulator::output_analysis::MetricEstimate;
+    use crate::simulator::QueuePolicy;
+
+    // Método de análise fictício cuja precisão decresce com a raiz da quantidade de fregueses,
+    // valendo initial_precision com 1000 fregueses, e que guarda cada extensão pedida
+    struct FakeAnalysis {
+        initial_precision: f64,
+        clients: usize,
+        extensions: Vec<usize>,
+    }
+
+    impl FakeAnalysis {
+        fn new(initial_precision: f64) -> Self {
+            Self {
+                initial_precision,
+                clients: 0,
+                extensions: vec![],
+            }
+        }
+    }
+
+    impl OutputAnalysis for FakeAnalysis {
+        fn extend(&mut self, clients: usize) -> Result<(), SimulationError> {
+            self.clients += clients;
+            self.extensions.push(clients);
+            Ok(())
+        }
+
+        fn clients(&self) -> usize {
+            self.clients
+        }
+
+        fn transient_phase_size(&self) -> usize {
+            0
+        }
+
+        fn estimates(&self, _confidence_level: f64) -> Estimates {
+            let half_width = self.initial_precision * (1000.0 / self.clients as f64).sqrt();
+            let metric = || MetricEstimate {
+                value: 1.0,
+                t_student: ConfidenceInterval::new(1.0 - half_width, 1.0 + half_width),
+                chi_square: None,
+            };
+            Estimates {
+                means_n_t_x: [0.0; 3],
+                variances_n_t_x: [0.0; 3],
+                arrival_rate: 0.0,
+                utilization: 0.0,
+                mean_w: metric(),
+                variance_w: metric(),
+                mean_nq: metric(),
+                variance_nq: metric(),
+                quantiles_w: vec![],
+                quantiles_t: vec![],
+            }
+        }
+
+        fn distributions(&self) -> Option<&Distributions> {
+            None
+        }
+
+        fn autocorrelation_series(&self) -> Option<&AutocorrelationSeries> {
+            None
+        }
+
+        fn round_means(&self) -> Option<[&[f64]; 2]> {
+            None
+        }
+
+        fn diagnostics(&self, _confidence_level: f64) -> Vec<String> {
+            vec![]
+        }
+    }
+
+    // Parâmetros com 10 rodadas de 100 fregueses, precisão alvo de 5% e o orçamento dado
+    fn parameters(maximum_clients: usize) -> SimulationParameters {
+        let mut parameters =
+            SimulationParameters::new(0.5, 100, 10, QueuePolicy::FCFS, QueueMode::ForReal);
+        parameters.target_precision = 0.05;
+        parameters.maximum_clients = maximum_clients;
+        parameters
+    }
+
+    #[test]
+    fn extends_by_the_estimated_growth_until_the_precision_is_reached() {
+        // Precisão de 10% exige 4 vezes mais fregueses, então pedimos 3 * 1.1 * 1000 a mais
+        let mut analysis = FakeAnalysis::new(0.1);
+        let mut report = Report::new(true);
+        let (_, stop_reason) =
+            run_sequentially(&mut analysis, &parameters(50_000), false, &mut report).unwrap();
+        assert_eq!(stop_reason, StopReason::PrecisionReached);
+        assert_eq!(analysis.extensions, vec![1000, 3300]);
+        let text = report.into_text();
+        assert!(text.contains("Precisão do IC pela T-Student de E[W] = 10.00000%"));
+        assert!(text.contains("Estendendo a simulação com mais 3300 fregueses (1000 até agora)"));
+    }
+
+    #[test]
+    fn extends_by_at_least_one_round() {
+        // Precisão de 5.1% exigiria só 44 fregueses a mais, menos que uma rodada
+        let mut analysis = FakeAnalysis::new(0.051);
+        let (_, stop_reason) = run_sequentially(
+            &mut analysis,
+            &parameters(50_000),
+            false,
+            &mut Report::new(true),
+        )
+        .unwrap();
+        assert_eq!(stop_reason, StopReason::PrecisionReached);
+        assert_eq!(analysis.extensions, vec![1000, 100]);
+    }
+
+    #[test]
+    fn growth_is_clamped_to_the_budget() {
+        // Os 3300 fregueses pedidos são limitados aos 1000 que restam no orçamento, e a simulação
+        // para sem atingir a precisão
+        let mut analysis = FakeAnalysis::new(0.1);
+        let mut report = Report::new(true);
+        let (estimates, stop_reason) =
+            run_sequentially(&mut analysis, &parameters(2000), false, &mut report).unwrap();
+        assert_eq!(stop_reason, StopReason::BudgetExhausted);
+        assert_eq!(analysis.extensions, vec![1000, 1000]);
+        assert!(estimates.mean_w.t_student.precision() > 0.05);
+    }
+
+    #[test]
+    fn undefined_precisions_are_never_enough() {
+        // Uma precisão NaN, de um IC de largura nula em torno de zero, ou negativa dobra a
+        // amostra a cada verificação, até esgotar o orçamento
+        for &initial_precision in [f64::NAN, -0.01].iter() {
+            let mut analysis = FakeAnalysis::new(initial_precision);
+            let mut report = Report::new(true);
+            let (_, stop_reason) =
+                run_sequentially(&mut analysis, &parameters(4000), false, &mut report).unwrap();
+            assert_eq!(stop_reason, StopReason::BudgetExhausted);
+            assert_eq!(analysis.extensions, vec![1000, 1100, 1900]);
+            assert!(report.into_text().contains("não é definida"));
+        }
+    }
+
+    #[test]
+    fn precise_analysis_is_not_extended() {
+        let mut analysis = FakeAnalysis::new(0.01);
+        let mut report = Report::new(true);
+        let (_, stop_reason) =
+            run_sequentially(&mut analysis, &parameters(50_000), false, &mut report).unwrap();
+        assert_eq!(stop_reason, StopReason::PrecisionReached);
+        assert_eq!(analysis.extensions, vec![1000]);
+        assert!(report.into_text().is_empty());
+    }
+
+    #[test]
+    fn resumed_analysis_only_runs_the_pending_rounds() {
+        // A análise retomada já tem seus fregueses, então só as pendentes, nenhuma aqui, rodam
+        let mut analysis = FakeAnalysis::new(0.01);
+        analysis.clients = 1000;
+        run_sequentially(
+            &mut analysis,
+            &parameters(50_000),
+            true,
+            &mut Report::new(true),
+        )
+        .unwrap();
+        assert_eq!(analysis.extensions, vec![0]);
+    }
+}
@@ -0,0 +1,296 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a verificação da lei de conservação entre as políticas
+use crate::simulator::consistency_checks::{conservation_check, ConsistencyCheck};
+// Importando a barra de progresso das rodadas, reaproveitada para contar as execuções, e a
+// representação das estimativas
+use crate::simulator::output_analysis::rounds::progress_bar;
+use crate::simulator::output_analysis::MetricEstimate;
+// Importando a derivação das sementes de cada execução
+use crate::simulator::random_number_generator::derived_seed;
+// Importando a escrita dos resultados de cada execução
+use crate::simulator::results_writer::{check_results_file, write_results};
+// Importando a execução do simulador, sua semente padrão, seus parâmetros, seu resultado e as
+// colunas dos resultados
+use crate::simulator::{
+    clock_seed, results_column_names, run, QueueMode, QueuePolicy, SimulationParameters,
+    SimulationReport,
+};
+// Contador atômico, canal e threads da biblioteca padrão
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+// Executa as simulações independentes de um sweep em até threads threads. A semente de cada
+// execução é derivada da semente dos seus parâmetros, ou de uma tirada do relógio, e da sua posição
+// no sweep, então os resultados não dependem da quantidade de threads. Com mais de uma thread as
+// barras de progresso de cada execução dão lugar a uma única barra com as execuções concluídas, e
+// os relatórios e os resultados são escritos na ordem das execuções assim que ficam prontos.
+// Os parâmetros e os arquivos dos resultados de todas as execuções são validados antes que
+// qualquer uma comece, e a primeira execução que falhar, na ordem do sweep, interrompe as que
+// ainda não começaram. Ao final, a lei de conservação é verificada entre as execuções FCFS e LCFS
+// de mesmo ρ
+pub fn run_sweep(
+    simulations: Vec<SimulationParameters>,
+    threads: usize,
+) -> Result<(), SimulationError> {
+    validate_sweep(&simulations, threads)?;
+    let base_seed = simulations
+        .first()
+        .and_then(|parameters| parameters.seed)
+        .unwrap_or_else(clock_seed);
+    println!(
+        "Sweep de {} execuções em {} threads; Semente base = {}",
+        simulations.len(),
+        threads,
+        base_seed
+    );
+    let simulations: Vec<SimulationParameters> = simulations
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut parameters)| {
+            parameters.seed = Some(derived_seed(parameters.seed.unwrap_or(base_seed), index));
+            parameters.deferred_output = threads > 1;
+            parameters
+        })
+        .collect();
+
+    let mut means_w = Vec::with_capacity(simulations.len());
+    if threads == 1 {
+        for parameters in simulations.iter() {
+            let report = run(parameters)?;
+            write_results(parameters, &report.results)?;
+            means_w.push(report.estimates.mean_w);
+        }
+        print_conservation_checks(&simulations, &means_w);
+        return Ok(());
+    }
+
+    let progress_bar = progress_bar(simulations.len(), false);
+    let next_job = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel::<(usize, Result<SimulationReport, SimulationError>)>();
+    thread::scope(|scope| {
+        for _ in 0..threads.min(simulations.len()) {
+            let sender = sender.clone();
+            let (next_job, simulations) = (&next_job, &simulations);
+            // Cada thread pega a próxima execução ainda não iniciada até que acabem
+            scope.spawn(move || loop {
+                let index = next_job.fetch_add(1, Ordering::Relaxed);
+                if index >= simulations.len() {
+                    break;
+                }
+                let report = run(&simulations[index]);
+                // O envio só falha quando o sweep já foi interrompido por um erro
+                if sender.send((index, report)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Execuções concluídas fora de ordem esperam até que todas as anteriores sejam escritas
+        let mut finished: Vec<Option<Result<SimulationReport, SimulationError>>> =
+            simulations.iter().map(|_| None).collect();
+        let mut next_to_write = 0;
+        for (index, report) in receiver {
+            progress_bar.inc(1);
+            finished[index] = Some(report);
+            while let Some(report) = finished.get_mut(next_to_write).and_then(Option::take) {
+                let written = report.and_then(|report| {
+                    print!("{}", report.text);
+                    write_results(&simulations[next_to_write], &report.results)?;
+                    Ok(report.estimates.mean_w)
+                });
+                match written {
+                    Ok(mean_w) => means_w.push(mean_w),
+                    Err(error) => {
+                        // Nenhuma execução nova começa, e as que estão em andamento são descartadas
+                        next_job.store(simulations.len(), Ordering::Relaxed);
+                        return Err(error);
+                    }
+                }
+                next_to_write += 1;
+            }
+        }
+        Ok(())
+    })?;
+    progress_bar.finish_with_message("Finalizado");
+    print_conservation_checks(&simulations, &means_w);
+    Ok(())
+}
+
+// Pares de execuções FCFS e LCFS com o mesmo ρ, chegadas Poisson e fila estável, para os quais a
+// lei de conservação de Kleinrock vale, com a verificação de cada par na ordem do sweep. Cada
+// execução FCFS é comparada com a primeira LCFS de mesmo ρ
+fn conservation_checks(
+    simulations: &[SimulationParameters],
+    means_w: &[MetricEstimate],
+) -> Vec<(f64, ConsistencyCheck)> {
+    let comparable = |parameters: &SimulationParameters, policy: QueuePolicy| {
+        parameters.queue_policy == policy
+            && parameters.queue_mode == QueueMode::ForReal
+            && parameters.stable()
+    };
+    let runs: Vec<(&SimulationParameters, &MetricEstimate)> =
+        simulations.iter().zip(means_w.iter()).collect();
+    runs.iter()
+        .filter(|(parameters, _)| comparable(parameters, QueuePolicy::FCFS))
+        .filter_map(|(fcfs, fcfs_mean_w)| {
+            runs.iter()
+                .find(|(lcfs, _)| comparable(lcfs, QueuePolicy::LCFS) && lcfs.rho == fcfs.rho)
+                .map(|(_, lcfs_mean_w)| (fcfs.rho, conservation_check(fcfs_mean_w, lcfs_mean_w)))
+        })
+        .collect()
+}
+
+// Imprime a verificação da lei de conservação de cada par de execuções FCFS e LCFS do sweep
+fn print_conservation_checks(simulations: &[SimulationParameters], means_w: &[MetricEstimate]) {
+    let checks = conservation_checks(simulations, means_w);
+    if checks.is_empty() {
+        return;
+    }
+    println!("Lei de conservação entre as políticas:");
+    for (rho, check) in checks.iter() {
+        println!(
+            "	ρ = {}; {} : {:0.5} = {:0.5}\tTolerância = {:0.5}\t{}",
+            rho,
+            check.name(),
+            check.observed(),
+            check.expected(),
+            check.tolerance(),
+            if check.passed() { "OK" } else { "VIOLADA" }
+        );
+    }
+}
+
+// Valida os parâmetros de todas as execuções do sweep e confere que seus resultados podem ser
+// escritos no arquivo, identificando cada problema pelo ρ e pela política da execução
+fn validate_sweep(
+    simulations: &[SimulationParameters],
+    threads: usize,
+) -> Result<(), SimulationError> {
+    let mut problems = vec![];
+    if threads == 0 {
+        problems.push("o sweep precisa de pelo menos uma thread".to_string());
+    }
+    let column_names = results_column_names();
+    for parameters in simulations {
+        match parameters
+            .validate()
+            .and_then(|()| check_results_file(parameters, &column_names))
+        {
+            Ok(()) => (),
+            Err(SimulationError::InvalidParameters(simulation_problems)) => {
+                problems.extend(simulation_problems.iter().map(|problem| {
+                    format!(
+                        "ρ = {}; Política = {:?}: {}",
+                        parameters.rho, parameters.queue_policy, problem
+                    )
+                }))
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(SimulationError::InvalidParameters(problems))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::confidence_interval::ConfidenceInterval;
+    use crate::simulator::results_writer::{read_results, ResultValue, ResultsFormat};
+    use std::env;
+    use std::fs;
+
+    // Sweep curto com ρ crescentes e políticas alternadas, todas com a mesma semente base, que
+    // escreve algumas colunas dos resultados no arquivo temporário dado
+    fn sweep(file_name: &str) -> Vec<SimulationParameters> {
+        let output_path = env::temp_dir().join(file_name);
+        let _ = fs::remove_file(&output_path);
+        [0.2, 0.3, 0.4, 0.5, 0.6]
+            .iter()
+            .enumerate()
+            .map(|(index, &rho)| {
+                let queue_policy = if index % 2 == 0 {
+                    QueuePolicy::FCFS
+                } else {
+                    QueuePolicy::LCFS
+                };
+                let mut parameters =
+                    SimulationParameters::new(rho, 100, 10, queue_policy, QueueMode::ForReal);
+                // Precisão folgada para que a regra de parada não estenda as rodadas
+                parameters.target_precision = 10.0;
+                parameters.seed = Some(11);
+                parameters.output_path = output_path.clone();
+                parameters.output_columns = Some(
+                    ["rho", "policy", "semente", "E[W]", "E[Nq]"]
+                        .iter()
+                        .map(|column| column.to_string())
+                        .collect(),
+                );
+                parameters
+            })
+            .collect()
+    }
+
+    #[test]
+    fn results_are_written_in_sweep_order_with_seeds_independent_of_threads() {
+        let mut contents = vec![];
+        for &threads in [1, 3].iter() {
+            let simulations = sweep(&format!("simulador_ad_sweep_{}.csv", threads));
+            let path = simulations[0].output_path.clone();
+            run_sweep(simulations, threads).unwrap();
+            let records = read_results(&path, ResultsFormat::Csv).unwrap();
+            let rhos: Vec<f64> = records
+                .iter()
+                .map(|record| record.value("rho").unwrap().as_number().unwrap())
+                .collect();
+            assert_eq!(rhos, vec![0.2, 0.3, 0.4, 0.5, 0.6]);
+            // A semente de cada execução vem da base e da posição no sweep
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(
+                    record.value("semente"),
+                    Some(&ResultValue::Integer(derived_seed(11, index)))
+                );
+            }
+            contents.push(fs::read_to_string(&path).unwrap());
+            fs::remove_file(&path).unwrap();
+        }
+        // Com as mesmas sementes as estimativas também são as mesmas
+        assert_eq!(contents[0], contents[1]);
+    }
+
+    #[test]
+    fn conservation_is_checked_between_policies_with_the_same_rho() {
+        let simulations: Vec<SimulationParameters> = [
+            (0.3, QueuePolicy::FCFS),
+            (0.5, QueuePolicy::FCFS),
+            (0.3, QueuePolicy::LCFS),
+            (0.6, QueuePolicy::LCFS),
+            (1.2, QueuePolicy::FCFS),
+            (1.2, QueuePolicy::LCFS),
+        ]
+        .iter()
+        .map(|&(rho, policy)| SimulationParameters::new(rho, 10, 10, policy, QueueMode::ForReal))
+        .collect();
+        let means_w: Vec<MetricEstimate> = [0.4, 1.0, 0.5, 1.5, 5.0, 9.0]
+            .iter()
+            .map(|&value| MetricEstimate {
+                value,
+                t_student: ConfidenceInterval::new(value - 0.01, value + 0.01),
+                chi_square: None,
+            })
+            .collect();
+        // Só ρ = 0.3 tem as duas políticas numa fila estável
+        let checks = conservation_checks(&simulations, &means_w);
+        assert_eq!(checks.len(), 1);
+        let (rho, check) = &checks[0];
+        assert_eq!(*rho, 0.3);
+        assert_eq!((check.expected(), check.observed()), (0.4, 0.5));
+        assert!(!check.passed());
+    }
+}
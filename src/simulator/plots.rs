@@ -0,0 +1,438 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando o modelo analítico da M/M/1, usado nas curvas analíticas
+use crate::simulator::analytic_models::mm1::MM1;
+use crate::simulator::analytic_models::AnalyticModel;
+// Importando a leitura dos resultados das execuções
+use crate::simulator::results_writer::{read_results, ResultsFormat, ResultsRecord};
+// Importando o acumulador usado nos ICs das médias acumuladas
+use crate::simulator::sample_accumulators::sample::Sample;
+// Importando o desenho dos gráficos em SVG
+use crate::simulator::svg_chart::{Chart, Series, SeriesStyle};
+// Importando a política de atendimento e os parâmetros de uma execução
+use crate::simulator::{QueuePolicy, SimulationParameters};
+
+// Criação de diretórios e caminhos de arquivos da biblioteca padrão
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Quantidade de pontos de cada curva analítica
+const ANALYTIC_CURVE_POINTS: usize = 100;
+// Quantidade máxima de pontos de uma série longa, como as médias das rodadas, para que o .svg
+// continue leve. As séries mais longas são amostradas em intervalos regulares
+const MAXIMUM_SERIES_POINTS: usize = 1000;
+
+// Cor das séries de cada política
+fn policy_color(policy: QueuePolicy) -> &'static str {
+    match policy {
+        QueuePolicy::FCFS => "#1f77b4",
+        QueuePolicy::LCFS => "#d62728",
+    }
+}
+
+// Nome da política nos nomes dos arquivos
+fn policy_file_name(policy: QueuePolicy) -> String {
+    format!("{:?}", policy).to_lowercase()
+}
+
+// Métrica desenhada em função de ρ a partir dos resultados das execuções
+struct RhoPlot {
+    file_name: &'static str,           // Nome do arquivo .svg
+    title: &'static str,               // Título do gráfico
+    column: &'static str,              // Coluna da estimativa nos resultados
+    analytic: fn(&MM1) -> Option<f64>, // Valor analítico da M/M/1, quando existe
+}
+
+// Gráficos desenhados a partir dos resultados: E[W] e E[Nq] de cada política e a comparação de
+// V(W) entre FCFS e LCFS, que é onde as políticas diferem
+const RHO_PLOTS: [RhoPlot; 3] = [
+    RhoPlot {
+        file_name: "media_w.svg",
+        title: "E[W] em função de ρ",
+        column: "E[W]",
+        analytic: |model| Some(model.mean_w()),
+    },
+    RhoPlot {
+        file_name: "media_nq.svg",
+        title: "E[Nq] em função de ρ",
+        column: "E[Nq]",
+        analytic: |model| Some(model.mean_nq()),
+    },
+    RhoPlot {
+        file_name: "variancia_w.svg",
+        title: "V(W) em função de ρ: FCFS e LCFS",
+        column: "V(W)",
+        analytic: |model| model.variance_w(),
+    },
+];
+
+// Estimativa de um resultado com o seu IC t-Student, lida das colunas da métrica
+struct PlottedResult {
+    rho: f64,            // Utilização da execução
+    policy: QueuePolicy, // Política de atendimento da execução
+    value: f64,          // Estimativa
+    lower_bound: f64,    // Limite inferior do IC
+    upper_bound: f64,    // Limite superior do IC
+}
+
+// Valor numérico de uma coluna dos resultados, com um erro que diz qual coluna falta
+fn number_column(record: &ResultsRecord, column: &str) -> Result<f64, String> {
+    record
+        .value(column)
+        .and_then(|value| value.as_number())
+        .ok_or_else(|| format!("os resultados não têm a coluna numérica {}", column))
+}
+
+// Estimativas de uma métrica nos resultados das execuções da M/M/1 estável. Resultados do modo de
+// corretude, com ρ = 0, e de filas instáveis não têm curva analítica e são ignorados
+fn plotted_results(records: &[ResultsRecord], column: &str) -> Result<Vec<PlottedResult>, String> {
+    let mut results = vec![];
+    for record in records.iter() {
+        let rho = number_column(record, "rho")?;
+        if !(rho > 0.0 && rho < 1.0) {
+            continue;
+        }
+        let policy = record
+            .value("policy")
+            .and_then(|value| value.as_text())
+            .ok_or_else(|| "os resultados não têm a coluna policy".to_string())?;
+        results.push(PlottedResult {
+            rho,
+            policy: policy.to_lowercase().parse()?,
+            value: number_column(record, column)?,
+            lower_bound: number_column(record, &format!("{}_IC_TS_L", column))?,
+            upper_bound: number_column(record, &format!("{}_IC_TS_U", column))?,
+        });
+    }
+    Ok(results)
+}
+
+// Desenha um gráfico em função de ρ, com as estimativas e seus ICs e a curva analítica de cada
+// política, na ordem em que as políticas aparecem nos resultados
+fn rho_chart(plot: &RhoPlot, results: &[PlottedResult]) -> Chart {
+    let mut chart = Chart::new(plot.title, "ρ", plot.column);
+    let mut policies: Vec<QueuePolicy> = vec![];
+    for result in results.iter() {
+        if !policies.contains(&result.policy) {
+            policies.push(result.policy);
+        }
+    }
+    let maximum_rho = results.iter().map(|result| result.rho).fold(0.0, f64::max);
+    for &policy in policies.iter() {
+        let policy_results: Vec<&PlottedResult> = results
+            .iter()
+            .filter(|result| result.policy == policy)
+            .collect();
+        let color = policy_color(policy);
+        chart.add_series(
+            Series::new(
+                &format!("{:?} simulado", policy),
+                color,
+                SeriesStyle::Markers,
+                policy_results
+                    .iter()
+                    .map(|result| (result.rho, result.value))
+                    .collect(),
+            )
+            .error_bars(
+                policy_results
+                    .iter()
+                    .map(|result| (result.rho, result.lower_bound, result.upper_bound))
+                    .collect(),
+            ),
+        );
+        let curve = (1..=ANALYTIC_CURVE_POINTS)
+            .map(|point| {
+                let rho = maximum_rho * point as f64 / ANALYTIC_CURVE_POINTS as f64;
+                // Acima de ρ = 1, nas filas instáveis, a curva analítica não existe
+                let value = MM1::new(rho, 1.0, policy)
+                    .ok()
+                    .and_then(|model| (plot.analytic)(&model))
+                    .unwrap_or(f64::NAN);
+                (rho, value)
+            })
+            .collect();
+        chart.add_series(Series::new(
+            &format!("{:?} analítico", policy),
+            color,
+            SeriesStyle::DashedLine,
+            curve,
+        ));
+    }
+    chart
+}
+
+// Desenha, a partir dos resultados escritos pelas execuções, os gráficos de E[W] e E[Nq] em função
+// de ρ e a comparação de V(W) entre FCFS e LCFS, com os ICs t-Student das estimativas e as curvas
+// analíticas da M/M/1. Os arquivos são criados no diretório, que é criado caso não exista, e seus
+// caminhos são devolvidos
+pub fn plot_results(
+    path: &Path,
+    format: ResultsFormat,
+    directory: &Path,
+) -> Result<Vec<PathBuf>, SimulationError> {
+    let records = read_results(path, format)?;
+    let invalid_file = |message: String| SimulationError::InvalidFile {
+        path: path.to_path_buf(),
+        message,
+    };
+    let mut charts = vec![];
+    for plot in RHO_PLOTS.iter() {
+        let results = plotted_results(&records, plot.column).map_err(invalid_file)?;
+        if results.is_empty() {
+            return Err(invalid_file(
+                "nenhum resultado com ρ em (0, 1) para desenhar".to_string(),
+            ));
+        }
+        charts.push((plot.file_name, rho_chart(plot, &results)));
+    }
+    fs::create_dir_all(directory).map_err(SimulationError::io(directory))?;
+    let mut paths = vec![];
+    for (file_name, chart) in charts.iter() {
+        let chart_path = directory.join(file_name);
+        chart.write(&chart_path)?;
+        paths.push(chart_path);
+    }
+    Ok(paths)
+}
+
+// Índices de uma série longa que são desenhados, em intervalos regulares e sempre com o último
+fn plotted_indices(length: usize) -> Vec<usize> {
+    let stride = length.div_ceil(MAXIMUM_SERIES_POINTS);
+    let mut indices: Vec<usize> = (0..length).step_by(stride.max(1)).collect();
+    if length > 0 && indices.last() != Some(&(length - 1)) {
+        indices.push(length - 1);
+    }
+    indices
+}
+
+// Desenha a convergência das médias de uma métrica ao longo das rodadas: a média de cada rodada, a
+// média acumulada das rodadas, que é a estimativa do método, com o seu IC t-Student, e o valor
+// analítico, quando existe. Devolve o caminho do arquivo criado no diretório
+pub(crate) fn plot_convergence(
+    directory: &Path,
+    parameters: &SimulationParameters,
+    metric: &str,
+    round_means: &[f64],
+    analytic_value: Option<f64>,
+) -> Result<PathBuf, SimulationError> {
+    let mut chart = Chart::new(
+        &format!(
+            "Convergência de {}: ρ = {}, {:?}",
+            metric, parameters.rho, parameters.queue_policy
+        ),
+        "Rodada",
+        metric,
+    );
+    let indices = plotted_indices(round_means.len());
+    // A média acumulada e o seu IC são calculados com todas as rodadas, mas só desenhados nos
+    // índices escolhidos
+    let mut accumulated = Sample::new(round_means.len());
+    let mut running_means = vec![];
+    let mut lower_bounds = vec![];
+    let mut upper_bounds = vec![];
+    let mut next_index = indices.iter().peekable();
+    for (index, &mean) in round_means.iter().enumerate() {
+        accumulated.append(mean);
+        if next_index.peek() != Some(&&index) {
+            continue;
+        }
+        next_index.next();
+        let round = (index + 1) as f64;
+        running_means.push((round, accumulated.mean()));
+        if accumulated.count() >= 2 {
+            let ci = accumulated.t_student(parameters.confidence_level);
+            lower_bounds.push((round, ci.lower_bound()));
+            upper_bounds.push((round, ci.upper_bound()));
+        }
+    }
+    chart.add_series(Series::new(
+        "Média da rodada",
+        "gray",
+        SeriesStyle::Dots,
+        indices
+            .iter()
+            .map(|&index| ((index + 1) as f64, round_means[index]))
+            .collect(),
+    ));
+    let color = policy_color(parameters.queue_policy);
+    chart.add_series(Series::new(
+        "Média acumulada",
+        color,
+        SeriesStyle::Line,
+        running_means,
+    ));
+    // Os dois limites do IC são uma única série, com um ponto não finito separando as linhas
+    let mut bounds = lower_bounds;
+    bounds.push((f64::NAN, f64::NAN));
+    bounds.extend(upper_bounds);
+    chart.add_series(Series::new(
+        &format!("IC de {}%", 100.0 * parameters.confidence_level),
+        color,
+        SeriesStyle::DashedLine,
+        bounds,
+    ));
+    if let Some(analytic_value) = analytic_value {
+        chart.add_series(Series::new(
+            "Analítico",
+            "black",
+            SeriesStyle::DashedLine,
+            vec![
+                (1.0, analytic_value),
+                (round_means.len() as f64, analytic_value),
+            ],
+        ));
+    }
+    fs::create_dir_all(directory).map_err(SimulationError::io(directory))?;
+    let path = directory.join(format!(
+        "convergencia_{}_rho{}_{}.svg",
+        metric
+            .chars()
+            .filter(|character| character.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase(),
+        parameters.rho,
+        policy_file_name(parameters.queue_policy)
+    ));
+    chart.write(&path)?;
+    Ok(path)
+}
+
+// Desenha as médias móveis de W e N do procedimento de Welch, com os truncamentos encontrados e os
+// valores analíticos de E[W] e E[N], quando existem. Devolve o caminho do arquivo criado no
+// diretório
+pub(crate) fn plot_welch(
+    directory: &Path,
+    parameters: &SimulationParameters,
+    moving_averages: [&[f64]; 2],
+    truncations: [usize; 2],
+    analytic_values: Option<[f64; 2]>,
+) -> Result<PathBuf, SimulationError> {
+    let mut chart = Chart::new(
+        &format!(
+            "Procedimento de Welch: ρ = {}, {:?}",
+            parameters.rho, parameters.queue_policy
+        ),
+        "Freguês",
+        "Média móvel",
+    );
+    let names = ["W", "N"];
+    let colors = ["#1f77b4", "#ff7f0e"];
+    for ((name, color), series) in names.iter().zip(colors.iter()).zip(moving_averages.iter()) {
+        chart.add_series(Series::new(
+            &format!("Média móvel de {}", name),
+            color,
+            SeriesStyle::Line,
+            plotted_indices(series.len())
+                .into_iter()
+                .map(|index| ((index + 1) as f64, series[index]))
+                .collect(),
+        ));
+    }
+    if let Some(analytic_values) = analytic_values {
+        let length = moving_averages[0].len() as f64;
+        for ((name, color), value) in names.iter().zip(colors.iter()).zip(analytic_values.iter()) {
+            chart.add_series(Series::new(
+                &format!("E[{}] analítico", name),
+                color,
+                SeriesStyle::DashedLine,
+                vec![(1.0, *value), (length, *value)],
+            ));
+        }
+    }
+    for (name, truncation) in names.iter().zip(truncations.iter()) {
+        chart.add_vertical_line(*truncation as f64, &format!("truncamento de {}", name));
+    }
+    fs::create_dir_all(directory).map_err(SimulationError::io(directory))?;
+    let path = directory.join(format!(
+        "welch_rho{}_{}.svg",
+        parameters.rho,
+        policy_file_name(parameters.queue_policy)
+    ));
+    chart.write(&path)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::results_writer::write_results;
+    use crate::simulator::QueueMode;
+    use std::env;
+
+    #[test]
+    fn long_series_are_thinned_keeping_the_last_point() {
+        assert_eq!(plotted_indices(3), vec![0, 1, 2]);
+        let indices = plotted_indices(3200);
+        assert!(indices.len() <= MAXIMUM_SERIES_POINTS + 1);
+        assert_eq!(indices[1], 4);
+        assert_eq!(indices.last(), Some(&3199));
+    }
+
+    #[test]
+    fn results_are_plotted_against_rho_for_each_policy() {
+        let directory = env::temp_dir().join("simulador_ad_plots");
+        let mut parameters =
+            SimulationParameters::new(0.5, 10, 10, QueuePolicy::FCFS, QueueMode::ForReal);
+        parameters.output_path = directory.join("resultados.jsonl");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        for &(rho, policy) in [
+            (0.3, "FCFS"),
+            (0.3, "LCFS"),
+            (0.6, "FCFS"),
+            (0.6, "LCFS"),
+            (0.0, "FCFS"),
+        ]
+        .iter()
+        {
+            let mut record = ResultsRecord::new();
+            record.number("rho", rho);
+            record.text("policy", policy);
+            for column in ["E[W]", "E[Nq]", "V(W)"].iter() {
+                record.number(column, 2.0 * rho);
+                record.number(&format!("{}_IC_TS_L", column), rho);
+                record.number(&format!("{}_IC_TS_U", column), 3.0 * rho);
+            }
+            write_results(&parameters, &record).unwrap();
+        }
+        let paths = plot_results(
+            &parameters.output_path,
+            ResultsFormat::JsonLines,
+            &directory,
+        )
+        .unwrap();
+        assert_eq!(paths.len(), RHO_PLOTS.len());
+        let variance = fs::read_to_string(directory.join("variancia_w.svg")).unwrap();
+        // Duas estimativas com IC por política, ignorando a execução com ρ = 0
+        assert_eq!(variance.matches("<path").count(), 4);
+        assert!(variance.contains("LCFS analítico"));
+
+        // Uma coluna ausente é apontada no erro
+        let mut record = ResultsRecord::new();
+        record.number("rho", 0.5);
+        record.text("policy", "FCFS");
+        parameters.output_path = directory.join("incompletos.csv");
+        write_results(&parameters, &record).unwrap();
+        let error =
+            plot_results(&parameters.output_path, ResultsFormat::Csv, &directory).unwrap_err();
+        assert!(error.to_string().contains("E[W]"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn convergence_plot_ends_at_the_estimate() {
+        let directory = env::temp_dir().join("simulador_ad_convergence");
+        let parameters =
+            SimulationParameters::new(0.5, 10, 10, QueuePolicy::LCFS, QueueMode::ForReal);
+        let round_means: Vec<f64> = (0..2500).map(|round| (round % 5) as f64).collect();
+        let path =
+            plot_convergence(&directory, &parameters, "E[W]", &round_means, Some(1.0)).unwrap();
+        assert_eq!(path, directory.join("convergencia_ew_rho0.5_lcfs.svg"));
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        // Dois trechos do IC, a média acumulada e a linha analítica
+        assert_eq!(svg.matches("<polyline").count(), 4);
+        assert!(svg.contains("IC de 95%"));
+    }
+}
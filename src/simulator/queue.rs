@@ -1,16 +1,26 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
 // Importando várias das nossas construções
 use crate::simulator::client::Client;
 use crate::simulator::exponential_time_generator::ExponentialTime;
-use crate::simulator::sample_accumulators::sample::Sample;
+use crate::simulator::queue_observer::QueueObserver;
+use crate::simulator::random_number_generator::GeneratorKind;
+use crate::simulator::sample_accumulators::sample::{Sample, SampleStorage};
 use crate::simulator::sample_accumulators::stochastic_process_sample::StochasticProcessSample;
 
 // Estruturas HashMap e VecDeque (vetor que podemos adicionar e remover no começo e no fim em O(1)
 use std::collections::{HashMap, VecDeque};
-// Troca dois valores de lugar na memória, utilizado para lidar com o Borrow Checker do Rust
-use std::mem::swap;
+// Troca dois valores de lugar na memória, utilizado para lidar com o Borrow Checker do Rust, e
+// retira um valor deixando o padrão no lugar
+use std::mem::{swap, take};
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
 
 // Struct para representar um evento, que possui um nome,
 // um momento de quando ele aconteceu e sua duração
+#[derive(Serialize, Deserialize)]
 struct Event {
     name: String,
     birth_time: f64,
@@ -20,20 +30,48 @@ struct Event {
 // Enum para representar a política de atendimento da fila, o derive é uma anotação que
 // faz o compilador dar algumas características para o enum, Debug permite que o mesmo possa ser
 // impresso num println, copy e clone permitem que o mesmo possa ser copiado de um lugar para outro
-#[derive(Debug, Copy, Clone)]
+// e PartialEq permite comparar as políticas, como ao separar os resultados de cada uma
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QueuePolicy {
     FCFS,
     LCFS,
 }
 
+impl FromStr for QueuePolicy {
+    type Err = String;
+
+    // Converte o nome da política, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "fcfs" => Ok(QueuePolicy::FCFS),
+            "lcfs" => Ok(QueuePolicy::LCFS),
+            _ => Err(format!("Política de atendimento desconhecida: {}", name)),
+        }
+    }
+}
+
 // Enum para determinar em qual modo estamos rodando o simulador, no modo para valer ou no
 // modo de verificar a corretude do mesmo, com chegadas e tempo de serviço deterministicoPra
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QueueMode {
     ForReal,
     CheckCorrectness
 }
 
+impl FromStr for QueueMode {
+    type Err = String;
+
+    // Converte o nome do modo, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "for-real" => Ok(QueueMode::ForReal),
+            "check-correctness" => Ok(QueueMode::CheckCorrectness),
+            _ => Err(format!("Modo de simulação desconhecido: {}", name)),
+        }
+    }
+}
+
 // Constantes das métricas de interesse
 pub const NQ: &str = "Nq";
 pub const N: &str = "N";
@@ -41,11 +79,18 @@ pub const W: &str = "W";
 pub const X: &str = "X";
 pub const T: &str = "T";
 
+// Coletores de amostras de uma rodada ou ciclo: variáveis aleatórias e processos estocásticos
+pub type SampleCollectors = (HashMap<String, Sample>, HashMap<String, StochasticProcessSample>);
+
+// Taxa de serviço do servidor, a utilização esperada da fila é lambda / SERVICE_RATE
+pub const SERVICE_RATE: f64 = 1.0;
+
 // Constantes dos tipos de evento que estamos interessados
 const CLIENT_ARRIVAL: &str = "client_arrival";
 const END_OF_SERVICE: &str = "end_of_service";
 
 // Struct que representa nossa fila M/M/1
+#[derive(Serialize, Deserialize)]
 pub struct Queue {
     samples: HashMap<String, Sample>, // Acumulador de amostras de variáveis aleatórias
     // Acumulador de amostras de processos estocásticos
@@ -60,13 +105,29 @@ pub struct Queue {
     exponential_time_generator: ExponentialTime, // Gerador de amostras exponenciais
     color: usize,                                // Cor da fila na rodada atual
     mode: QueueMode,                              // O modo de funcionamento da fila
+    // Modo de armazenamento de cada métrica, as que não estão aqui guardam todos os valores
+    sample_storages: HashMap<String, SampleStorage>,
+    // Probabilidades dos quantis de W e T acompanhados em fluxo pelos coletores de cada rodada
+    tracked_quantiles: Vec<f64>,
+    // Acumuladores de W e T emprestados pelo método de análise, que recebem os valores de todas
+    // as rodadas além dos coletores de cada uma
+    pooled_samples: HashMap<String, Sample>,
+    next_client_id: usize, // Identificador do próximo freguês a chegar
+    // Observadores dos eventos da fila, que não fazem parte do estado guardado nos checkpoints
+    #[serde(skip)]
+    observers: Vec<Box<dyn QueueObserver>>,
 }
 
 impl Queue {
-    // Instancia uma nova fila, de acordo com o lambda, política de atendimento e semente
-    pub fn new(lambda: f64, queue_policy: QueuePolicy, seed: u64) -> Self {
+    // Instancia uma nova fila, de acordo com o lambda, política de atendimento, semente e gerador
+    pub fn new(
+        lambda: f64,
+        queue_policy: QueuePolicy,
+        seed: u64,
+        generator_kind: GeneratorKind,
+    ) -> Self {
         // Instancia o gerador de amostras exponenciais
-        let mut exponential_time_generator = ExponentialTime::new(seed);
+        let mut exponential_time_generator = ExponentialTime::new(seed, generator_kind);
         // Calcula quando será o primeiro evento de chegada
         let first_event_duration = exponential_time_generator.get(lambda);
         let mut queue = Self {
@@ -82,6 +143,11 @@ impl Queue {
             exponential_time_generator,
             color: 0,
             mode: QueueMode::ForReal,
+            sample_storages: HashMap::new(),
+            tracked_quantiles: vec![],
+            pooled_samples: HashMap::new(),
+            next_client_id: 0,
+            observers: vec![],
         };
         // Adiciona o evento da primeira chegada
         queue.add_event(CLIENT_ARRIVAL, first_event_duration);
@@ -98,24 +164,91 @@ impl Queue {
             client_in_service: None,
             past_events: vec![],
             current_time: 0.0,
-            exponential_time_generator: ExponentialTime::new(0), // Não é usado
+            exponential_time_generator: ExponentialTime::new(0, GeneratorKind::Std), // Não é usado
             color: 0,
             mode: QueueMode::CheckCorrectness,
+            sample_storages: HashMap::new(),
+            tracked_quantiles: vec![],
+            pooled_samples: HashMap::new(),
+            next_client_id: 0,
+            observers: vec![],
         }
     }
 
+    // Acrescenta um observador, que passa a receber os eventos da fila a partir de agora
+    pub fn add_observer(&mut self, observer: Box<dyn QueueObserver>) {
+        self.observers.push(observer);
+    }
+
+    // Repassa um evento a todos os observadores da fila
+    fn notify_observers<F: FnMut(&mut dyn QueueObserver)>(&mut self, mut notify: F) {
+        for observer in self.observers.iter_mut() {
+            notify(observer.as_mut());
+        }
+    }
+
+    // Avisa os observadores do fim da fase transiente, qualquer que tenha sido o procedimento
+    pub(crate) fn end_warmup(&mut self, transient_phase_size: usize) {
+        let current_time = self.current_time;
+        self.notify_observers(|observer| {
+            observer.on_warmup_end(transient_phase_size, current_time)
+        });
+    }
+
+    // Avisa os observadores do fim da rodada ou do ciclo regenerativo atual
+    fn end_round(&mut self) {
+        let (color, current_time) = (self.color, self.current_time);
+        self.notify_observers(|observer| observer.on_round_end(color, current_time));
+    }
+
+    // Escolhe como os coletores de uma métrica guardam suas amostras nas próximas rodadas ou ciclos
+    pub fn set_sample_storage(&mut self, name: &str, storage: SampleStorage) {
+        self.sample_storages.insert(name.to_string(), storage);
+    }
+
+    // Modo de armazenamento de uma métrica, por padrão todos os valores são guardados
+    pub fn sample_storage(&self, name: &str) -> SampleStorage {
+        *self.sample_storages.get(name).unwrap_or(&SampleStorage::Raw)
+    }
+
+    // Acompanha em fluxo os quantis de W e T das probabilidades dadas nos coletores das próximas
+    // rodadas ou ciclos, sem depender dos valores guardados
+    pub fn track_quantiles(&mut self, probabilities: &[f64]) {
+        self.tracked_quantiles = probabilities.to_vec();
+    }
+
+    // Empresta à fila acumuladores de W e T que recebem os valores de cada freguês das próximas
+    // rodadas, até serem devolvidos
+    pub fn lend_pooled_samples(&mut self, pooled_samples: HashMap<String, Sample>) {
+        self.pooled_samples = pooled_samples;
+    }
+
+    // Devolve os acumuladores emprestados, já com os valores das rodadas executadas
+    pub fn return_pooled_samples(&mut self) -> HashMap<String, Sample> {
+        take(&mut self.pooled_samples)
+    }
+
     // Inicializa os coletores de amostras das métricas de interesse
     fn initialize_sample_collectors(&mut self, num_samples: usize) {
         let mut samples = HashMap::with_capacity(3);
-        samples.insert(W.to_string(), Sample::new(num_samples));
-        samples.insert(X.to_string(), Sample::new(num_samples));
-        samples.insert(T.to_string(), Sample::new(num_samples));
+        for &name in [W, X, T].iter() {
+            let storage = self.sample_storage(name);
+            let mut sample = Sample::with_storage(num_samples, storage);
+            if name != X {
+                sample.track_quantiles(&self.tracked_quantiles);
+            }
+            samples.insert(name.to_string(), sample);
+        }
         self.samples = samples;
 
         let mut stochastic_process_samples = HashMap::with_capacity(2);
-        stochastic_process_samples
-            .insert(NQ.to_string(), StochasticProcessSample::new(num_samples));
-        stochastic_process_samples.insert(N.to_string(), StochasticProcessSample::new(num_samples));
+        for &name in [NQ, N].iter() {
+            let storage = self.sample_storage(name);
+            stochastic_process_samples.insert(
+                name.to_string(),
+                StochasticProcessSample::with_storage(num_samples, storage),
+            );
+        }
         self.stochastic_process_samples = stochastic_process_samples;
     }
 
@@ -138,12 +271,21 @@ impl Queue {
     }
 
     // Registra as métricas de W, X e T do freguês nos coletores de amostras de variáveis aleatórias
-    fn register_client_queue_and_server_times(&mut self, client: &Client) {
-        let w = client.calculate_event_time(W);
-        let x = client.calculate_event_time(X);
+    fn register_client_queue_and_server_times(
+        &mut self,
+        client: &Client,
+    ) -> Result<(), SimulationError> {
+        let w = client.calculate_event_time(W)?;
+        let x = client.calculate_event_time(X)?;
         self.samples.get_mut(W).unwrap().append(w);
         self.samples.get_mut(X).unwrap().append(x);
         self.samples.get_mut(T).unwrap().append(w + x);
+        for &(name, value) in [(W, w), (T, w + x)].iter() {
+            if let Some(sample) = self.pooled_samples.get_mut(name) {
+                sample.append(value);
+            }
+        }
+        Ok(())
     }
 
     // Adiciona um novo evento na lista de eventos ocorridos
@@ -157,13 +299,14 @@ impl Queue {
     }
 
     // Seleciona o próximo evento e remove ele da lista de eventos ocorridos
-    fn get_next_event(&mut self) -> Event {
-        assert!(
-            !self.past_events.is_empty(),
-            "A lista de eventos está vazia!"
-        );
+    fn get_next_event(&mut self) -> Result<Event, SimulationError> {
+        if self.past_events.is_empty() {
+            return Err(SimulationError::InconsistentState(
+                "A lista de eventos está vazia!".to_string(),
+            ));
+        }
         let mut smallest_element_index = 0;
-        let mut smallest_event_time = std::f64::INFINITY;
+        let mut smallest_event_time = f64::INFINITY;
         for (index, event) in self.past_events.iter().enumerate() {
             let event_time = event.birth_time + event.duration;
             if smallest_event_time > event_time {
@@ -171,16 +314,21 @@ impl Queue {
                 smallest_event_time = event_time;
             }
         }
-        self.past_events.swap_remove(smallest_element_index)
+        Ok(self.past_events.swap_remove(smallest_element_index))
     }
 
     // Seleciona o próximo cliente a ser atendido, de acordo com a política de atendimento atual
-    fn get_next_client(&mut self) -> Client {
-        assert!(!self.queue.is_empty(), "A fila está vazia!");
-        match self.queue_policy {
-            QueuePolicy::FCFS => self.queue.pop_back().unwrap(),
-            QueuePolicy::LCFS => self.queue.pop_front().unwrap(),
-        }
+    fn get_next_client(&mut self) -> Result<Client, SimulationError> {
+        let client = match self.queue_policy {
+            QueuePolicy::FCFS => self.queue.pop_back(),
+            QueuePolicy::LCFS => self.queue.pop_front(),
+        };
+        client.ok_or_else(|| SimulationError::InconsistentState("A fila está vazia!".to_string()))
+    }
+
+    // Erro de um evento que não é de chegada nem de fim de serviço
+    fn invalid_event(event: &Event) -> SimulationError {
+        SimulationError::InconsistentState(format!("Tipo de evento inválido: {}", event.name))
     }
 
     // Processa um evento de chegada de freguês
@@ -191,12 +339,16 @@ impl Queue {
             let next_client_arrival_duration = self.exponential_time_generator.get(self.lambda);
             // Adiciona o evento da próxima chegada na lista de eventos caso seja uma simulação real
             self.add_event(CLIENT_ARRIVAL, next_client_arrival_duration);
-            Client::new(self.exponential_time_generator.get(1.0), self.color)
+            let x = self.exponential_time_generator.get(SERVICE_RATE);
+            Client::new(self.next_client_id, x, self.color)
         } else {
-            Client::new(0.0, self.color)
+            Client::new(self.next_client_id, 0.0, self.color)
         };
+        self.next_client_id += 1;
         // Marca o inicio da espera desse freguês
         client.register_start(W, self.current_time);
+        let current_time = self.current_time;
+        self.notify_observers(|observer| observer.on_arrival(&client, current_time));
         // Verifica se a fila está vazia e se não tem nenhum cliente em serviço
         if self.queue.is_empty() && self.client_in_service.is_none() {
             // Como não tem ninguém na fila e nem em serviço, esse freguês entra em atendimento
@@ -204,11 +356,16 @@ impl Queue {
             client.register_end(W, self.current_time);
             // Inicializa o período do atendimento desse freguês
             client.register_start(X, self.current_time);
+            self.notify_observers(|observer| observer.on_service_start(&client, current_time));
             // Adiciona o evento do fim de serviço desse freguês de acordo com seu X
             if self.mode == QueueMode::ForReal {
                 self.add_event(END_OF_SERVICE, client.x());
             }
             self.client_in_service = Some(client); // Colocamos esse freguês em atendimento
+            // Registra o estado atual, pois o sistema deixou de estar vazio. Sem esse registro
+            // o período em que esse freguês é atendido sozinho seria contabilizado como N = 0,
+            // e as médias temporais de N de todos os métodos sairiam subestimadas
+            self.register_current_state_values();
         } else {
             // Caso haja alguém na fila ou alguém sendo atendido, freguês vai pra fila de espera
             self.queue.push_front(client);
@@ -217,7 +374,7 @@ impl Queue {
     }
 
     // Processa um evento de fim de atendimento de um freguês
-    fn end_of_service_event(&mut self) {
+    fn end_of_service_event(&mut self) -> Result<(), SimulationError> {
         if self.client_in_service.is_some() {
             // Verifica se há algum freguês sendo atendido
             // Retira esse freguês do atendimento para coletarmos suas métricas
@@ -226,18 +383,23 @@ impl Queue {
             let mut current_client = current_wrapped_client.unwrap();
             // Registra o fim de atendimento desse freguês
             current_client.register_end(X, self.current_time);
+            let current_time = self.current_time;
+            self.notify_observers(|observer| observer.on_departure(&current_client, current_time));
             // Coleta as métricas W, X e T desse freguês se ele for da cor rodada atual
             if current_client.color() == self.color {
-                self.register_client_queue_and_server_times(&current_client);
+                self.register_client_queue_and_server_times(&current_client)?;
             }
             if !self.queue.is_empty() {
                 // Caso a fila não esteja vazia
                 // Seleciona o próximo freguês
-                let mut next_client = self.get_next_client();
+                let mut next_client = self.get_next_client()?;
                 // Finalizamos seu tempo de espera
                 next_client.register_end(W, self.current_time);
                 // Inicializamos seu tempo de atendimento
                 next_client.register_start(X, self.current_time);
+                self.notify_observers(|observer| {
+                    observer.on_service_start(&next_client, current_time)
+                });
                 // Registramos o evento de fim de serviço desse freguês
                 self.add_event(END_OF_SERVICE, next_client.x());
                 self.client_in_service = Some(next_client); // Colocamos esse freguês em atendimento
@@ -247,9 +409,12 @@ impl Queue {
             }
         }
         self.register_current_state_values(); // Registra o estado atual da fila
+        Ok(())
     }
 
-    pub fn transient_phase(&mut self) -> usize {
+    // Executa a fase transiente até que a utilização simulada fique próxima da esperada, e retorna
+    // o tamanho da fase transiente
+    pub fn transient_phase(&mut self) -> Result<usize, SimulationError> {
         // Coletores de métricas com um valor qualquer, essas métricas serão descartadas
         self.initialize_sample_collectors(5000);
         self.register_current_state_values();
@@ -258,14 +423,17 @@ impl Queue {
         // Contabilizamos o tamanho da fase transiente
         let mut transient_phase_counter = 0;
         let mut stable_queue_counter = 0usize;
+        // Utilização esperada, que só coincide com lambda quando a taxa de serviço é 1. Numa fila
+        // instável o servidor fica sempre ocupado, então a utilização esperada é 1
+        let expected_rho = f64::min(self.lambda / SERVICE_RATE, 1.0);
         loop {
             // Acumulamos os períodos ocupados
-            busy_time += self.handle_transient_phase_events();
+            busy_time += self.handle_transient_phase_events()?;
             // Incrementamos o tamanho atual da fase transiente
             transient_phase_counter += 1;
             // Calculamos um rho simulado, que é taxa atual de utilização da fila
             let simulated_rho = busy_time / self.current_time;
-            if 1.0 - f64::min(simulated_rho, self.lambda) / f64::max(simulated_rho, self.lambda)
+            if 1.0 - f64::min(simulated_rho, expected_rho) / f64::max(simulated_rho, expected_rho)
                 <= 0.01
             {
                 // Se o rho da simulação estiver razoavelmente próximo do rho contabilizamos
@@ -277,14 +445,15 @@ impl Queue {
             if stable_queue_counter == 500 {
                 // Se atingimos 500 iterações sequenciais com estabilidade, então podemos sair
                 // da fase transiente
-                break transient_phase_counter;
+                self.end_warmup(transient_phase_counter);
+                break Ok(transient_phase_counter);
             }
         }
     }
 
-    fn handle_transient_phase_events(&mut self) -> f64 {
+    fn handle_transient_phase_events(&mut self) -> Result<f64, SimulationError> {
         // Selecionamos o próximo evento
-        let event = self.get_next_event();
+        let event = self.get_next_event()?;
         // Aqui vemos se o evento atual está ocorrendo ou não durante um período ocupado
         let new_busy_time = if !self.queue.is_empty() || self.client_in_service.is_some() {
             event.birth_time + event.duration - self.current_time
@@ -298,21 +467,18 @@ impl Queue {
             self.handle_arrival_event(); // Processamos a chegada
         } else if END_OF_SERVICE == event.name {
             // Caso seja evento de fim de serviço
-            self.end_of_service_event(); // Processamos a saída
+            self.end_of_service_event()?; // Processamos a saída
         } else {
-            panic!("Tipo de evento inválido"); // Apenas eventos de chegada e saída são válidos
+            return Err(Self::invalid_event(&event)); // Apenas eventos de chegada e saída são válidos
         }
-        new_busy_time
+        Ok(new_busy_time)
     }
 
     // Executa uma rodada de simulação da fila e retorna as amostras coletadas das métricas
     pub fn run_one_simulation_round(
         &mut self,
         client_count: usize, // Número de freguêses dessa rodada
-    ) -> (
-        HashMap<String, Sample>,
-        HashMap<String, StochasticProcessSample>,
-    ) {
+    ) -> Result<SampleCollectors, SimulationError> {
         // Atualiza a cor da fila preparando a mesma para a rodada de simulação
         self.color += 1;
         // Inicializa os coletores de amostras
@@ -322,7 +488,7 @@ impl Queue {
        if self.mode == QueueMode::ForReal {
            while client < client_count {
                // Enquanto não processarmos todos os clientes pedidos
-               let event = self.get_next_event(); // Pegamos o próximo evento
+               let event = self.get_next_event()?; // Pegamos o próximo evento
                self.current_time = event.birth_time + event.duration; // Atualizamos o tempo atual da fila
                if CLIENT_ARRIVAL == event.name {
                    // Caso seja evento de chegada de freguês
@@ -335,35 +501,94 @@ impl Queue {
                            client += 1;
                        }
                    }
-                   self.end_of_service_event(); // Processamos a saída
+                   self.end_of_service_event()?; // Processamos a saída
                } else {
-                   panic!("Tipo de evento inválido"); // Apenas eventos de chegada e saída são válidos
+                   return Err(Self::invalid_event(&event)); // Apenas eventos de chegada e saída são válidos
                }
            }
        } else {
-           // Aqui forçamos uma fila onde temos chegadas nos momentos 0, 1, 2 e 3, com tempo
-           // de serviço constante igual a 2, de maneira que assim temos um resultado deterministico
-           // temos um ciclo de 9 segundos de duração
             while client < client_count {
-                for _step in 0..9 {
-                    match _step {
-                        0 => self.handle_arrival_event(),
-                        1 => self.handle_arrival_event(),
-                        2 => {
-                            self.handle_arrival_event();
-                            self.end_of_service_event();
-                        },
-                        3 => self.handle_arrival_event(),
-                        4 => self.end_of_service_event(),
-                        6 => self.end_of_service_event(),
-                        8 => self.end_of_service_event(),
-                        _ => ()
-                    }
-                    self.current_time += 1.0;
-                }
+                self.run_deterministic_cycle()?;
                 client += 4; // Tendo em vista que 4 clientes chegaram e saíram em cada ciclo
             }
        }
+        self.end_round(); // Avisa os observadores do fim da rodada
+        Ok(self.take_sample_collectors())
+    }
+
+    // Aqui forçamos uma fila onde temos chegadas nos momentos 0, 1, 2 e 3, com tempo
+    // de serviço constante igual a 2, de maneira que assim temos um resultado deterministico
+    // temos um ciclo de 9 segundos de duração
+    fn run_deterministic_cycle(&mut self) -> Result<(), SimulationError> {
+        for step in 0..9 {
+            match step {
+                0 => self.handle_arrival_event(),
+                1 => self.handle_arrival_event(),
+                2 => {
+                    self.handle_arrival_event();
+                    self.end_of_service_event()?;
+                }
+                3 => self.handle_arrival_event(),
+                4 => self.end_of_service_event()?,
+                6 => self.end_of_service_event()?,
+                8 => self.end_of_service_event()?,
+                _ => (),
+            }
+            self.current_time += 1.0;
+        }
+        Ok(())
+    }
+
+    // Executa um ciclo regenerativo da fila e retorna as amostras coletadas das métricas.
+    // A fila M/M/1 se regenera toda vez que o sistema esvazia, então o ciclo começa com o sistema
+    // vazio e termina no fim de serviço que deixa o sistema vazio novamente. Todos os fregueses
+    // que chegam durante o ciclo também saem nele
+    pub fn run_one_regeneration_cycle(&mut self) -> Result<SampleCollectors, SimulationError> {
+        if !self.queue.is_empty() || self.client_in_service.is_some() {
+            return Err(SimulationError::InconsistentState(
+                "Um ciclo regenerativo deve começar com o sistema vazio!".to_string(),
+            ));
+        }
+        // Atualiza a cor da fila, todos os fregueses do ciclo terão essa cor
+        self.color += 1;
+        // Inicializa os coletores de amostras com uma capacidade pequena, a maioria dos ciclos
+        // tem poucos fregueses
+        self.initialize_sample_collectors(16);
+        self.register_current_state_values(); // Registra o estado atual da fila
+        if self.mode == QueueMode::ForReal {
+            loop {
+                let event = self.get_next_event()?; // Pegamos o próximo evento
+                self.current_time = event.birth_time + event.duration; // Atualizamos o tempo atual
+                if CLIENT_ARRIVAL == event.name {
+                    // Caso seja evento de chegada de freguês
+                    self.handle_arrival_event(); // Processamos a chegada
+                } else if END_OF_SERVICE == event.name {
+                    // Caso seja evento de fim de serviço
+                    self.end_of_service_event()?; // Processamos a saída
+                    if self.queue.is_empty() && self.client_in_service.is_none() {
+                        // O sistema esvaziou, fim do ciclo regenerativo
+                        break;
+                    }
+                } else {
+                    return Err(Self::invalid_event(&event)); // Apenas eventos de chegada e saída são válidos
+                }
+            }
+        } else {
+            // No modo de corretude cada ciclo de 9 segundos é um ciclo regenerativo, que termina
+            // com um segundo ocioso, contabilizado quando os coletores são fechados
+            self.run_deterministic_cycle()?;
+        }
+        self.end_round(); // Avisa os observadores do fim do ciclo
+        Ok(self.take_sample_collectors())
+    }
+
+    // Fecha os coletores de processos estocásticos no tempo atual, para que o último estado
+    // registrado seja contabilizado até o fim da rodada, e remove e retorna os coletores da struct
+    fn take_sample_collectors(&mut self) -> SampleCollectors {
+        let current_time = self.current_time;
+        for sample in self.stochastic_process_samples.values_mut() {
+            sample.close(current_time);
+        }
         // Estratégia abaixo é usada para remover e retornar os coletores de amostras da struct
         // sem a necessidade de copiar seus dados, por questões de performance
         let mut output_samples = HashMap::new();
@@ -378,3 +603,51 @@ impl Queue {
         (output_samples, output_stochastic_process_samples)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_round_counts_the_final_idle_second() {
+        // Cada ciclo de 9 segundos tem N = 1, 2, 2, 3, 2, 2, 1, 1, 0, ou seja, E[N] = 14 / 9, e
+        // Nq = 0, 1, 1, 2, 1, 1, 0, 0, 0, ou seja, E[Nq] = 6 / 9. O último segundo, ocioso, só é
+        // contabilizado porque os coletores são fechados no fim da rodada
+        for &policy in [QueuePolicy::FCFS, QueuePolicy::LCFS].iter() {
+            let mut queue = Queue::check_correctness(policy);
+            let (samples, stochastic_process_samples) =
+                queue.run_one_simulation_round(8).unwrap();
+            let n = &stochastic_process_samples[N];
+            let nq = &stochastic_process_samples[NQ];
+            assert_eq!(n.duration(), 18.0);
+            assert_eq!(n.integral(), 28.0);
+            assert_eq!(nq.integral(), 12.0);
+            assert_eq!(n.quantile(0.5), 2);
+            assert_eq!(samples[X].mean(), 2.0);
+        }
+    }
+
+    #[test]
+    fn arrival_to_an_empty_system_is_counted_while_served() {
+        // Um segundo ocioso seguido de dois segundos com um único freguês em atendimento, sem
+        // ninguém esperando, então N vale 1 durante 2 dos 3 segundos e Nq é sempre 0
+        let mut queue = Queue::check_correctness(QueuePolicy::FCFS);
+        queue.initialize_sample_collectors(1);
+        queue.register_current_state_values();
+        queue.current_time = 1.0;
+        queue.handle_arrival_event();
+        queue.current_time = 3.0;
+        let (_, stochastic_process_samples) = queue.take_sample_collectors();
+        assert_eq!(stochastic_process_samples[N].duration(), 3.0);
+        assert_eq!(stochastic_process_samples[N].integral(), 2.0);
+        assert_eq!(stochastic_process_samples[NQ].integral(), 0.0);
+    }
+
+    #[test]
+    fn empty_event_list_is_an_error_instead_of_a_panic() {
+        let mut queue = Queue::check_correctness(QueuePolicy::FCFS);
+        assert!(queue.get_next_event().is_err());
+        assert!(queue.get_next_client().is_err());
+        assert!(queue.run_one_simulation_round(4).is_ok());
+    }
+}
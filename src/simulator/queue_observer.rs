@@ -0,0 +1,129 @@
+// Importando o freguês, que é passado aos observadores
+use crate::simulator::client::Client;
+
+// Estruturas da biblioteca padrão para exibir a fábrica de observadores e compartilhá-la entre threads
+use std::fmt;
+use std::sync::Arc;
+
+// Trait dos observadores de uma fila, que recebem os eventos da simulação sem modificá-la. Todos os
+// métodos têm uma implementação vazia, então cada observador implementa só os eventos que lhe
+// interessam. O tempo passado é o tempo atual da fila no evento. Os eventos da fase transiente
+// também são observados, inclusive as rodadas piloto dos procedimentos de aquecimento, e terminam
+// com on_warmup_end. Como as filas paralelas executam em threads, o observador deve ser Send
+pub trait QueueObserver: Send {
+    // Chegada de um freguês, antes de ele entrar na fila de espera ou em atendimento
+    fn on_arrival(&mut self, _client: &Client, _time: f64) {}
+
+    // Início do atendimento de um freguês, que não é mais contado na fila de espera
+    fn on_service_start(&mut self, _client: &Client, _time: f64) {}
+
+    // Fim do atendimento e saída de um freguês, que já tem seus tempos de W e X encerrados
+    fn on_departure(&mut self, _client: &Client, _time: f64) {}
+
+    // Fim de uma rodada ou de um ciclo regenerativo, identificado pela cor dos seus fregueses
+    fn on_round_end(&mut self, _round: usize, _time: f64) {}
+
+    // Fim da fase transiente, com o seu tamanho conforme o procedimento de aquecimento. As filas
+    // do método regenerativo e do modo de corretude não têm fase transiente e avisam um tamanho 0
+    fn on_warmup_end(&mut self, _transient_phase_size: usize, _time: f64) {}
+}
+
+// Fábrica dos observadores de uma execução, chamada uma vez para cada fila simulada com a semente
+// da fila, que distingue as filas paralelas e as replicações. As filas piloto do procedimento de
+// Welch não são observadas
+#[derive(Clone)]
+pub struct ObserverFactory(Arc<dyn Fn(u64) -> Box<dyn QueueObserver> + Send + Sync>);
+
+impl ObserverFactory {
+    // Instancia a fábrica a partir de uma função que cria o observador de uma fila
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: Fn(u64) -> Box<dyn QueueObserver> + Send + Sync + 'static,
+    {
+        Self(Arc::new(factory))
+    }
+
+    // Cria o observador da fila com a semente dada
+    pub fn create(&self, seed: u64) -> Box<dyn QueueObserver> {
+        (self.0)(seed)
+    }
+}
+
+impl fmt::Debug for ObserverFactory {
+    // A função da fábrica não pode ser exibida, então só indicamos que ela existe
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ObserverFactory")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::queue::{Queue, QueuePolicy, W};
+    use crate::simulator::random_number_generator::GeneratorKind;
+    use std::sync::Mutex;
+
+    // Observador que conta os eventos recebidos e guarda os tempos em que foram observados
+    struct CountingObserver {
+        events: Arc<Mutex<Vec<(&'static str, f64)>>>,
+    }
+
+    impl QueueObserver for CountingObserver {
+        fn on_arrival(&mut self, _client: &Client, time: f64) {
+            self.events.lock().unwrap().push(("arrival", time));
+        }
+
+        fn on_service_start(&mut self, _client: &Client, time: f64) {
+            self.events.lock().unwrap().push(("service_start", time));
+        }
+
+        fn on_departure(&mut self, _client: &Client, time: f64) {
+            self.events.lock().unwrap().push(("departure", time));
+        }
+
+        fn on_round_end(&mut self, _round: usize, time: f64) {
+            self.events.lock().unwrap().push(("round_end", time));
+        }
+
+        fn on_warmup_end(&mut self, _transient_phase_size: usize, time: f64) {
+            self.events.lock().unwrap().push(("warmup_end", time));
+        }
+    }
+
+    #[test]
+    fn observers_see_every_event_in_time_order() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut queue = Queue::new(0.5, QueuePolicy::FCFS, 3, GeneratorKind::Std);
+        queue.add_observer(Box::new(CountingObserver {
+            events: Arc::clone(&events),
+        }));
+        queue.transient_phase().unwrap();
+        queue.run_one_simulation_round(100).unwrap();
+        let events = events.lock().unwrap();
+        let count = |name| events.iter().filter(|(event, _)| *event == name).count();
+        assert_eq!(count("warmup_end"), 1);
+        assert_eq!(count("round_end"), 1);
+        assert_eq!(events.last().unwrap().0, "round_end");
+        // Cada freguês que saiu chegou e foi atendido, e no máximo um está em atendimento
+        assert!(count("departure") >= 100);
+        assert!(count("service_start") - count("departure") <= 1);
+        assert!(count("arrival") >= count("service_start"));
+        assert!(events.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+
+    #[test]
+    fn observers_do_not_change_the_simulation() {
+        let round_mean_w = |observed: bool| {
+            let mut queue = Queue::new(0.7, QueuePolicy::LCFS, 11, GeneratorKind::Std);
+            if observed {
+                queue.add_observer(Box::new(CountingObserver {
+                    events: Arc::new(Mutex::new(vec![])),
+                }));
+            }
+            queue.transient_phase().unwrap();
+            let (samples, _) = queue.run_one_simulation_round(500).unwrap();
+            samples[W].mean()
+        };
+        assert_eq!(round_mean_w(true), round_mean_w(false));
+    }
+}
@@ -0,0 +1,298 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+// Importando os traits de geradores de números aleatórios e os geradores disponíveis
+use rand::{Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rand_pcg::Pcg64;
+use rand_xoshiro::Xoshiro256StarStar;
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
+
+// Enum para selecionar qual gerador de números aleatórios será usado pelo simulador
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GeneratorKind {
+    // Gerador padrão da biblioteca rand, que na versão 0.7 é um ChaCha20 semeado da mesma forma
+    // que o ChaCha abaixo. Como o StdRng não expõe seu estado, usamos o ChaCha20 diretamente, e um
+    // teste confere que a sequência continua idêntica à do StdRng
+    Std,
+    Pcg,     // Permuted Congruential Generator de 64 bits
+    Xoshiro, // Xoshiro256**
+    ChaCha,  // Cifra ChaCha com 20 rodadas
+    Lcg,     // Gerador congruencial linear simples, para fins didáticos
+}
+
+impl FromStr for GeneratorKind {
+    type Err = String;
+
+    // Converte o nome do gerador, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "std" => Ok(GeneratorKind::Std),
+            "pcg" => Ok(GeneratorKind::Pcg),
+            "xoshiro" => Ok(GeneratorKind::Xoshiro),
+            "chacha" => Ok(GeneratorKind::ChaCha),
+            "lcg" => Ok(GeneratorKind::Lcg),
+            _ => Err(format!(
+                "Gerador de números aleatórios desconhecido: {}",
+                name
+            )),
+        }
+    }
+}
+
+impl GeneratorKind {
+    // Todos os geradores disponíveis, usado para rodar a bateria de testes em todos eles
+    pub const ALL: [GeneratorKind; 5] = [
+        GeneratorKind::Std,
+        GeneratorKind::Pcg,
+        GeneratorKind::Xoshiro,
+        GeneratorKind::ChaCha,
+        GeneratorKind::Lcg,
+    ];
+}
+
+// Gerador congruencial linear de 64 bits com as constantes do MMIX do Knuth.
+// Os bits menos significativos de um LCG têm período curto, então usamos apenas os 32 mais altos
+#[derive(Serialize, Deserialize)]
+pub struct LinearCongruentialGenerator {
+    state: u64,
+}
+
+impl LinearCongruentialGenerator {
+    const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+    const INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+    // Avança o estado do gerador uma vez
+    fn step(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT);
+        self.state
+    }
+}
+
+impl RngCore for LinearCongruentialGenerator {
+    fn next_u32(&mut self) -> u32 {
+        (self.step() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for LinearCongruentialGenerator {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: u64::from_le_bytes(seed),
+        }
+    }
+}
+
+// Gerador ChaCha20 junto da semente que o originou, pois esta versão da biblioteca não expõe a
+// semente. O estado é serializado como a semente e a posição na sequência, a partir das quais o
+// gerador é reconstruído sem repetir os sorteios
+pub struct SeededChaCha {
+    generator: ChaCha20Rng,
+    seed: u64,
+}
+
+// Estado serializado do gerador ChaCha20
+#[derive(Serialize, Deserialize)]
+struct SeededChaChaState {
+    seed: u64,
+    word_position: u128,
+}
+
+impl SeededChaCha {
+    // Instancia o gerador da mesma forma que o ChaCha20Rng::seed_from_u64. O primeiro bloco já é
+    // gerado, sem mudar a sequência, pois a posição de um gerador que ainda não gerou nenhum
+    // bloco estoura a subtração do get_word_pos desta versão da biblioteca
+    fn new(seed: u64) -> Self {
+        let mut generator = ChaCha20Rng::seed_from_u64(seed);
+        generator.set_word_pos(0);
+        Self { generator, seed }
+    }
+}
+
+impl Serialize for SeededChaCha {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SeededChaChaState {
+            seed: self.seed,
+            word_position: self.generator.get_word_pos(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SeededChaCha {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = SeededChaChaState::deserialize(deserializer)?;
+        let mut chacha = Self::new(state.seed);
+        chacha.generator.set_word_pos(state.word_position);
+        Ok(chacha)
+    }
+}
+
+// Enum que encapsula o gerador escolhido, despachando as chamadas para o gerador concreto. É
+// serializado com o estado interno de cada gerador, para a retomada dos checkpoints
+#[derive(Serialize, Deserialize)]
+pub enum RandomNumberGenerator {
+    Std(SeededChaCha),
+    Pcg(Pcg64),
+    Xoshiro(Xoshiro256StarStar),
+    ChaCha(SeededChaCha),
+    Lcg(LinearCongruentialGenerator),
+}
+
+impl RandomNumberGenerator {
+    // Instancia o gerador escolhido a partir de uma semente
+    pub fn new(kind: GeneratorKind, seed: u64) -> Self {
+        match kind {
+            GeneratorKind::Std => RandomNumberGenerator::Std(SeededChaCha::new(seed)),
+            GeneratorKind::Pcg => RandomNumberGenerator::Pcg(Pcg64::seed_from_u64(seed)),
+            GeneratorKind::Xoshiro => {
+                RandomNumberGenerator::Xoshiro(Xoshiro256StarStar::seed_from_u64(seed))
+            }
+            GeneratorKind::ChaCha => RandomNumberGenerator::ChaCha(SeededChaCha::new(seed)),
+            GeneratorKind::Lcg => {
+                RandomNumberGenerator::Lcg(LinearCongruentialGenerator::seed_from_u64(seed))
+            }
+        }
+    }
+
+    // Retorna o gerador concreto como um objeto de trait, para não repetirmos o match em cada método
+    fn inner(&mut self) -> &mut dyn RngCore {
+        match self {
+            RandomNumberGenerator::Std(chacha) => &mut chacha.generator,
+            RandomNumberGenerator::Pcg(generator) => generator,
+            RandomNumberGenerator::Xoshiro(generator) => generator,
+            RandomNumberGenerator::ChaCha(chacha) => &mut chacha.generator,
+            RandomNumberGenerator::Lcg(generator) => generator,
+        }
+    }
+}
+
+impl RngCore for RandomNumberGenerator {
+    fn next_u32(&mut self) -> u32 {
+        self.inner().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.inner().try_fill_bytes(dest)
+    }
+}
+
+// Incremento e multiplicadores do SplitMix64, usado para derivar sementes independentes
+const SPLITMIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
+const SPLITMIX_MULTIPLIERS: [u64; 2] = [0xBF58_476D_1CE4_E5B9, 0x94D0_49BB_1331_11EB];
+
+// Primeiro índice do fluxo de sementes das filas piloto da detecção do aquecimento. Os índices
+// das execuções de um sweep, das filas paralelas e das replicações começam em zero, então os dois
+// fluxos derivados da mesma semente base são disjuntos
+const PILOT_SEED_STREAM: usize = usize::MAX / 2 + 1;
+
+// Deriva a semente de índice index a partir da semente base pelo SplitMix64, usada pelas execuções
+// de um sweep, pelas filas paralelas e pelas replicações de uma execução. Sementes consecutivas não
+// servem, pois derivações vizinhas compartilhariam filas: a piloto de uma replicação repetiria a
+// trajetória da replicação seguinte
+pub fn derived_seed(base_seed: u64, index: usize) -> u64 {
+    let mut z = base_seed.wrapping_add((index as u64 + 1).wrapping_mul(SPLITMIX_INCREMENT));
+    z = (z ^ (z >> 30)).wrapping_mul(SPLITMIX_MULTIPLIERS[0]);
+    z = (z ^ (z >> 27)).wrapping_mul(SPLITMIX_MULTIPLIERS[1]);
+    z ^ (z >> 31)
+}
+
+// Deriva a semente da fila piloto de índice index da detecção do aquecimento de uma fila
+pub fn pilot_seed(base_seed: u64, index: usize) -> u64 {
+    derived_seed(base_seed, PILOT_SEED_STREAM + index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn replication_and_pilot_seeds_never_coincide() {
+        // As sementes das replicações, e das pilotos de cada replicação e da execução, são todas
+        // diferentes, ao contrário das sementes consecutivas
+        let base_seed = 42;
+        let mut seeds = HashSet::new();
+        for replication in 0..50 {
+            let replication_seed = derived_seed(base_seed, replication);
+            assert!(seeds.insert(replication_seed));
+            assert!(seeds.insert(pilot_seed(base_seed, replication)));
+            for pilot in 0..10 {
+                assert!(seeds.insert(pilot_seed(replication_seed, pilot)));
+            }
+        }
+        assert!(!seeds.contains(&base_seed));
+    }
+
+    #[test]
+    fn lcg_follows_the_mmix_recurrence() {
+        // Valores calculados à parte com x' = 6364136223846793005 x + 1442695040888963407 mod 2^64
+        // a partir de x = 0, cada u32 é a metade alta do estado
+        let mut generator = LinearCongruentialGenerator::from_seed(0u64.to_le_bytes());
+        assert_eq!(generator.next_u32(), 335_903_614);
+        assert_eq!(generator.next_u32(), 436_792_849);
+        assert_eq!(generator.next_u64(), 11_166_244_415_259_155_177);
+        let mut generator = LinearCongruentialGenerator::from_seed(0u64.to_le_bytes());
+        assert_eq!(generator.next_u64(), 1_442_695_037_175_000_593);
+    }
+
+    #[test]
+    fn std_generator_matches_the_library_std_rng() {
+        let mut generator = RandomNumberGenerator::new(GeneratorKind::Std, 42);
+        let mut std_rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            assert_eq!(generator.next_u64(), std_rng.next_u64());
+        }
+    }
+
+    #[test]
+    fn serialized_generators_resume_the_sequence() {
+        for &kind in GeneratorKind::ALL.iter() {
+            // Uma quantidade ímpar de palavras de 32 bits deixa o ChaCha no meio de um bloco
+            for &draws in [0, 1, 7, 100].iter() {
+                let mut generator = RandomNumberGenerator::new(kind, 42);
+                for _ in 0..draws {
+                    generator.next_u32();
+                }
+                let json = serde_json::to_string(&generator).unwrap();
+                let mut resumed: RandomNumberGenerator = serde_json::from_str(&json).unwrap();
+                for _ in 0..100 {
+                    assert_eq!(
+                        resumed.next_u64(),
+                        generator.next_u64(),
+                        "{:?} após {} sorteios",
+                        kind,
+                        draws
+                    );
+                }
+            }
+        }
+    }
+}
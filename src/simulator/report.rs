@@ -0,0 +1,34 @@
+// Relatório textual de uma execução do simulador. Numa execução isolada cada trecho é impresso na
+// hora, entre as barras de progresso. Nas execuções de um sweep paralelo o relatório é acumulado,
+// para ser impresso inteiro e na ordem das execuções
+pub struct Report {
+    buffer: Option<String>, // Texto acumulado, ou None quando o relatório é impresso na hora
+}
+
+impl Report {
+    // Instancia um relatório impresso na hora ou, com deferred, acumulado
+    pub fn new(deferred: bool) -> Self {
+        Self {
+            buffer: if deferred { Some(String::new()) } else { None },
+        }
+    }
+
+    // Acrescenta um trecho ao relatório, sem quebra de linha, como o print!
+    pub fn print(&mut self, text: &str) {
+        match &mut self.buffer {
+            Some(buffer) => buffer.push_str(text),
+            None => print!("{}", text),
+        }
+    }
+
+    // Acrescenta uma linha ao relatório, como o println!
+    pub fn println(&mut self, text: &str) {
+        self.print(text);
+        self.print("\n");
+    }
+
+    // Texto acumulado, vazio quando o relatório foi impresso na hora
+    pub fn into_text(self) -> String {
+        self.buffer.unwrap_or_default()
+    }
+}
@@ -0,0 +1,731 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a struct dos parâmetros, que diz onde e como os resultados são escritos
+use crate::simulator::SimulationParameters;
+// Serialização dos parâmetros nos checkpoints
+use serde::{Deserialize, Serialize};
+
+// Arquivos, caminhos e conversão de strings da biblioteca padrão
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+// Versão do esquema dos resultados, escrita em todos os formatos. Ela só é incrementada quando uma
+// coluna existente muda de nome ou de significado, então novas métricas não quebram os scripts
+// que leem os resultados. Na versão 2, V(N), V(T) e V(X) do método das rodadas passaram a ser a
+// média das variâncias de cada rodada, e não mais a variância dessas variâncias
+pub const RESULTS_SCHEMA_VERSION: u32 = 2;
+
+// Coluna com a versão do esquema, sempre escrita antes das demais
+const SCHEMA_VERSION_COLUMN: &str = "schema_version";
+
+// Arquivos de resultados já substituídos nesta execução do programa. As próximas escritas no mesmo
+// arquivo acrescentam seus resultados, como nas simulações de um sweep. A trava também impede que
+// execuções paralelas escrevam no mesmo arquivo ao mesmo tempo
+static OVERWRITTEN_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+// Formato do arquivo de resultados
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ResultsFormat {
+    Csv,       // Uma linha por execução, com o cabeçalho na primeira linha
+    Json,      // Um documento com a versão do esquema e a lista dos resultados
+    JsonLines, // Um objeto JSON por linha, cada um com a versão do esquema
+}
+
+impl ResultsFormat {
+    // Formato correspondente à extensão do arquivo: .json, .jsonl ou, para as demais, .csv
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => ResultsFormat::Json,
+            Some("jsonl") => ResultsFormat::JsonLines,
+            _ => ResultsFormat::Csv,
+        }
+    }
+}
+
+impl FromStr for ResultsFormat {
+    type Err = String;
+
+    // Converte o nome do formato, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "csv" => Ok(ResultsFormat::Csv),
+            "json" => Ok(ResultsFormat::Json),
+            "jsonl" => Ok(ResultsFormat::JsonLines),
+            _ => Err(format!("Formato de resultados desconhecido: {}", name)),
+        }
+    }
+}
+
+// Modo de escrita do arquivo de resultados
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WriteMode {
+    Append,    // Acrescenta os resultados aos que já estão no arquivo
+    Overwrite, // Substitui o arquivo na primeira escrita do programa
+}
+
+impl FromStr for WriteMode {
+    type Err = String;
+
+    // Converte o nome do modo, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "append" => Ok(WriteMode::Append),
+            "overwrite" => Ok(WriteMode::Overwrite),
+            _ => Err(format!("Modo de escrita desconhecido: {}", name)),
+        }
+    }
+}
+
+// Valor de uma coluna dos resultados
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResultValue {
+    Number(f64),  // Estatísticas e parâmetros reais, que podem não ter valor definido
+    Integer(u64), // Contagens e a semente, que não cabem num f64 sem perder precisão
+    Text(String), // Nomes dos enums, como a política e o motivo da parada
+}
+
+impl ResultValue {
+    // Valor numérico da coluna, ou None numa coluna de texto
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            ResultValue::Number(number) => Some(*number),
+            ResultValue::Integer(integer) => Some(*integer as f64),
+            ResultValue::Text(_) => None,
+        }
+    }
+
+    // Texto da coluna, ou None numa coluna numérica
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            ResultValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // Valor lido de um .csv: inteiro, real, inclusive NaN, ou texto
+    fn from_csv_value(value: &str) -> Self {
+        if let Ok(integer) = value.parse() {
+            ResultValue::Integer(integer)
+        } else if let Ok(number) = value.parse() {
+            ResultValue::Number(number)
+        } else {
+            ResultValue::Text(value.to_string())
+        }
+    }
+
+    // Valor lido de JSON, com null como NaN. Listas e objetos não são escritos pelo simulador
+    fn from_json_value(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Null => Some(ResultValue::Number(f64::NAN)),
+            serde_json::Value::Number(number) => Some(match number.as_u64() {
+                Some(integer) => ResultValue::Integer(integer),
+                None => ResultValue::Number(number.as_f64()?),
+            }),
+            serde_json::Value::String(text) => Some(ResultValue::Text(text.clone())),
+            _ => None,
+        }
+    }
+
+    // Valor como é escrito no .csv
+    fn csv_value(&self) -> String {
+        match self {
+            ResultValue::Number(number) => number.to_string(),
+            ResultValue::Integer(integer) => integer.to_string(),
+            ResultValue::Text(text) => text.clone(),
+        }
+    }
+
+    // Valor como é escrito em JSON, com os números sem valor definido, como NaN, escritos como null
+    fn json_value(&self) -> String {
+        match self {
+            ResultValue::Number(number) if number.is_finite() => number.to_string(),
+            ResultValue::Number(_) => "null".to_string(),
+            ResultValue::Integer(integer) => integer.to_string(),
+            ResultValue::Text(text) => json_string(text),
+        }
+    }
+}
+
+// String em JSON, com as aspas e os escapes necessários
+fn json_string(text: &str) -> String {
+    serde_json::to_string(text).expect("Uma string sempre pode ser serializada")
+}
+
+// Resultados de uma execução como colunas nomeadas, na ordem em que são escritas. Novas métricas
+// devem ser acrescentadas ao fim, para que a ordem das colunas existentes não mude
+#[derive(Debug, Clone, Default)]
+pub struct ResultsRecord {
+    columns: Vec<(String, ResultValue)>, // Nome e valor de cada coluna
+}
+
+impl ResultsRecord {
+    // Instancia os resultados sem nenhuma coluna
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Acrescenta uma coluna numérica
+    pub fn number(&mut self, name: &str, value: f64) {
+        self.columns
+            .push((name.to_string(), ResultValue::Number(value)));
+    }
+
+    // Acrescenta uma coluna inteira
+    pub fn integer(&mut self, name: &str, value: u64) {
+        self.columns
+            .push((name.to_string(), ResultValue::Integer(value)));
+    }
+
+    // Acrescenta uma coluna de texto
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.columns
+            .push((name.to_string(), ResultValue::Text(value.to_string())));
+    }
+
+    // Getter das colunas, na ordem em que são escritas
+    pub fn columns(&self) -> &[(String, ResultValue)] {
+        &self.columns
+    }
+
+    // Valor de uma coluna, caso ela exista
+    pub fn value(&self, name: &str) -> Option<&ResultValue> {
+        self.columns
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, value)| value)
+    }
+
+    // Colunas escritas: a versão do esquema seguida das colunas escolhidas, na ordem pedida, ou de
+    // todas elas. Colunas desconhecidas são rejeitadas junto com a lista das existentes
+    fn selected_columns(
+        &self,
+        selection: Option<&[String]>,
+    ) -> Result<Vec<(String, ResultValue)>, SimulationError> {
+        let version = (
+            SCHEMA_VERSION_COLUMN.to_string(),
+            ResultValue::Integer(RESULTS_SCHEMA_VERSION as u64),
+        );
+        let selection = match selection {
+            Some(selection) => selection,
+            None => {
+                return Ok(std::iter::once(version)
+                    .chain(self.columns.clone())
+                    .collect())
+            }
+        };
+        let unknown: Vec<&str> = selection
+            .iter()
+            .filter(|name| self.value(name).is_none())
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            let known: Vec<&str> = self.columns.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(SimulationError::InvalidParameters(vec![format!(
+                "colunas de resultados desconhecidas: {}. As colunas existentes são: {}",
+                unknown.join(", "),
+                known.join(", ")
+            )]));
+        }
+        Ok(std::iter::once(version)
+            .chain(
+                selection
+                    .iter()
+                    .filter_map(|name| self.value(name).map(|value| (name.clone(), value.clone()))),
+            )
+            .collect())
+    }
+}
+
+// Objeto JSON com as colunas, na ordem em que são escritas
+fn json_object(columns: &[(String, ResultValue)]) -> String {
+    let fields: Vec<String> = columns
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value.json_value()))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+// Confere que os resultados existentes, que vão receber os desta execução, têm a mesma versão do
+// esquema, para que colunas com significados diferentes não se misturem
+fn check_appended_version(path: &Path, version: Option<u64>) -> Result<(), SimulationError> {
+    if version == Some(RESULTS_SCHEMA_VERSION as u64) {
+        Ok(())
+    } else {
+        Err(SimulationError::InvalidFile {
+            path: path.to_path_buf(),
+            message: format!(
+                "os resultados têm a versão {:?} do esquema, mas esta execução escreve a {}, use \
+                 outro arquivo ou o modo overwrite",
+                version, RESULTS_SCHEMA_VERSION
+            ),
+        })
+    }
+}
+
+// Conteúdo a ser escrito num .csv: o cabeçalho, caso o arquivo seja novo, e a linha. Um arquivo
+// existente com outro cabeçalho ou outra versão do esquema é rejeitado, para que colunas
+// diferentes não se misturem
+fn csv_contents(
+    path: &Path,
+    existing: Option<&str>,
+    columns: &[(String, ResultValue)],
+) -> Result<String, SimulationError> {
+    let header: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+    let header = header.join(",");
+    let row: Vec<String> = columns.iter().map(|(_, value)| value.csv_value()).collect();
+    let row = row.join(",");
+    let mut existing_lines = existing.into_iter().flat_map(str::lines);
+    match existing_lines.next() {
+        None => Ok(format!("{}\n{}\n", header, row)),
+        Some(existing_header) if existing_header == header => {
+            if let Some(line) = existing_lines.find(|line| !line.is_empty()) {
+                let version = line.split(',').next().and_then(|value| value.parse().ok());
+                check_appended_version(path, version)?;
+            }
+            Ok(format!("{}\n", row))
+        }
+        Some(_) => Err(SimulationError::InvalidFile {
+            path: path.to_path_buf(),
+            message: "o cabeçalho do arquivo é diferente das colunas desta execução, use outro \
+                      arquivo ou o modo overwrite"
+                .to_string(),
+        }),
+    }
+}
+
+// Conteúdo a ser acrescentado a um .jsonl: o objeto desta execução. As linhas existentes devem ter
+// a mesma versão do esquema
+fn json_lines_contents(
+    path: &Path,
+    existing: Option<&str>,
+    columns: &[(String, ResultValue)],
+) -> Result<String, SimulationError> {
+    if let Some(line) = existing
+        .into_iter()
+        .flat_map(str::lines)
+        .find(|line| !line.trim().is_empty())
+    {
+        let version = serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|object| object.get(SCHEMA_VERSION_COLUMN)?.as_u64());
+        check_appended_version(path, version)?;
+    }
+    Ok(format!("{}\n", json_object(columns)))
+}
+
+// Novo conteúdo de um .json: o documento com a lista dos resultados, que no modo append recebe o
+// resultado desta execução ao fim. O documento existente deve ter a mesma versão do esquema
+fn json_contents(
+    path: &Path,
+    existing: Option<&str>,
+    columns: &[(String, ResultValue)],
+) -> Result<String, SimulationError> {
+    let object = json_object(columns);
+    let existing = match existing {
+        Some(existing) if !existing.trim().is_empty() => existing,
+        _ => {
+            return Ok(format!(
+                "{{\"{}\":{},\"results\":[\n{}\n]}}\n",
+                SCHEMA_VERSION_COLUMN, RESULTS_SCHEMA_VERSION, object
+            ))
+        }
+    };
+    let invalid_file = |message: String| SimulationError::InvalidFile {
+        path: path.to_path_buf(),
+        message,
+    };
+    let document: serde_json::Value = serde_json::from_str(existing)
+        .map_err(|error| invalid_file(format!("resultados inválidos: {}", error)))?;
+    check_appended_version(
+        path,
+        document
+            .get(SCHEMA_VERSION_COLUMN)
+            .and_then(|version| version.as_u64()),
+    )?;
+    let empty = match document
+        .get("results")
+        .and_then(|results| results.as_array())
+    {
+        Some(results) => results.is_empty(),
+        None => return Err(invalid_file("a lista results não existe".to_string())),
+    };
+    // O documento foi escrito por esta função, então termina com o fim da lista e do objeto, e o
+    // novo resultado é inserido antes deles para preservar a ordem das colunas dos anteriores
+    let body = existing.trim_end();
+    let body = body[..body.len() - 2].trim_end();
+    let separator = if empty { "" } else { "," };
+    Ok(format!("{}{}\n{}\n]}}\n", body, separator, object))
+}
+
+// Resultados de um objeto JSON, sem a versão do esquema. Como os objetos são lidos sem preservar a
+// ordem das chaves, as colunas ficam em ordem alfabética
+fn record_from_json(object: &serde_json::Value) -> Result<ResultsRecord, String> {
+    let object = object
+        .as_object()
+        .ok_or_else(|| "os resultados devem ser objetos".to_string())?;
+    let mut record = ResultsRecord::new();
+    for (name, value) in object.iter() {
+        if name == SCHEMA_VERSION_COLUMN {
+            continue;
+        }
+        let value = ResultValue::from_json_value(value)
+            .ok_or_else(|| format!("valor inválido na coluna {}", name))?;
+        record.columns.push((name.clone(), value));
+    }
+    Ok(record)
+}
+
+// Verifica se a versão do esquema lida é a escrita por esta versão do simulador
+fn check_schema_version(version: Option<u64>) -> Result<(), String> {
+    if version == Some(RESULTS_SCHEMA_VERSION as u64) {
+        Ok(())
+    } else {
+        Err(format!(
+            "os resultados têm a versão {:?} do esquema, mas esta versão do simulador lê a {}",
+            version, RESULTS_SCHEMA_VERSION
+        ))
+    }
+}
+
+// Lê os resultados de um .csv, com a versão do esquema na primeira coluna
+fn read_csv_results(contents: &str) -> Result<Vec<ResultsRecord>, String> {
+    let mut lines = contents.lines();
+    let header: Vec<&str> = match lines.next() {
+        Some(header) => header.split(',').collect(),
+        None => return Ok(vec![]),
+    };
+    if header.first() != Some(&SCHEMA_VERSION_COLUMN) {
+        return Err(format!(
+            "o cabeçalho não começa com {}, o arquivo é de uma versão anterior do simulador",
+            SCHEMA_VERSION_COLUMN
+        ));
+    }
+    let mut records = vec![];
+    for (index, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+        let values: Vec<&str> = line.split(',').collect();
+        if values.len() != header.len() {
+            return Err(format!(
+                "linha {}: {} valores para {} colunas",
+                index + 2,
+                values.len(),
+                header.len()
+            ));
+        }
+        check_schema_version(values[0].parse().ok())?;
+        let mut record = ResultsRecord::new();
+        for (name, value) in header.iter().zip(values.iter()).skip(1) {
+            record
+                .columns
+                .push((name.to_string(), ResultValue::from_csv_value(value)));
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// Lê os resultados de um .json, o documento com a versão do esquema e a lista dos resultados
+fn read_json_results(contents: &str) -> Result<Vec<ResultsRecord>, String> {
+    let document: serde_json::Value = serde_json::from_str(contents)
+        .map_err(|error| format!("resultados inválidos: {}", error))?;
+    check_schema_version(
+        document
+            .get(SCHEMA_VERSION_COLUMN)
+            .and_then(|version| version.as_u64()),
+    )?;
+    document
+        .get("results")
+        .and_then(|results| results.as_array())
+        .ok_or_else(|| "a lista results não existe".to_string())?
+        .iter()
+        .map(record_from_json)
+        .collect()
+}
+
+// Lê os resultados de um .jsonl, um objeto com a versão do esquema em cada linha
+fn read_json_lines_results(contents: &str) -> Result<Vec<ResultsRecord>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let object: serde_json::Value = serde_json::from_str(line)
+                .map_err(|error| format!("linha {}: {}", index + 1, error))?;
+            check_schema_version(
+                object
+                    .get(SCHEMA_VERSION_COLUMN)
+                    .and_then(|version| version.as_u64()),
+            )?;
+            record_from_json(&object)
+        })
+        .collect()
+}
+
+// Lê os resultados escritos por write_results no formato dado, como para desenhar os gráficos. A
+// versão do esquema de cada resultado é verificada e não faz parte das colunas lidas
+pub fn read_results(
+    path: &Path,
+    format: ResultsFormat,
+) -> Result<Vec<ResultsRecord>, SimulationError> {
+    let contents = fs::read_to_string(path).map_err(SimulationError::io(path))?;
+    match format {
+        ResultsFormat::Csv => read_csv_results(&contents),
+        ResultsFormat::Json => read_json_results(&contents),
+        ResultsFormat::JsonLines => read_json_lines_results(&contents),
+    }
+    .map_err(|message| SimulationError::InvalidFile {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+// Conteúdo escrito no arquivo dos resultados, indicando se ele é acrescentado ao fim do arquivo e
+// se o arquivo é substituído. Só as colunas escolhidas nos parâmetros são escritas, todas quando
+// nenhuma é escolhida, e um arquivo existente incompatível com elas é rejeitado
+fn results_file_contents(
+    parameters: &SimulationParameters,
+    record: &ResultsRecord,
+    overwritten_files: &[PathBuf],
+) -> Result<(String, bool, bool), SimulationError> {
+    let path = parameters.output_path.as_path();
+    let format = parameters
+        .output_format
+        .unwrap_or_else(|| ResultsFormat::from_path(path));
+    let columns = record.selected_columns(parameters.output_columns.as_deref())?;
+
+    let overwrite = parameters.output_mode == WriteMode::Overwrite
+        && !overwritten_files.iter().any(|file| file == path);
+    let existing = if overwrite || !path.exists() {
+        None
+    } else {
+        Some(fs::read_to_string(path).map_err(SimulationError::io(path))?)
+    };
+
+    // O .json é reescrito por inteiro, os demais formatos só acrescentam ao arquivo
+    Ok(match format {
+        ResultsFormat::Csv => (
+            csv_contents(path, existing.as_deref(), &columns)?,
+            true,
+            overwrite,
+        ),
+        ResultsFormat::JsonLines => (
+            json_lines_contents(path, existing.as_deref(), &columns)?,
+            true,
+            overwrite,
+        ),
+        ResultsFormat::Json => (
+            json_contents(path, existing.as_deref(), &columns)?,
+            false,
+            overwrite,
+        ),
+    })
+}
+
+// Verifica, antes de a simulação começar, que resultados com as colunas dadas poderão ser escritos
+// no arquivo: as colunas escolhidas nos parâmetros precisam existir e um arquivo existente que
+// recebe os resultados precisa ter o mesmo cabeçalho ou a mesma versão do esquema
+pub fn check_results_file(
+    parameters: &SimulationParameters,
+    column_names: &[String],
+) -> Result<(), SimulationError> {
+    let mut record = ResultsRecord::new();
+    for name in column_names {
+        record.number(name, f64::NAN);
+    }
+    let overwritten_files = OVERWRITTEN_FILES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    results_file_contents(parameters, &record, &overwritten_files).map(|_| ())
+}
+
+// Escreve os resultados de uma execução no arquivo, no formato e no modo pedidos nos parâmetros
+pub fn write_results(
+    parameters: &SimulationParameters,
+    record: &ResultsRecord,
+) -> Result<(), SimulationError> {
+    let path = parameters.output_path.as_path();
+    let mut overwritten_files = OVERWRITTEN_FILES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let (contents, append, overwrite) =
+        results_file_contents(parameters, record, &overwritten_files)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append && !overwrite)
+        .truncate(!append || overwrite)
+        .open(path)
+        .map_err(SimulationError::io(path))?;
+    file.write_all(contents.as_bytes())
+        .map_err(SimulationError::io(path))?;
+    if overwrite {
+        overwritten_files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{QueueMode, QueuePolicy};
+    use std::env;
+
+    // Resultados de uma execução com uma coluna numérica, uma de texto e uma sem valor
+    fn record(mean_w: f64) -> ResultsRecord {
+        let mut record = ResultsRecord::new();
+        record.number("rho", 0.5);
+        record.text("policy", "FCFS");
+        record.number("E[W]", mean_w);
+        record.number("V(W)_analytic", f64::NAN);
+        record
+    }
+
+    // Parâmetros que escrevem no arquivo temporário dado
+    fn parameters(file_name: &str, output_mode: WriteMode) -> SimulationParameters {
+        let mut parameters =
+            SimulationParameters::new(0.5, 10, 10, QueuePolicy::FCFS, QueueMode::ForReal);
+        parameters.output_path = env::temp_dir().join(file_name);
+        parameters.output_mode = output_mode;
+        let _ = fs::remove_file(&parameters.output_path);
+        parameters
+    }
+
+    #[test]
+    fn every_format_carries_the_schema_version() {
+        let csv = parameters("simulador_ad_results.csv", WriteMode::Append);
+        write_results(&csv, &record(1.0)).unwrap();
+        write_results(&csv, &record(2.0)).unwrap();
+        let contents = fs::read_to_string(&csv.output_path).unwrap();
+        assert_eq!(
+            contents,
+            "schema_version,rho,policy,E[W],V(W)_analytic\n2,0.5,FCFS,1,NaN\n2,0.5,FCFS,2,NaN\n"
+        );
+
+        let json_lines = parameters("simulador_ad_results.jsonl", WriteMode::Append);
+        write_results(&json_lines, &record(1.0)).unwrap();
+        let contents = fs::read_to_string(&json_lines.output_path).unwrap();
+        assert_eq!(
+            contents,
+            "{\"schema_version\":2,\"rho\":0.5,\"policy\":\"FCFS\",\"E[W]\":1,\
+             \"V(W)_analytic\":null}\n"
+        );
+
+        let json = parameters("simulador_ad_results.json", WriteMode::Append);
+        for &mean_w in [1.0, 2.0, 3.0].iter() {
+            write_results(&json, &record(mean_w)).unwrap();
+        }
+        let document: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&json.output_path).unwrap()).unwrap();
+        assert_eq!(document["schema_version"], 2);
+        let means: Vec<f64> = document["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["E[W]"].as_f64().unwrap())
+            .collect();
+        assert_eq!(means, vec![1.0, 2.0, 3.0]);
+
+        // Os três formatos são lidos de volta com os mesmos valores
+        for parameters in [csv, json_lines, json].iter() {
+            let path = &parameters.output_path;
+            let records = read_results(path, ResultsFormat::from_path(path)).unwrap();
+            let record = &records[0];
+            assert_eq!(record.value("rho"), Some(&ResultValue::Number(0.5)));
+            assert_eq!(record.value("policy").unwrap().as_text(), Some("FCFS"));
+            assert_eq!(record.value("E[W]").unwrap().as_number(), Some(1.0));
+            assert!(record
+                .value("V(W)_analytic")
+                .unwrap()
+                .as_number()
+                .unwrap()
+                .is_nan());
+            assert!(record.value(SCHEMA_VERSION_COLUMN).is_none());
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn selected_columns_are_written_in_the_given_order() {
+        let mut parameters = parameters("simulador_ad_selected.csv", WriteMode::Append);
+        parameters.output_columns = Some(vec!["E[W]".to_string(), "rho".to_string()]);
+        write_results(&parameters, &record(1.5)).unwrap();
+        let contents = fs::read_to_string(&parameters.output_path).unwrap();
+        assert_eq!(contents, "schema_version,E[W],rho\n2,1.5,0.5\n");
+
+        // Um arquivo com outras colunas não recebe a linha
+        parameters.output_columns = None;
+        let error = write_results(&parameters, &record(1.5)).unwrap_err();
+        assert!(error.to_string().contains("cabeçalho"));
+        parameters.output_columns = Some(vec!["E[T]".to_string()]);
+        let error = write_results(&parameters, &record(1.5)).unwrap_err();
+        assert!(error.to_string().contains("E[T]"));
+        fs::remove_file(&parameters.output_path).unwrap();
+    }
+
+    #[test]
+    fn incompatible_files_are_rejected_before_the_simulation() {
+        let column_names: Vec<String> = record(1.0)
+            .columns()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        let mut csv = parameters("simulador_ad_checked.csv", WriteMode::Append);
+        check_results_file(&csv, &column_names).unwrap();
+        fs::write(&csv.output_path, "schema_version,rho\n2,0.5\n").unwrap();
+        let error = check_results_file(&csv, &column_names).unwrap_err();
+        assert!(error.to_string().contains("cabeçalho"));
+        csv.output_columns = Some(vec!["rho".to_string()]);
+        check_results_file(&csv, &column_names).unwrap();
+        csv.output_columns = Some(vec!["E[T]".to_string()]);
+        let error = check_results_file(&csv, &column_names).unwrap_err();
+        assert!(error.to_string().contains("E[T]"));
+        // O arquivo que ainda vai ser substituído não precisa ser compatível
+        csv.output_columns = None;
+        csv.output_mode = WriteMode::Overwrite;
+        check_results_file(&csv, &column_names).unwrap();
+        // A verificação não escreve nada
+        assert_eq!(
+            fs::read_to_string(&csv.output_path).unwrap(),
+            "schema_version,rho\n2,0.5\n"
+        );
+        fs::remove_file(&csv.output_path).unwrap();
+
+        // Os resultados da versão 1 têm outro significado para V(N), V(T) e V(X)
+        csv.output_mode = WriteMode::Append;
+        csv.output_columns = Some(vec!["rho".to_string()]);
+        fs::write(&csv.output_path, "schema_version,rho\n1,0.5\n").unwrap();
+        let error = check_results_file(&csv, &column_names).unwrap_err();
+        assert!(error.to_string().contains("esquema"));
+        fs::remove_file(&csv.output_path).unwrap();
+
+        let json_lines = parameters("simulador_ad_checked.jsonl", WriteMode::Append);
+        fs::write(
+            &json_lines.output_path,
+            "{\"schema_version\":1,\"rho\":0.5}\n",
+        )
+        .unwrap();
+        let error = check_results_file(&json_lines, &column_names).unwrap_err();
+        assert!(error.to_string().contains("esquema"));
+        fs::remove_file(&json_lines.output_path).unwrap();
+
+        let json = parameters("simulador_ad_checked.json", WriteMode::Append);
+        fs::write(&json.output_path, "{\"schema_version\":0,\"results\":[]}\n").unwrap();
+        let error = check_results_file(&json, &column_names).unwrap_err();
+        assert!(error.to_string().contains("esquema"));
+        fs::remove_file(&json.output_path).unwrap();
+    }
+
+    #[test]
+    fn overwrite_replaces_the_file_only_on_the_first_write() {
+        let parameters = parameters("simulador_ad_overwrite.jsonl", WriteMode::Overwrite);
+        fs::write(&parameters.output_path, "resultado antigo\n").unwrap();
+        write_results(&parameters, &record(1.0)).unwrap();
+        write_results(&parameters, &record(2.0)).unwrap();
+        let contents = fs::read_to_string(&parameters.output_path).unwrap();
+        fs::remove_file(&parameters.output_path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert!(!contents.contains("antigo"));
+    }
+}
@@ -0,0 +1,123 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+
+// Histograma com classes de mesma largura começando em zero, onde cada valor pode ter um peso,
+// por exemplo o tempo em que um processo estocástico ficou em cada estado. A quantidade de classes
+// cresce conforme aparecem valores maiores, então não precisamos conhecer o máximo de antemão
+#[derive(Serialize, Deserialize)]
+pub struct Histogram {
+    bin_width: f64,    // Largura de cada classe
+    weights: Vec<f64>, // Peso acumulado em cada classe
+    total_weight: f64, // Peso total acumulado
+}
+
+// Uma classe do histograma, com sua probabilidade e a probabilidade acumulada até seu fim
+pub struct HistogramBin {
+    pub lower_bound: f64, // Início da classe, incluído
+    pub upper_bound: f64, // Fim da classe, excluído
+    pub probability: f64, // Fração do peso total dentro da classe
+    pub cumulative: f64,  // Função de distribuição empírica no fim da classe
+}
+
+impl Histogram {
+    // Instancia um histograma vazio com a largura de classe pedida
+    pub fn new(bin_width: f64) -> Self {
+        assert!(
+            bin_width > 0.0,
+            "A largura das classes {} deve ser positiva",
+            bin_width
+        );
+        Self {
+            bin_width,
+            weights: vec![],
+            total_weight: 0.0,
+        }
+    }
+
+    // Adiciona um valor não negativo com o peso dado
+    pub fn append(&mut self, value: f64, weight: f64) {
+        assert!(value >= 0.0, "O valor {} deve ser não negativo", value);
+        let bin = (value / self.bin_width).floor() as usize;
+        if bin >= self.weights.len() {
+            self.weights.resize(bin + 1, 0.0);
+        }
+        self.weights[bin] += weight;
+        self.total_weight += weight;
+    }
+
+    // Acumula os pesos de outro histograma com a mesma largura de classe
+    pub fn merge(&mut self, other: &Histogram) {
+        assert!(
+            self.bin_width == other.bin_width,
+            "Só podemos juntar histogramas com a mesma largura de classe"
+        );
+        if other.weights.len() > self.weights.len() {
+            self.weights.resize(other.weights.len(), 0.0);
+        }
+        for (weight, other_weight) in self.weights.iter_mut().zip(other.weights.iter()) {
+            *weight += other_weight;
+        }
+        self.total_weight += other.total_weight;
+    }
+
+    // Classes do histograma normalizadas, junto com a função de distribuição empírica
+    pub fn bins(&self) -> Vec<HistogramBin> {
+        let mut cumulative = 0.0;
+        self.weights
+            .iter()
+            .enumerate()
+            .map(|(index, &weight)| {
+                let probability = weight / self.total_weight;
+                cumulative += probability;
+                HistogramBin {
+                    lower_bound: index as f64 * self.bin_width,
+                    upper_bound: (index + 1) as f64 * self.bin_width,
+                    probability,
+                    cumulative,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::test_helpers::assert_close;
+
+    #[test]
+    fn values_fall_in_half_open_bins() {
+        let mut histogram = Histogram::new(0.5);
+        // 0.0 e 0.49 em [0, 0.5), 0.5 em [0.5, 1), 1.75 em [1.5, 2) com peso 2
+        histogram.append(0.0, 1.0);
+        histogram.append(0.49, 1.0);
+        histogram.append(0.5, 1.0);
+        histogram.append(1.75, 2.0);
+        let bins = histogram.bins();
+        assert_eq!(bins.len(), 4);
+        let probabilities: Vec<f64> = bins.iter().map(|bin| bin.probability).collect();
+        assert_eq!(probabilities, vec![0.4, 0.2, 0.0, 0.4]);
+        assert_close(bins[3].lower_bound, 1.5);
+        assert_close(bins[3].upper_bound, 2.0);
+        assert_close(bins[1].cumulative, 0.6);
+        assert_close(bins[3].cumulative, 1.0);
+    }
+
+    #[test]
+    fn merge_adds_the_weights_of_each_bin() {
+        let mut first = Histogram::new(1.0);
+        first.append(0.5, 1.0);
+        let mut second = Histogram::new(1.0);
+        second.append(0.2, 1.0);
+        second.append(2.5, 2.0);
+        first.merge(&second);
+        let probabilities: Vec<f64> = first.bins().iter().map(|bin| bin.probability).collect();
+        assert_eq!(probabilities, vec![0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merging_different_bin_widths_panics() {
+        Histogram::new(1.0).merge(&Histogram::new(0.5));
+    }
+}
@@ -1,3 +1,7 @@
+// Módulo com o histograma de classes de mesma largura, com pesos
+pub(crate) mod histogram;
+// Módulo com o estimador de quantis em fluxo P², que usa memória constante
+pub(crate) mod p_square_quantile;
 // Módulo para coletar amostras de uma variável aleatória e calcular estimadores e estatísticas
 pub(crate) mod sample;
 // Módulo para coletar amostras de um processo estocástico e calcular estimadores
@@ -0,0 +1,154 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando o quantil exato, usado enquanto ainda não temos os 5 marcadores do algoritmo
+use super::sample::exact_quantile;
+
+// Estimador de quantil em fluxo pelo algoritmo P² de Jain e Chlamtac. Em vez de guardar todos os
+// valores, mantemos apenas 5 marcadores: o mínimo, o quantil p/2, o quantil p, o quantil (1+p)/2
+// e o máximo. A cada novo valor as posições dos marcadores são atualizadas e suas alturas são
+// ajustadas por interpolação parabólica, usando memória constante
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PSquareQuantile {
+    probability: f64,            // Probabilidade do quantil estimado
+    heights: [f64; 5],           // Alturas dos marcadores, ou seja, as estimativas dos quantis
+    positions: [f64; 5],         // Posições atuais dos marcadores
+    desired_positions: [f64; 5], // Posições desejadas dos marcadores
+    increments: [f64; 5],        // Incremento das posições desejadas a cada novo valor
+    count: usize,                // Quantidade de valores recebidos
+}
+
+impl PSquareQuantile {
+    // Instancia um estimador para o quantil de probabilidade p
+    pub fn new(probability: f64) -> Self {
+        assert!(
+            probability > 0.0 && probability < 1.0,
+            "A probabilidade {} deve estar em (0, 1)",
+            probability
+        );
+        Self {
+            probability,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired_positions: [
+                1.0,
+                1.0 + 2.0 * probability,
+                1.0 + 4.0 * probability,
+                3.0 + 2.0 * probability,
+                5.0,
+            ],
+            increments: [
+                0.0,
+                probability / 2.0,
+                probability,
+                (1.0 + probability) / 2.0,
+                1.0,
+            ],
+            count: 0,
+        }
+    }
+
+    // Instancia um estimador e já processa uma sequência de valores
+    pub fn from_values(values: &[f64], probability: f64) -> Self {
+        let mut estimator = Self::new(probability);
+        for &value in values {
+            estimator.append(value);
+        }
+        estimator
+    }
+
+    // Getter da probabilidade do quantil estimado
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    // Processa um novo valor
+    pub fn append(&mut self, value: f64) {
+        // Os 5 primeiros valores, ordenados, são as alturas iniciais dos marcadores
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights
+                    .sort_by(|a, b| a.partial_cmp(b).expect("Valor inválido"));
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Encontra a célula k em que o valor cai, atualizando os extremos se necessário
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (0..4)
+                .find(|&index| value < self.heights[index + 1])
+                .unwrap_or(3)
+        };
+        for position in self.positions.iter_mut().skip(cell + 1) {
+            *position += 1.0;
+        }
+        for (desired, increment) in self
+            .desired_positions
+            .iter_mut()
+            .zip(self.increments.iter())
+        {
+            *desired += increment;
+        }
+
+        // Ajusta os 3 marcadores centrais que se afastaram mais de uma posição da desejada
+        for index in 1..4 {
+            let difference = self.desired_positions[index] - self.positions[index];
+            if (difference >= 1.0 && self.positions[index + 1] - self.positions[index] > 1.0)
+                || (difference <= -1.0 && self.positions[index - 1] - self.positions[index] < -1.0)
+            {
+                let direction = difference.signum();
+                let parabolic = self.parabolic(index, direction);
+                self.heights[index] =
+                    if self.heights[index - 1] < parabolic && parabolic < self.heights[index + 1] {
+                        parabolic
+                    } else {
+                        self.linear(index, direction)
+                    };
+                self.positions[index] += direction;
+            }
+        }
+    }
+
+    // Fórmula de interpolação parabólica (P²) para a nova altura de um marcador
+    fn parabolic(&self, index: usize, direction: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[index]
+            + direction / (n[index + 1] - n[index - 1])
+                * ((n[index] - n[index - 1] + direction) * (q[index + 1] - q[index])
+                    / (n[index + 1] - n[index])
+                    + (n[index + 1] - n[index] - direction) * (q[index] - q[index - 1])
+                        / (n[index] - n[index - 1]))
+    }
+
+    // Interpolação linear, usada quando a parabólica sairia da ordem dos marcadores
+    fn linear(&self, index: usize, direction: f64) -> f64 {
+        let neighbour = if direction > 0.0 {
+            index + 1
+        } else {
+            index - 1
+        };
+        self.heights[index]
+            + direction * (self.heights[neighbour] - self.heights[index])
+                / (self.positions[neighbour] - self.positions[index])
+    }
+
+    // Estimativa atual do quantil. Com menos de 5 valores usamos o quantil exato deles
+    pub fn quantile(&self) -> f64 {
+        if self.count < 5 {
+            if self.count == 0 {
+                return 0.0;
+            }
+            exact_quantile(&self.heights[..self.count], self.probability)
+        } else {
+            self.heights[2]
+        }
+    }
+}
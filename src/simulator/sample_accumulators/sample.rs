@@ -1,72 +1,339 @@
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
 // Importando a representação do nosso intervalo de confiança
 use crate::simulator::confidence_interval::ConfidenceInterval;
+// Importando as inversas das funções de distribuição acumulada da T-Student e da Chi²
+use crate::simulator::statistical_distributions::{chi_square_quantile, student_t_quantile};
+// Importando o histograma e o estimador de quantis em fluxo
+use super::histogram::Histogram;
+use super::p_square_quantile::PSquareQuantile;
 
-// Percentis da Distribuição Chi² para uma confiança de 95% e 3199 graus de liberdade.
-// Obtido através da biblioteca scipy no Python
-const CHI_SQUARE_LOWER_PERCENTILE: f64 = 3_044.130_201_770_939_5;
-const CHI_SQUARE_UPPER_PERCENTILE: f64 = 3_357.658_239_649_767_4;
+// Quantil exato de um conjunto de valores, interpolando linearmente entre as estatísticas de ordem
+// vizinhas à posição p (n - 1)
+pub fn exact_quantile(values: &[f64], probability: f64) -> f64 {
+    assert!(
+        (0.0..=1.0).contains(&probability),
+        "A probabilidade {} deve estar em [0, 1]",
+        probability
+    );
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).expect("Valor inválido"));
+    let position = probability * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower])
+}
 
-// Percentil da Distribuição T-Student para uma confiança de 95% e 3199 de liberdade.
-// Obtido através da biblioteca scipy no Python
-const T_STUDENT_PERCENTILE: f64 = 1.960_705_826_924_122_4;
+// Enum para selecionar como um acumulador guarda suas amostras
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SampleStorage {
+    // Guarda todos os valores, necessário para quantis, histogramas e séries
+    Raw,
+    // Guarda apenas os acumuladores em fluxo, com memória constante
+    Streaming,
+}
 
-// Struct para acumular os valores das variáveis aleatórias
+// Struct para acumular os valores das variáveis aleatórias. A média e a variância são sempre
+// mantidas em fluxo pelo algoritmo de Welford, e os valores só são guardados no modo Raw. Os
+// quantis acompanhados em fluxo têm seus marcadores P² atualizados a cada valor, em qualquer modo
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Sample {
-    values: Vec<f64>,
+    storage: SampleStorage,  // Modo de armazenamento das amostras
+    values: Vec<f64>,        // Valores coletados, vazio no modo em fluxo
+    count: usize,            // Quantidade de valores coletados
+    running_mean: f64,       // Média dos valores coletados
+    squared_deviations: f64, // Soma dos desvios quadráticos em relação à média
+    // Estimadores P² dos quantis acompanhados em fluxo, um para cada probabilidade
+    quantile_markers: Vec<PSquareQuantile>,
 }
 
 impl Sample {
     // Instancia um novo objeto, já tendo uma ideia aproximada de quantos valores serão recebidos
     pub fn new(capacity: usize) -> Self {
+        Self::with_storage(capacity, SampleStorage::Raw)
+    }
+
+    // Instancia um novo objeto com o modo de armazenamento pedido. A capacidade só é reservada
+    // quando os valores são guardados
+    pub fn with_storage(capacity: usize, storage: SampleStorage) -> Self {
+        let capacity = match storage {
+            SampleStorage::Raw => capacity,
+            SampleStorage::Streaming => 0,
+        };
         Self {
+            storage,
             values: Vec::with_capacity(capacity),
+            count: 0,
+            running_mean: 0.0,
+            squared_deviations: 0.0,
+            quantile_markers: vec![],
         }
     }
 
-    // Adiciona novos valores no nosso vetor
+    // Passa a acompanhar em fluxo os quantis das probabilidades dadas, a partir do próximo valor
+    pub fn track_quantiles(&mut self, probabilities: &[f64]) {
+        self.quantile_markers = probabilities
+            .iter()
+            .map(|&probability| PSquareQuantile::new(probability))
+            .collect();
+    }
+
+    // Adiciona um novo valor, atualizando a média e a soma dos desvios quadráticos pelo algoritmo
+    // de Welford, que evita o cancelamento numérico da fórmula E[X²] - E[X]²
     pub fn append(&mut self, value: f64) {
-        self.values.push(value);
+        self.count += 1;
+        let deviation = value - self.running_mean;
+        self.running_mean += deviation / self.count as f64;
+        self.squared_deviations += deviation * (value - self.running_mean);
+        if self.storage == SampleStorage::Raw {
+            self.values.push(value);
+        }
+        for markers in self.quantile_markers.iter_mut() {
+            markers.append(value);
+        }
+    }
+
+    // Acumula os valores de outra amostra, combinando os acumuladores em fluxo pela fórmula de
+    // Chan et al. Uma amostra que guarda seus valores só pode receber outra que também os guarda.
+    // Os marcadores P² não podem ser combinados, então os quantis em fluxo não são acumulados
+    pub fn merge(&mut self, other: &Sample) {
+        if other.count == 0 {
+            return;
+        }
+        if self.storage == SampleStorage::Raw {
+            self.values.extend_from_slice(other.values());
+        }
+        let count = self.count + other.count;
+        let deviation = other.running_mean - self.running_mean;
+        self.running_mean += deviation * other.count as f64 / count as f64;
+        self.squared_deviations += other.squared_deviations
+            + deviation.powi(2) * self.count as f64 * other.count as f64 / count as f64;
+        self.count = count;
+    }
+
+    // Getter dos valores coletados, na ordem em que foram adicionados
+    pub fn values(&self) -> &[f64] {
+        assert!(
+            self.storage == SampleStorage::Raw,
+            "Os valores não são guardados por um acumulador em fluxo"
+        );
+        &self.values
+    }
+
+    // Quantidade de valores coletados
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Soma dos valores coletados
+    pub fn sum(&self) -> f64 {
+        self.running_mean * self.count as f64
+    }
+
+    // Soma dos quadrados dos valores coletados
+    pub fn sum_of_squares(&self) -> f64 {
+        self.squared_deviations + self.running_mean.powi(2) * self.count as f64
     }
 
     // Calcula o estimador da média
     pub fn mean(&self) -> f64 {
-        if !self.values.is_empty() {
-            self.values.iter().sum::<f64>() / self.values.len() as f64
+        if self.count > 0 {
+            self.running_mean
         } else {
             0.0
         }
     }
 
-    // Calcula o estimador da variância
+    // Calcula o estimador não enviesado da variância, que é nulo com menos de dois valores
     pub fn variance(&self) -> f64 {
-        let mean = self.mean();
-        if mean == 0.0 {
-            0.0
+        if self.count > 1 {
+            self.squared_deviations / (self.count - 1) as f64
         } else {
-            self.values
-                .iter()
-                .fold(0.0, |sum, &value| sum + (value - mean).powi(2))
-                / (self.values.len() - 1) as f64
+            0.0
         }
     }
 
-    // Calcula o intervalo de confiança segundo a distribuição T-Student
-    pub fn t_student_95percent(&self) -> ConfidenceInterval {
+    // Calcula o quantil exato a partir dos valores coletados
+    pub fn quantile(&self, probability: f64) -> f64 {
+        exact_quantile(self.values(), probability)
+    }
+
+    // Estimativa em fluxo P² de um quantil acompanhado, que não precisa dos valores coletados
+    pub fn streaming_quantile(&self, probability: f64) -> f64 {
+        self.quantile_markers
+            .iter()
+            .find(|markers| markers.probability() == probability)
+            .unwrap_or_else(|| panic!("O quantil {} não é acompanhado em fluxo", probability))
+            .quantile()
+    }
+
+    // Constrói o histograma dos valores coletados, com classes de largura bin_width
+    pub fn histogram(&self, bin_width: f64) -> Histogram {
+        let mut histogram = Histogram::new(bin_width);
+        for &value in self.values() {
+            histogram.append(value, 1.0);
+        }
+        histogram
+    }
+
+    // Calcula o intervalo de confiança segundo a distribuição T-Student, com n - 1 graus de
+    // liberdade e o nível de confiança pedido (por exemplo 0.95 para 95%). Com menos de dois
+    // valores não há graus de liberdade e o intervalo é a reta inteira
+    pub fn t_student(&self, confidence_level: f64) -> ConfidenceInterval {
+        if self.count < 2 {
+            return ConfidenceInterval::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
         let mean = self.mean();
+        let degrees_of_freedom = (self.count - 1) as f64;
+        let t_student_percentile =
+            student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, degrees_of_freedom);
         let t_student_times_sqrt_of_variance_by_sample_count =
-            T_STUDENT_PERCENTILE * (self.variance() / self.values.len() as f64).sqrt();
+            t_student_percentile * (self.variance() / self.count as f64).sqrt();
         ConfidenceInterval::new(
             mean - t_student_times_sqrt_of_variance_by_sample_count,
             mean + t_student_times_sqrt_of_variance_by_sample_count,
         )
     }
 
-    // Calcula o intervalo de confiança segundo a distribuição Chi-Square
-    pub fn chi_square_95percent(&self, sample_variance: f64) -> ConfidenceInterval {
-        let n_minus_one_times_variance = (self.values.len() - 1) as f64 * sample_variance;
+    // Calcula o intervalo de confiança segundo a distribuição Chi-Square, com n - 1 graus de
+    // liberdade e o nível de confiança pedido. Com menos de dois valores o intervalo é [0, ∞)
+    pub fn chi_square(&self, sample_variance: f64, confidence_level: f64) -> ConfidenceInterval {
+        if self.count < 2 {
+            return ConfidenceInterval::new(0.0, f64::INFINITY);
+        }
+        let degrees_of_freedom = (self.count - 1) as f64;
+        let alpha = 1.0 - confidence_level;
+        let n_minus_one_times_variance = degrees_of_freedom * sample_variance;
         ConfidenceInterval::new(
-            n_minus_one_times_variance / CHI_SQUARE_UPPER_PERCENTILE,
-            n_minus_one_times_variance / CHI_SQUARE_LOWER_PERCENTILE,
+            n_minus_one_times_variance / chi_square_quantile(1.0 - alpha / 2.0, degrees_of_freedom),
+            n_minus_one_times_variance / chi_square_quantile(alpha / 2.0, degrees_of_freedom),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::test_helpers::assert_close;
+
+    // Amostra com os valores dados e o modo de armazenamento pedido
+    fn sample_of(values: &[f64], storage: SampleStorage) -> Sample {
+        let mut sample = Sample::with_storage(values.len(), storage);
+        for &value in values {
+            sample.append(value);
+        }
+        sample
+    }
+
+    #[test]
+    fn variance_with_zero_mean() {
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let sample = sample_of(&[-1.0, 1.0], storage);
+            assert_close(sample.mean(), 0.0);
+            assert_close(sample.variance(), 2.0);
+        }
+    }
+
+    #[test]
+    fn variance_needs_two_values() {
+        assert_close(sample_of(&[], SampleStorage::Raw).mean(), 0.0);
+        assert_close(sample_of(&[], SampleStorage::Raw).variance(), 0.0);
+        assert_close(sample_of(&[3.0], SampleStorage::Raw).variance(), 0.0);
+    }
+
+    #[test]
+    fn welford_matches_hand_computed_estimators() {
+        // Soma dos desvios quadráticos em relação à média 5 é 32
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let sample = sample_of(&values, storage);
+            assert_eq!(sample.count(), 8);
+            assert_close(sample.mean(), 5.0);
+            assert_close(sample.variance(), 32.0 / 7.0);
+            assert_close(sample.sum(), 40.0);
+            assert_close(sample.sum_of_squares(), 232.0);
+        }
+    }
+
+    #[test]
+    fn welford_is_stable_with_a_large_offset() {
+        // A fórmula E[X²] - E[X]² perderia todos os dígitos significativos aqui
+        let sample = sample_of(
+            &[1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0],
+            SampleStorage::Streaming,
+        );
+        assert_close(sample.variance(), 30.0);
+    }
+
+    #[test]
+    fn merge_matches_appending_every_value() {
+        let first = [1.0, 2.0, 3.0];
+        let second = [10.0, 20.0];
+        let all = [1.0, 2.0, 3.0, 10.0, 20.0];
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let mut merged = sample_of(&first, storage);
+            merged.merge(&sample_of(&second, SampleStorage::Raw));
+            let expected = sample_of(&all, SampleStorage::Raw);
+            assert_eq!(merged.count(), 5);
+            assert_close(merged.mean(), expected.mean());
+            assert_close(merged.variance(), expected.variance());
+            if storage == SampleStorage::Raw {
+                assert_eq!(merged.values(), &all[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn intervals_need_two_values() {
+        for values in [&[][..], &[3.0][..]].iter() {
+            let sample = sample_of(values, SampleStorage::Streaming);
+            let t_student = sample.t_student(0.95);
+            assert_eq!(t_student.lower_bound(), f64::NEG_INFINITY);
+            assert_eq!(t_student.upper_bound(), f64::INFINITY);
+            let chi_square = sample.chi_square(sample.variance(), 0.95);
+            assert_eq!(chi_square.lower_bound(), 0.0);
+            assert_eq!(chi_square.upper_bound(), f64::INFINITY);
+        }
+    }
+
+    #[test]
+    fn exact_quantile_interpolates() {
+        let sample = sample_of(&[4.0, 1.0, 3.0, 2.0], SampleStorage::Raw);
+        assert_close(sample.quantile(0.0), 1.0);
+        assert_close(sample.quantile(0.5), 2.5);
+        assert_close(sample.quantile(1.0), 4.0);
+    }
+
+    #[test]
+    fn tracked_quantiles_do_not_need_the_values() {
+        let values: Vec<f64> = (0..1000)
+            .map(|index| ((index * 37) % 1000) as f64)
+            .collect();
+        let mut sample = Sample::with_storage(0, SampleStorage::Streaming);
+        sample.track_quantiles(&[0.5, 0.95]);
+        for &value in values.iter() {
+            sample.append(value);
+        }
+        for &probability in [0.5, 0.95].iter() {
+            assert_close(
+                sample.streaming_quantile(probability),
+                PSquareQuantile::from_values(&values, probability).quantile(),
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "não é acompanhado")]
+    fn untracked_quantiles_are_rejected() {
+        sample_of(&[1.0, 2.0], SampleStorage::Raw).streaming_quantile(0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "em fluxo")]
+    fn streaming_values_are_not_stored() {
+        sample_of(&[1.0, 2.0], SampleStorage::Streaming).values();
+    }
+}
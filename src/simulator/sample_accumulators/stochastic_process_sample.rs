@@ -1,52 +1,486 @@
-// Struct para acumular os valores e tempos de um processo estocástico
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando o histograma, usado na distribuição de ocupação dos estados
+use super::histogram::Histogram;
+// Importando o modo de armazenamento das amostras
+use super::sample::SampleStorage;
+
+// Struct para acumular os valores e tempos de um processo estocástico com valores inteiros e
+// trajetória constante por partes. O tempo em que o processo ficou em cada estado é sempre mantido
+// em fluxo, o que basta para os momentos e quantis ponderados pelo tempo, e a trajetória só é
+// guardada no modo Raw. O último valor vigora até o registro seguinte ou até o fechamento da
+// amostra, que deve ser feito no fim da rodada para que o último trecho seja contabilizado
+#[derive(Serialize, Deserialize)]
 pub struct StochasticProcessSample {
-    arrivals_times: Vec<f64>,
-    values: Vec<usize>,
+    storage: SampleStorage,    // Modo de armazenamento das amostras
+    arrivals_times: Vec<f64>,  // Instantes de cada registro, vazio no modo em fluxo
+    values: Vec<usize>,        // Valores de cada registro, vazio no modo em fluxo
+    count: usize,              // Quantidade de registros
+    first_time: f64,           // Instante do primeiro registro
+    last_time: f64,            // Instante do último registro, ou do fechamento
+    last_value: usize,         // Valor do processo desde o último registro
+    occupancy_times: Vec<f64>, // Tempo em que o processo ficou em cada estado
+    upward_jumps: usize,       // Quantidade de registros em que o valor aumentou
+    closed: bool,              // Indica se a amostra já foi fechada
 }
 
 impl StochasticProcessSample {
-    // Instancia um novo objeto, já tendo uma ideia aproximada de quantos valores serão recebidos
-    pub fn new(capacity: usize) -> Self {
+    // Instancia um novo objeto com o modo de armazenamento pedido, já tendo uma ideia aproximada
+    // de quantos valores serão recebidos. A capacidade só é reservada quando a trajetória é guardada
+    pub fn with_storage(capacity: usize, storage: SampleStorage) -> Self {
+        let capacity = match storage {
+            SampleStorage::Raw => capacity,
+            SampleStorage::Streaming => 0,
+        };
         Self {
+            storage,
             arrivals_times: Vec::with_capacity(capacity),
             values: Vec::with_capacity(capacity),
+            count: 0,
+            first_time: 0.0,
+            last_time: 0.0,
+            last_value: 0,
+            occupancy_times: vec![],
+            upward_jumps: 0,
+            closed: false,
         }
     }
 
-    // Adiciona novos valores e tempos nos nossos vetores
-    pub fn append(&mut self, time: f64, value: usize) {
-        self.arrivals_times.push(time);
-        self.values.push(value);
+    // Acumula o tempo do valor atual até o instante dado
+    fn advance_to(&mut self, time: f64) {
+        assert!(
+            time >= self.last_time,
+            "O instante {} é anterior ao último registro {}",
+            time,
+            self.last_time
+        );
+        if self.last_value >= self.occupancy_times.len() {
+            self.occupancy_times.resize(self.last_value + 1, 0.0);
+        }
+        self.occupancy_times[self.last_value] += time - self.last_time;
+        self.last_time = time;
     }
 
-    // Calcula o estimador da média
-    pub fn mean(&self) -> f64 {
-        let sample_count = self.values.len();
-        if sample_count > 0 {
-            let mut result = 0.0;
-            for index in 0..(sample_count - 1) {
-                result += self.values[index] as f64
-                    * (self.arrivals_times[index + 1] - self.arrivals_times[index])
+    // Adiciona um novo valor e seu tempo, acumulando o tempo do valor anterior, que vigorou desde
+    // o último registro
+    pub fn append(&mut self, time: f64, value: usize) {
+        assert!(
+            !self.closed,
+            "Não podemos registrar valores numa amostra fechada"
+        );
+        if self.count > 0 {
+            self.advance_to(time);
+            if value > self.last_value {
+                self.upward_jumps += 1;
             }
-            result / (self.arrivals_times[sample_count - 1] - self.arrivals_times[0])
+        } else {
+            self.first_time = time;
+            self.last_time = time;
+        }
+        self.count += 1;
+        self.last_value = value;
+        if self.storage == SampleStorage::Raw {
+            self.arrivals_times.push(time);
+            self.values.push(value);
+        }
+    }
+
+    // Fecha a amostra no instante final da observação, contabilizando o último valor registrado
+    // até ele. Depois de fechada a amostra não recebe novos valores
+    pub fn close(&mut self, end_time: f64) {
+        assert!(!self.closed, "A amostra já foi fechada");
+        self.closed = true;
+        if self.count == 0 {
+            return;
+        }
+        let last_time = self.last_time;
+        self.advance_to(end_time);
+        if self.storage == SampleStorage::Raw && end_time > last_time {
+            // Registro final que marca o fim do último trecho da trajetória
+            self.arrivals_times.push(end_time);
+            self.values.push(self.last_value);
+        }
+    }
+
+    // Acumula a trajetória de outra amostra já fechada como se ela continuasse esta, somando os
+    // tempos em cada estado. Serve para juntar rodadas ou ciclos de uma mesma fila, e a amostra
+    // resultante fica fechada no fim da trajetória acumulada
+    pub fn merge(&mut self, other: &StochasticProcessSample) {
+        if other.count == 0 {
+            return;
+        }
+        assert!(other.closed, "Só podemos juntar amostras já fechadas");
+        if self.count == 0 {
+            self.first_time = other.first_time;
+            self.last_time = other.first_time;
+        }
+        if self.storage == SampleStorage::Raw {
+            // Os instantes da outra trajetória são deslocados para começar no fim desta
+            other.assert_raw();
+            let offset = self.last_time - other.first_time;
+            self.arrivals_times
+                .extend(other.arrivals_times.iter().map(|time| time + offset));
+            self.values.extend_from_slice(&other.values);
+        }
+        if other.occupancy_times.len() > self.occupancy_times.len() {
+            self.occupancy_times
+                .resize(other.occupancy_times.len(), 0.0);
+        }
+        for (time, other_time) in self
+            .occupancy_times
+            .iter_mut()
+            .zip(other.occupancy_times.iter())
+        {
+            *time += other_time;
+        }
+        self.count += other.count;
+        self.upward_jumps += other.upward_jumps;
+        self.last_time += other.duration();
+        self.last_value = other.last_value;
+        self.closed = true;
+    }
+
+    // Garante que a trajetória do processo foi guardada
+    fn assert_raw(&self) {
+        assert!(
+            self.storage == SampleStorage::Raw,
+            "A trajetória não é guardada por um acumulador em fluxo"
+        );
+    }
+
+    // Integral de uma potência do processo ao longo do intervalo observado
+    fn power_integral(&self, order: i32) -> f64 {
+        self.occupancy_times
+            .iter()
+            .enumerate()
+            .fold(0.0, |sum, (state, &time)| {
+                sum + (state as f64).powi(order) * time
+            })
+    }
+
+    // Calcula o momento de ordem k ponderado pelo tempo, E[N^k]
+    pub fn moment(&self, order: i32) -> f64 {
+        let duration = self.duration();
+        if duration > 0.0 {
+            self.power_integral(order) / duration
+        } else {
+            0.0
+        }
+    }
+
+    // Calcula o momento central de ordem k ponderado pelo tempo, E[(N - E[N])^k]
+    pub fn central_moment(&self, order: i32) -> f64 {
+        let duration = self.duration();
+        if duration > 0.0 {
+            let mean = self.mean();
+            self.occupancy_times
+                .iter()
+                .enumerate()
+                .fold(0.0, |sum, (state, &time)| {
+                    sum + (state as f64 - mean).powi(order) * time
+                })
+                / duration
         } else {
             0.0
         }
     }
 
+    // Calcula o estimador da média
+    pub fn mean(&self) -> f64 {
+        self.moment(1)
+    }
+
     // Calcula o estimador da variância
     pub fn variance(&self) -> f64 {
-        let sample_count = self.values.len();
-        if sample_count > 0 {
-            let mut second_moment = 0.0;
-            for index in 0..(sample_count - 1) {
-                second_moment += self.values[index].pow(2) as f64
-                    * (self.arrivals_times[index + 1] - self.arrivals_times[index])
+        self.central_moment(2)
+    }
+
+    // Quantil ponderado pelo tempo: o menor estado k em que o processo passou pelo menos uma
+    // fração p do tempo em estados menores ou iguais a k
+    pub fn quantile(&self, probability: f64) -> usize {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "A probabilidade {} deve estar em [0, 1]",
+            probability
+        );
+        let target = probability * self.duration();
+        let mut cumulative_time = 0.0;
+        let mut last_visited_state = 0;
+        for (state, &time) in self.occupancy_times.iter().enumerate() {
+            if time > 0.0 {
+                cumulative_time += time;
+                last_visited_state = state;
+                if cumulative_time >= target {
+                    return state;
+                }
             }
-            second_moment /= self.arrivals_times[sample_count - 1] - self.arrivals_times[0];
-            second_moment - self.mean() * self.mean()
+        }
+        // Erros de arredondamento podem deixar o tempo acumulado um pouco abaixo do alvo
+        last_visited_state
+    }
+
+    // Fração do tempo em que o processo ficou no estado dado
+    pub fn state_probability(&self, state: usize) -> f64 {
+        let duration = self.duration();
+        match self.occupancy_times.get(state) {
+            Some(time) if duration > 0.0 => time / duration,
+            _ => 0.0,
+        }
+    }
+
+    // Quantidade de vezes em que o valor do processo aumentou. Para o número de fregueses no
+    // sistema com buffer infinito, cada aumento é uma chegada
+    pub fn upward_jumps(&self) -> usize {
+        self.upward_jumps
+    }
+
+    // Duração do intervalo observado, do primeiro registro ao último ou ao fechamento
+    pub fn duration(&self) -> f64 {
+        if self.count > 0 {
+            self.last_time - self.first_time
         } else {
             0.0
         }
     }
+
+    // Integral do processo ao longo do intervalo observado
+    pub fn integral(&self) -> f64 {
+        self.power_integral(1)
+    }
+
+    // Integral do quadrado do processo ao longo do intervalo observado
+    pub fn squared_integral(&self) -> f64 {
+        self.power_integral(2)
+    }
+
+    // Distribuição de ocupação dos estados ponderada pelo tempo, ou seja, um histograma com uma
+    // classe por estado k cujo peso é o tempo que o processo ficou em k, estimando P(N = k)
+    pub fn occupancy_histogram(&self) -> Histogram {
+        let mut histogram = Histogram::new(1.0);
+        for (state, &time) in self.occupancy_times.iter().enumerate() {
+            histogram.append(state as f64, time);
+        }
+        histogram
+    }
+
+    // Divide o intervalo observado em fatias de tempo de mesma duração e calcula a média temporal
+    // de uma transformação do processo em cada uma delas, produzindo uma série discreta que pode
+    // ser analisada pelos métodos de médias em lotes
+    pub fn time_slot_averages<F: Fn(f64) -> f64>(
+        &self,
+        slot_count: usize,
+        transform: F,
+    ) -> Vec<f64> {
+        self.assert_raw();
+        let sample_count = self.values.len();
+        let mut averages = vec![0.0; slot_count];
+        if sample_count < 2 || slot_count == 0 {
+            return averages;
+        }
+        let start = self.arrivals_times[0];
+        let slot_duration = (self.arrivals_times[sample_count - 1] - start) / slot_count as f64;
+        // Fatia atual e o instante em que ela termina
+        let mut slot = 0;
+        let mut slot_end = start + slot_duration;
+        for index in 0..(sample_count - 1) {
+            let value = transform(self.values[index] as f64);
+            let mut segment_start = self.arrivals_times[index];
+            let segment_end = self.arrivals_times[index + 1];
+            // Um mesmo valor pode se estender por várias fatias, então o distribuímos entre elas
+            while segment_start < segment_end {
+                while slot < slot_count - 1 && segment_start >= slot_end {
+                    slot += 1;
+                    slot_end = start + (slot + 1) as f64 * slot_duration;
+                }
+                let piece_end = if slot == slot_count - 1 {
+                    segment_end
+                } else {
+                    f64::min(slot_end, segment_end)
+                };
+                averages[slot] += value * (piece_end - segment_start);
+                segment_start = piece_end;
+            }
+        }
+        averages
+            .iter()
+            .map(|integral| integral / slot_duration)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::test_helpers::assert_close;
+
+    // Trajetória N = 0 em [0, 1), N = 2 em [1, 3) e N = 1 em [3, 6), fechada em 6. O processo fica
+    // 1 unidade de tempo no estado 0, 3 no estado 1 e 2 no estado 2
+    fn staircase(storage: SampleStorage) -> StochasticProcessSample {
+        let mut sample = StochasticProcessSample::with_storage(4, storage);
+        sample.append(0.0, 0);
+        sample.append(1.0, 2);
+        sample.append(3.0, 1);
+        sample.close(6.0);
+        sample
+    }
+
+    #[test]
+    fn closed_staircase_moments() {
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let sample = staircase(storage);
+            assert_close(sample.duration(), 6.0);
+            assert_close(sample.integral(), 7.0);
+            assert_close(sample.squared_integral(), 11.0);
+            assert_close(sample.mean(), 7.0 / 6.0);
+            assert_close(sample.moment(2), 11.0 / 6.0);
+            assert_close(sample.moment(3), 19.0 / 6.0);
+            assert_close(sample.variance(), 17.0 / 36.0);
+            assert_close(sample.central_moment(3), -2.0 / 27.0);
+            assert_close(sample.state_probability(0), 1.0 / 6.0);
+            assert_close(sample.state_probability(3), 0.0);
+            // Só o salto de 0 para 2 aumenta o valor
+            assert_eq!(sample.upward_jumps(), 1);
+        }
+    }
+
+    #[test]
+    fn last_state_is_ignored_until_closed() {
+        let mut sample = StochasticProcessSample::with_storage(4, SampleStorage::Raw);
+        sample.append(0.0, 0);
+        sample.append(1.0, 2);
+        sample.append(3.0, 1);
+        // Sem o fechamento o estado 1 ainda não vigorou por tempo algum
+        assert_close(sample.duration(), 3.0);
+        assert_close(sample.mean(), 4.0 / 3.0);
+        sample.close(6.0);
+        assert_close(sample.mean(), 7.0 / 6.0);
+    }
+
+    #[test]
+    fn closing_at_the_last_record_adds_nothing() {
+        let mut sample = StochasticProcessSample::with_storage(2, SampleStorage::Raw);
+        sample.append(0.0, 1);
+        sample.append(2.0, 3);
+        sample.close(2.0);
+        assert_close(sample.duration(), 2.0);
+        assert_close(sample.mean(), 1.0);
+        assert_close(sample.variance(), 0.0);
+        assert_eq!(sample.time_slot_averages(2, |value| value), vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn empty_sample_has_null_estimators() {
+        let mut sample = StochasticProcessSample::with_storage(0, SampleStorage::Streaming);
+        sample.close(5.0);
+        assert_close(sample.duration(), 0.0);
+        assert_close(sample.mean(), 0.0);
+        assert_close(sample.variance(), 0.0);
+        assert_eq!(sample.quantile(0.5), 0);
+    }
+
+    #[test]
+    fn time_weighted_quantiles() {
+        // P(N <= 0) = 1/6, P(N <= 1) = 4/6 e P(N <= 2) = 1
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let sample = staircase(storage);
+            assert_eq!(sample.quantile(0.0), 0);
+            assert_eq!(sample.quantile(0.1), 0);
+            assert_eq!(sample.quantile(0.5), 1);
+            assert_eq!(sample.quantile(0.9), 2);
+            assert_eq!(sample.quantile(1.0), 2);
+        }
+    }
+
+    #[test]
+    fn quantile_skips_unvisited_states() {
+        // O estado 0 nunca é visitado, então nem o quantil 0 pode ser 0
+        let mut sample = StochasticProcessSample::with_storage(2, SampleStorage::Streaming);
+        sample.append(0.0, 2);
+        sample.append(1.0, 3);
+        sample.close(2.0);
+        assert_eq!(sample.quantile(0.0), 2);
+        assert_eq!(sample.quantile(0.75), 3);
+    }
+
+    #[test]
+    fn occupancy_histogram_uses_time_weights() {
+        let bins = staircase(SampleStorage::Streaming)
+            .occupancy_histogram()
+            .bins();
+        let probabilities: Vec<f64> = bins.iter().map(|bin| bin.probability).collect();
+        assert_eq!(probabilities.len(), 3);
+        assert_close(probabilities[0], 1.0 / 6.0);
+        assert_close(probabilities[1], 3.0 / 6.0);
+        assert_close(probabilities[2], 2.0 / 6.0);
+        assert_close(bins[2].cumulative, 1.0);
+    }
+
+    #[test]
+    fn time_slot_averages_include_the_closed_segment() {
+        // Fatias [0, 2), [2, 4) e [4, 6)
+        let averages = staircase(SampleStorage::Raw).time_slot_averages(3, |value| value);
+        assert_eq!(averages.len(), 3);
+        assert_close(averages[0], 1.0);
+        assert_close(averages[1], 1.5);
+        assert_close(averages[2], 1.0);
+    }
+
+    #[test]
+    fn merge_concatenates_closed_trajectories() {
+        // Segunda trajetória: N = 1 em [10, 12) e N = 0 em [12, 13)
+        let second = |storage| {
+            let mut sample = StochasticProcessSample::with_storage(2, storage);
+            sample.append(10.0, 1);
+            sample.append(12.0, 0);
+            sample.close(13.0);
+            sample
+        };
+        for &storage in [SampleStorage::Raw, SampleStorage::Streaming].iter() {
+            let mut merged = StochasticProcessSample::with_storage(0, storage);
+            merged.merge(&staircase(storage));
+            merged.merge(&second(storage));
+            assert_close(merged.duration(), 9.0);
+            assert_close(merged.mean(), 1.0);
+            assert_close(merged.moment(2), 13.0 / 9.0);
+            assert_eq!(merged.quantile(0.5), 1);
+            assert_eq!(merged.upward_jumps(), 1);
+        }
+        // A trajetória guardada continua a primeira, deslocada para começar no instante 6
+        let mut merged = staircase(SampleStorage::Raw);
+        merged.merge(&second(SampleStorage::Raw));
+        let averages = merged.time_slot_averages(3, |value| value);
+        assert_close(averages[0], 4.0 / 3.0);
+        assert_close(averages[1], 1.0);
+        assert_close(averages[2], 2.0 / 3.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "fechada")]
+    fn append_after_close_panics() {
+        let mut sample = staircase(SampleStorage::Streaming);
+        sample.append(7.0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "fechadas")]
+    fn merging_an_open_sample_panics() {
+        let mut open = StochasticProcessSample::with_storage(1, SampleStorage::Streaming);
+        open.append(0.0, 1);
+        StochasticProcessSample::with_storage(0, SampleStorage::Streaming).merge(&open);
+    }
+
+    #[test]
+    #[should_panic(expected = "anterior")]
+    fn closing_before_the_last_record_panics() {
+        let mut sample = StochasticProcessSample::with_storage(2, SampleStorage::Streaming);
+        sample.append(0.0, 1);
+        sample.append(2.0, 0);
+        sample.close(1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "em fluxo")]
+    fn streaming_trajectory_is_not_stored() {
+        staircase(SampleStorage::Streaming).time_slot_averages(3, |value| value);
+    }
 }
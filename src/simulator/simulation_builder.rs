@@ -0,0 +1,299 @@
+// Importando os enums que configuram a simulação e a struct dos parâmetros
+use crate::simulator::output_analysis::quantiles::QuantileEstimator;
+use crate::simulator::output_analysis::AnalysisMethod;
+use crate::simulator::queue::{QueueMode, QueuePolicy};
+use crate::simulator::queue_observer::ObserverFactory;
+use crate::simulator::random_number_generator::GeneratorKind;
+use crate::simulator::results_writer::{ResultsFormat, WriteMode};
+use crate::simulator::warmup_detection::WarmupMethod;
+use crate::simulator::SimulationParameters;
+// Valores padrão do tamanho e da quantidade de rodadas, os mesmos da linha de comando
+use crate::{DEFAULT_ROUNDS_COUNT, DEFAULT_ROUND_SIZE};
+// Caminho de arquivos da biblioteca padrão
+use std::path::PathBuf;
+
+// Builder dos parâmetros de uma execução, para quem embute o simulador em outro programa. Começa
+// com os padrões da linha de comando: política FCFS, modo for-real e rodadas padrão, e os demais
+// valores de SimulationParameters::new
+pub struct SimulationBuilder {
+    parameters: SimulationParameters, // Parâmetros sendo configurados
+    // Máximo de fregueses da regra de parada, ou None para o padrão proporcional às rodadas
+    maximum_clients: Option<usize>,
+}
+
+impl SimulationBuilder {
+    // Instancia o builder com a utilização ρ da fila
+    pub fn new(rho: f64) -> Self {
+        Self {
+            parameters: SimulationParameters::new(
+                rho,
+                DEFAULT_ROUND_SIZE,
+                DEFAULT_ROUNDS_COUNT,
+                QueuePolicy::FCFS,
+                QueueMode::ForReal,
+            ),
+            maximum_clients: None,
+        }
+    }
+
+    // Quantidade de fregueses por rodada
+    pub fn round_size(mut self, round_size: usize) -> Self {
+        self.parameters.round_size = round_size;
+        self
+    }
+
+    // Quantidade inicial de rodadas
+    pub fn rounds_count(mut self, rounds_count: usize) -> Self {
+        self.parameters.rounds_count = rounds_count;
+        self
+    }
+
+    // Política de atendimento
+    pub fn queue_policy(mut self, queue_policy: QueuePolicy) -> Self {
+        self.parameters.queue_policy = queue_policy;
+        self
+    }
+
+    // Modo de simulação
+    pub fn queue_mode(mut self, queue_mode: QueueMode) -> Self {
+        self.parameters.queue_mode = queue_mode;
+        self
+    }
+
+    // Gerador de números aleatórios
+    pub fn generator_kind(mut self, generator_kind: GeneratorKind) -> Self {
+        self.parameters.generator_kind = generator_kind;
+        self
+    }
+
+    // Nível de confiança dos ICs
+    pub fn confidence_level(mut self, confidence_level: f64) -> Self {
+        self.parameters.confidence_level = confidence_level;
+        self
+    }
+
+    // Método de análise de saída
+    pub fn analysis_method(mut self, analysis_method: AnalysisMethod) -> Self {
+        self.parameters.analysis_method = analysis_method;
+        self
+    }
+
+    // Procedimento que detecta o fim da fase transiente
+    pub fn warmup_method(mut self, warmup_method: WarmupMethod) -> Self {
+        self.parameters.warmup_method = warmup_method;
+        self
+    }
+
+    // Precisão relativa alvo dos ICs
+    pub fn target_precision(mut self, target_precision: f64) -> Self {
+        self.parameters.target_precision = target_precision;
+        self
+    }
+
+    // Quantidade máxima de fregueses da regra de parada
+    pub fn maximum_clients(mut self, maximum_clients: usize) -> Self {
+        self.maximum_clients = Some(maximum_clients);
+        self
+    }
+
+    // Estimador dos quantis de W e T
+    pub fn quantile_estimator(mut self, quantile_estimator: QuantileEstimator) -> Self {
+        self.parameters.quantile_estimator = quantile_estimator;
+        self
+    }
+
+    // Exporta os histogramas de W, T e N num .csv
+    pub fn export_distributions(mut self, export_distributions: bool) -> Self {
+        self.parameters.export_distributions = export_distributions;
+        self
+    }
+
+    // Maior lag da análise de autocorrelação
+    pub fn autocorrelation_max_lag(mut self, max_lag: usize) -> Self {
+        self.parameters.autocorrelation_max_lag = Some(max_lag);
+        self
+    }
+
+    // Métricas que usam acumuladores em fluxo, entre as que o método não precisa dos valores
+    pub fn streaming_metrics(mut self, streaming_metrics: Vec<String>) -> Self {
+        self.parameters.streaming_metrics = streaming_metrics;
+        self
+    }
+
+    // Semente do gerador de números aleatórios
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.parameters.seed = Some(seed);
+        self
+    }
+
+    // Arquivo onde a função simulator escreve os resultados
+    pub fn output_path(mut self, output_path: PathBuf) -> Self {
+        self.parameters.output_path = output_path;
+        self
+    }
+
+    // Formato do arquivo dos resultados, em vez do indicado pela sua extensão
+    pub fn output_format(mut self, output_format: ResultsFormat) -> Self {
+        self.parameters.output_format = Some(output_format);
+        self
+    }
+
+    // Acrescenta os resultados ao arquivo ou o substitui na primeira escrita
+    pub fn output_mode(mut self, output_mode: WriteMode) -> Self {
+        self.parameters.output_mode = output_mode;
+        self
+    }
+
+    // Escreve só as colunas dadas dos resultados, na ordem dada
+    pub fn output_columns(mut self, output_columns: Vec<String>) -> Self {
+        self.parameters.output_columns = Some(output_columns);
+        self
+    }
+
+    // Desenha no diretório os gráficos da convergência das rodadas e do procedimento de Welch
+    pub fn plots_directory(mut self, plots_directory: PathBuf) -> Self {
+        self.parameters.plots_directory = Some(plots_directory);
+        self
+    }
+
+    // Acumula o relatório no resultado em vez de imprimi-lo, sem barras de progresso
+    pub fn deferred_output(mut self, deferred_output: bool) -> Self {
+        self.parameters.deferred_output = deferred_output;
+        self
+    }
+
+    // Quantidade de filas paralelas do método das rodadas
+    pub fn parallel_queues(mut self, parallel_queues: usize) -> Self {
+        self.parameters.parallel_queues = parallel_queues;
+        self
+    }
+
+    // Aceita ρ >= 1, simulando uma fila instável
+    pub fn allow_unstable(mut self, allow_unstable: bool) -> Self {
+        self.parameters.allow_unstable = allow_unstable;
+        self
+    }
+
+    // Observa cada fila simulada com um observador criado pela fábrica
+    pub fn observer(mut self, observer_factory: ObserverFactory) -> Self {
+        self.parameters.observer_factory = Some(observer_factory);
+        self
+    }
+
+    // Guarda um checkpoint no arquivo a cada interval rodadas
+    pub fn checkpoint(mut self, path: PathBuf, interval: usize) -> Self {
+        self.parameters.checkpoint_path = Some(path);
+        self.parameters.checkpoint_interval = interval;
+        self
+    }
+
+    // Constrói os parâmetros. Sem um máximo de fregueses explícito, ele acompanha o tamanho e a
+    // quantidade de rodadas escolhidos, como em SimulationParameters::new
+    pub fn build(self) -> SimulationParameters {
+        let mut parameters = self.parameters;
+        parameters.maximum_clients = self
+            .maximum_clients
+            .unwrap_or(50 * parameters.round_size * parameters.rounds_count);
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_command_line() {
+        let parameters = SimulationBuilder::new(0.5).build();
+        assert_eq!(parameters.rho, 0.5);
+        assert_eq!(parameters.round_size, DEFAULT_ROUND_SIZE);
+        assert_eq!(parameters.rounds_count, DEFAULT_ROUNDS_COUNT);
+        assert_eq!(
+            parameters.maximum_clients,
+            50 * DEFAULT_ROUND_SIZE * DEFAULT_ROUNDS_COUNT
+        );
+        assert!(parameters.seed.is_none());
+    }
+
+    #[test]
+    fn maximum_clients_follows_the_rounds_unless_given() {
+        let parameters = SimulationBuilder::new(0.5)
+            .round_size(10)
+            .rounds_count(4)
+            .build();
+        assert_eq!(parameters.maximum_clients, 2000);
+        let parameters = SimulationBuilder::new(0.5)
+            .round_size(10)
+            .maximum_clients(100)
+            .rounds_count(4)
+            .build();
+        assert_eq!(parameters.maximum_clients, 100);
+    }
+
+    #[test]
+    fn built_parameters_run_into_a_structured_report() {
+        let parameters = SimulationBuilder::new(0.3)
+            .round_size(100)
+            .rounds_count(10)
+            .target_precision(1.0)
+            .seed(3)
+            .deferred_output(true)
+            .build();
+        let report = crate::simulator::run(&parameters).unwrap();
+        assert_eq!(report.seed, 3);
+        assert!(report.total_clients >= 1000);
+        assert!(report.transient_phase_size > 0);
+        let analytic_values = report.analytic_values.unwrap();
+        assert!((analytic_values.mean_w - 0.3 / 0.7).abs() < 1e-12);
+        // As colunas conferidas com o arquivo antes da simulação são as escritas depois dela
+        let column_names: Vec<&String> = report
+            .results
+            .columns()
+            .iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            column_names,
+            crate::simulator::results_column_names()
+                .iter()
+                .collect::<Vec<_>>()
+        );
+        assert!(report.text.starts_with("Semente = 3"));
+    }
+
+    #[test]
+    fn every_parallel_queue_gets_its_own_observer() {
+        use crate::simulator::{Client, QueueObserver};
+        use std::sync::{Arc, Mutex};
+
+        // Observador que conta as saídas da sua fila num contador compartilhado por semente
+        struct Departures(Arc<Mutex<Vec<(u64, usize)>>>, usize);
+        impl QueueObserver for Departures {
+            fn on_departure(&mut self, _client: &Client, _time: f64) {
+                self.0.lock().unwrap()[self.1].1 += 1;
+            }
+        }
+
+        let departures = Arc::new(Mutex::new(vec![]));
+        let shared = Arc::clone(&departures);
+        let factory = ObserverFactory::new(move |seed| {
+            let mut departures = shared.lock().unwrap();
+            departures.push((seed, 0));
+            Box::new(Departures(Arc::clone(&shared), departures.len() - 1))
+        });
+        let parameters = SimulationBuilder::new(0.3)
+            .round_size(100)
+            .rounds_count(10)
+            .parallel_queues(2)
+            .seed(3)
+            .deferred_output(true)
+            .observer(factory)
+            .build();
+        let report = crate::simulator::run(&parameters).unwrap();
+        let departures = departures.lock().unwrap();
+        assert_eq!(departures.len(), 2);
+        assert_ne!(departures[0].0, departures[1].0);
+        let total: usize = departures.iter().map(|(_, count)| count).sum();
+        assert!(total >= report.total_clients);
+    }
+}
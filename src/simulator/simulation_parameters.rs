@@ -0,0 +1,371 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando os enums que configuram a simulação
+use crate::simulator::output_analysis::quantiles::QuantileEstimator;
+use crate::simulator::output_analysis::AnalysisMethod;
+use crate::simulator::queue::{QueueMode, QueuePolicy, T, W};
+use crate::simulator::queue_observer::ObserverFactory;
+use crate::simulator::random_number_generator::GeneratorKind;
+use crate::simulator::results_writer::{ResultsFormat, WriteMode};
+use crate::simulator::warmup_detection::WarmupMethod;
+// Caminho de arquivos da biblioteca padrão
+use std::path::PathBuf;
+
+// Struct com os parâmetros de uma execução do simulador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationParameters {
+    pub rho: f64,                              // Taxa de utilização do sistema
+    pub round_size: usize,                     // Quantidade de fregueses por rodada
+    pub rounds_count: usize,                   // Quantidade de rodadas
+    pub queue_policy: QueuePolicy,             // Política de atendimento FCFS ou LCFS
+    pub queue_mode: QueueMode,                 // Modo de simulação
+    pub generator_kind: GeneratorKind,         // Gerador de números aleatórios
+    pub confidence_level: f64,                 // Nível de confiança dos ICs, por exemplo 0.95
+    pub analysis_method: AnalysisMethod,       // Método de análise usado para construir os ICs
+    pub warmup_method: WarmupMethod,           // Procedimento que detecta o fim da fase transiente
+    pub target_precision: f64,                 // Precisão relativa alvo dos ICs, por exemplo 0.05
+    pub maximum_clients: usize,                // Quantidade máxima de fregueses da regra de parada
+    pub quantile_estimator: QuantileEstimator, // Estimador dos quantis de W e T
+    pub export_distributions: bool,            // Exporta os histogramas de W, T e N num .csv
+    // Maior lag da análise de autocorrelação, se pedida
+    pub autocorrelation_max_lag: Option<usize>,
+    // Métricas cujos acumuladores funcionam em fluxo, com memória constante, sem guardar os
+    // valores. Só valem as que o método de análise não precisa dos valores
+    pub streaming_metrics: Vec<String>,
+    // Semente do gerador de números aleatórios, ou uma tirada do relógio quando não é dada
+    pub seed: Option<u64>,
+    // Arquivo onde os resultados de cada execução são escritos
+    pub output_path: PathBuf,
+    // Formato do arquivo dos resultados, ou o da extensão do arquivo quando não é dado
+    pub output_format: Option<ResultsFormat>,
+    // Acrescenta os resultados aos que já estão no arquivo ou substitui o arquivo
+    pub output_mode: WriteMode,
+    // Colunas dos resultados escritas no arquivo, na ordem dada, ou todas quando não são dadas
+    pub output_columns: Option<Vec<String>>,
+    // Diretório onde os gráficos da convergência das rodadas e do procedimento de Welch são
+    // desenhados, se pedidos
+    pub plots_directory: Option<PathBuf>,
+    // Acumula o relatório em vez de imprimi-lo e suprime as barras de progresso e as mensagens de
+    // andamento, como nas execuções de um sweep paralelo
+    pub deferred_output: bool,
+    // Quantidade de filas independentes, cada uma com sua semente e fase transiente, que dividem
+    // as rodadas entre si em paralelo
+    pub parallel_queues: usize,
+    // Arquivo onde o estado do método das rodadas é guardado periodicamente, se pedido
+    pub checkpoint_path: Option<PathBuf>,
+    // Quantidade de rodadas entre dois checkpoints
+    pub checkpoint_interval: usize,
+    // Aceita ρ >= 1 no modo for-real. A fila instável não tem regime estacionário, então não há
+    // valores analíticos e a regra de parada tende a esgotar o máximo de fregueses
+    pub allow_unstable: bool,
+    // Fábrica dos observadores das filas simuladas, se houver. Não é guardada nos checkpoints,
+    // então uma execução retomada não é observada
+    #[serde(skip)]
+    pub observer_factory: Option<ObserverFactory>,
+}
+
+impl SimulationParameters {
+    // Instancia os parâmetros com o gerador padrão, 95% de confiança, o método das rodadas, a
+    // heurística da utilização para a fase transiente e precisão alvo de 5%, limitada a 50 vezes a
+    // quantidade inicial de fregueses. Os quantis são calculados de forma exata e as distribuições
+    // completas e a análise de autocorrelação não são exportadas. Todas as amostras são guardadas,
+    // a semente vem do relógio, todas as colunas dos resultados são acrescentadas ao output.csv,
+    // nenhum gráfico é desenhado e o relatório é impresso na hora, com uma única fila e sem
+    // checkpoints. Só filas estáveis são aceitas, e nenhuma fila é observada
+    pub fn new(
+        rho: f64,
+        round_size: usize,
+        rounds_count: usize,
+        queue_policy: QueuePolicy,
+        queue_mode: QueueMode,
+    ) -> Self {
+        Self {
+            rho,
+            round_size,
+            rounds_count,
+            queue_policy,
+            queue_mode,
+            generator_kind: GeneratorKind::Std,
+            confidence_level: 0.95,
+            analysis_method: AnalysisMethod::Rounds,
+            warmup_method: WarmupMethod::Utilization,
+            target_precision: 0.05,
+            maximum_clients: 50 * round_size * rounds_count,
+            quantile_estimator: QuantileEstimator::Exact,
+            export_distributions: false,
+            autocorrelation_max_lag: None,
+            streaming_metrics: vec![],
+            seed: None,
+            output_path: PathBuf::from("output.csv"),
+            output_format: None,
+            output_mode: WriteMode::Append,
+            output_columns: None,
+            plots_directory: None,
+            deferred_output: false,
+            parallel_queues: 1,
+            checkpoint_path: None,
+            checkpoint_interval: 100,
+            allow_unstable: false,
+            observer_factory: None,
+        }
+    }
+
+    // Indica se a fila simulada tem regime estacionário. No modo de corretude ρ é desconsiderado
+    pub fn stable(&self) -> bool {
+        self.queue_mode == QueueMode::CheckCorrectness || self.rho < 1.0
+    }
+
+    // Métricas que podem ser acumuladas em fluxo: as que o método de análise dispensa e, nas
+    // rodadas e replicações com o estimador P², cujos coletores acompanham os quantis, também W
+    // e T, a não ser que as distribuições ou a autocorrelação, que precisam dos valores, sejam
+    // pedidas
+    pub fn streamable_metrics(&self) -> Vec<&'static str> {
+        let mut metrics = self.analysis_method.streamable_metrics().to_vec();
+        let streamed_quantiles = self.quantile_estimator == QuantileEstimator::PSquare
+            && matches!(
+                self.analysis_method,
+                AnalysisMethod::Rounds | AnalysisMethod::Replications
+            )
+            && !self.export_distributions;
+        if streamed_quantiles {
+            if self.autocorrelation_max_lag.is_none() {
+                metrics.push(W);
+            }
+            metrics.push(T);
+        }
+        metrics
+    }
+
+    // Verifica a consistência dos parâmetros antes de a simulação começar, devolvendo todos os
+    // problemas encontrados de uma vez
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let problems = self.field_problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SimulationError::InvalidParameters(
+                problems.into_iter().map(|(_, problem)| problem).collect(),
+            ))
+        }
+    }
+
+    // Problemas encontrados pela validação, cada um com o nome do campo a que se refere, para que
+    // quem montou os parâmetros, como um arquivo de experimento, aponte a sua própria chave
+    pub fn field_problems(&self) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        let mut check = |field: &'static str, condition: bool, problem: String| {
+            if !condition {
+                problems.push((field, problem));
+            }
+        };
+        if self.queue_mode == QueueMode::ForReal {
+            check(
+                "rho",
+                self.rho > 0.0 && self.rho.is_finite(),
+                format!("ρ = {} deve ser positivo", self.rho),
+            );
+            check(
+                "rho",
+                self.rho < 1.0 || self.allow_unstable,
+                format!(
+                    "ρ = {} deve estar em (0, 1) para a fila ser estável, a não ser que filas \
+                     instáveis sejam permitidas",
+                    self.rho
+                ),
+            );
+            check(
+                "analysis_method",
+                self.stable() || self.analysis_method != AnalysisMethod::Regenerative,
+                "o método regenerativo precisa que o sistema esvazie, o que uma fila instável \
+                 pode nunca fazer"
+                    .to_string(),
+            );
+            // Cada rodada só termina quando saem todos os seus fregueses, e numa fila instável o
+            // acúmulo cresce a cada rodada, então elas levariam cada vez mais tempo. Como as filas
+            // paralelas só existem nas rodadas, elas também ficam de fora
+            check(
+                "analysis_method",
+                self.stable() || self.analysis_method != AnalysisMethod::Rounds,
+                "o método das rodadas espera a saída de todos os fregueses de cada rodada, o que \
+                 numa fila instável leva cada vez mais tempo"
+                    .to_string(),
+            );
+        }
+        check(
+            "round_size",
+            self.round_size > 0,
+            "o tamanho das rodadas deve ser positivo".to_string(),
+        );
+        check(
+            "rounds_count",
+            self.rounds_count >= 2,
+            "são necessárias pelo menos 2 rodadas para construir os ICs".to_string(),
+        );
+        check(
+            "confidence_level",
+            self.confidence_level > 0.0 && self.confidence_level < 1.0,
+            format!(
+                "o nível de confiança {} deve estar em (0, 1)",
+                self.confidence_level
+            ),
+        );
+        check(
+            "target_precision",
+            self.target_precision > 0.0,
+            format!(
+                "a precisão alvo {} deve ser positiva",
+                self.target_precision
+            ),
+        );
+        check(
+            "maximum_clients",
+            self.maximum_clients >= self.round_size.saturating_mul(self.rounds_count),
+            format!(
+                "o máximo de {} fregueses deve ser pelo menos a quantidade inicial de fregueses, \
+                 {} rodadas de {}",
+                self.maximum_clients, self.rounds_count, self.round_size
+            ),
+        );
+        let streamable_metrics = self.streamable_metrics();
+        for metric in self.streaming_metrics.iter() {
+            check(
+                "streaming_metrics",
+                streamable_metrics.contains(&metric.as_str()),
+                format!(
+                    "a métrica {} não pode ser acumulada em fluxo com o método {:?} e os quantis \
+                     {:?}, que só dispensam os valores de {}",
+                    metric,
+                    self.analysis_method,
+                    self.quantile_estimator,
+                    streamable_metrics.join(", ")
+                ),
+            );
+        }
+        check(
+            "autocorrelation_max_lag",
+            self.autocorrelation_max_lag != Some(0),
+            "o lag da análise de autocorrelação deve ser positivo".to_string(),
+        );
+        check(
+            "parallel_queues",
+            self.parallel_queues > 0,
+            "a quantidade de filas paralelas deve ser positiva".to_string(),
+        );
+        check(
+            "parallel_queues",
+            self.parallel_queues <= 1 || self.analysis_method == AnalysisMethod::Rounds,
+            "só o método das rodadas divide as rodadas entre filas paralelas".to_string(),
+        );
+        if self.checkpoint_path.is_some() {
+            check(
+                "checkpoint_path",
+                self.analysis_method == AnalysisMethod::Rounds,
+                "só o método das rodadas guarda checkpoints".to_string(),
+            );
+            check(
+                "checkpoint_interval",
+                self.checkpoint_interval > 0,
+                "o intervalo entre checkpoints deve ter pelo menos uma rodada".to_string(),
+            );
+        }
+        if let Some(columns) = self.output_columns.as_ref() {
+            check(
+                "output_columns",
+                !columns.is_empty(),
+                "a lista de colunas dos resultados não pode ser vazia".to_string(),
+            );
+            for (index, column) in columns.iter().enumerate() {
+                check(
+                    "output_columns",
+                    !columns[..index].contains(column),
+                    format!(
+                        "a coluna {} dos resultados foi escolhida mais de uma vez",
+                        column
+                    ),
+                );
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parâmetros válidos de uma execução curta
+    fn parameters(rho: f64) -> SimulationParameters {
+        SimulationParameters::new(rho, 100, 10, QueuePolicy::FCFS, QueueMode::ForReal)
+    }
+
+    // Problemas encontrados na validação dos parâmetros
+    fn problems(parameters: &SimulationParameters) -> Vec<String> {
+        match parameters.validate() {
+            Ok(()) => vec![],
+            Err(SimulationError::InvalidParameters(problems)) => problems,
+            Err(error) => panic!("erro inesperado: {}", error),
+        }
+    }
+
+    #[test]
+    fn unstable_queues_are_rejected_unless_allowed() {
+        assert!(problems(&parameters(0.5)).is_empty());
+        // Com o método das rodadas, o padrão, nem permitir a fila instável bastaria
+        assert_eq!(problems(&parameters(1.2)).len(), 2);
+        assert_eq!(problems(&parameters(0.0)).len(), 1);
+        let mut unstable = parameters(1.2);
+        unstable.allow_unstable = true;
+        unstable.analysis_method = AnalysisMethod::BatchMeans;
+        assert!(problems(&unstable).is_empty());
+        unstable.analysis_method = AnalysisMethod::Replications;
+        assert!(problems(&unstable).is_empty());
+        unstable.analysis_method = AnalysisMethod::Regenerative;
+        assert_eq!(problems(&unstable).len(), 1);
+        unstable.analysis_method = AnalysisMethod::Rounds;
+        assert_eq!(problems(&unstable).len(), 1);
+        unstable.parallel_queues = 4;
+        assert_eq!(problems(&unstable).len(), 1);
+        let correctness =
+            SimulationParameters::new(7.0, 100, 10, QueuePolicy::FCFS, QueueMode::CheckCorrectness);
+        assert!(problems(&correctness).is_empty());
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let mut invalid = parameters(0.5);
+        invalid.round_size = 0;
+        invalid.rounds_count = 1;
+        invalid.confidence_level = 1.0;
+        invalid.parallel_queues = 2;
+        invalid.analysis_method = AnalysisMethod::BatchMeans;
+        assert_eq!(problems(&invalid).len(), 4);
+        let mut small_budget = parameters(0.5);
+        small_budget.maximum_clients = 999;
+        assert_eq!(problems(&small_budget).len(), 1);
+    }
+
+    #[test]
+    fn only_dispensable_metrics_are_streamed() {
+        let mut streaming = parameters(0.5);
+        streaming.streaming_metrics = vec!["X".to_string(), "N".to_string(), "Nq".to_string()];
+        assert!(problems(&streaming).is_empty());
+        // As médias em lotes precisam da trajetória de Nq, e os quantis dos valores de W
+        streaming.analysis_method = AnalysisMethod::BatchMeans;
+        assert_eq!(problems(&streaming).len(), 1);
+        streaming.streaming_metrics = vec!["W".to_string(), "Z".to_string()];
+        assert_eq!(problems(&streaming).len(), 2);
+    }
+
+    #[test]
+    fn output_columns_must_be_distinct_and_not_empty() {
+        let mut columns = parameters(0.5);
+        columns.output_columns = Some(vec![]);
+        assert_eq!(problems(&columns).len(), 1);
+        columns.output_columns = Some(vec!["E[W]".to_string(), "E[W]".to_string()]);
+        assert_eq!(problems(&columns).len(), 1);
+        columns.output_columns = Some(vec!["E[W]".to_string(), "rho".to_string()]);
+        assert!(problems(&columns).is_empty());
+    }
+}
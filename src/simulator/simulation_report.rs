@@ -0,0 +1,57 @@
+// Importando a trait dos modelos analíticos
+use crate::simulator::analytic_models::AnalyticModel;
+// Importando a representação das estimativas e o motivo da parada
+use crate::simulator::output_analysis::sequential::StopReason;
+use crate::simulator::output_analysis::Estimates;
+// Importando as colunas dos resultados de uma execução
+use crate::simulator::results_writer::ResultsRecord;
+// Importando a struct com os parâmetros de uma execução do simulador
+use crate::simulator::SimulationParameters;
+// Duração da biblioteca padrão
+use std::time::Duration;
+
+// Valores analíticos do modelo simulado. As variâncias que não têm forma fechada ficam como None
+#[derive(Debug, Clone)]
+pub struct AnalyticValues {
+    pub model: String,            // Nome do modelo
+    pub exact: bool,              // Indica se os valores são exatos ou uma aproximação
+    pub mean_w: f64,              // E[W]
+    pub variance_w: Option<f64>,  // V(W)
+    pub mean_nq: f64,             // E[Nq]
+    pub variance_nq: Option<f64>, // V(Nq)
+    pub mean_n: f64,              // E[N]
+    pub waiting_probability: f64, // P(W > 0)
+}
+
+impl AnalyticValues {
+    // Consulta os valores de um modelo analítico
+    pub fn new(model: &dyn AnalyticModel) -> Self {
+        Self {
+            model: model.name(),
+            exact: model.exact(),
+            mean_w: model.mean_w(),
+            variance_w: model.variance_w(),
+            mean_nq: model.mean_nq(),
+            variance_nq: model.variance_nq(),
+            mean_n: model.mean_n(),
+            waiting_probability: model.waiting_probability(),
+        }
+    }
+}
+
+// Resultado estruturado de uma execução do simulador, para quem usa o simulador como biblioteca
+pub struct SimulationReport {
+    pub parameters: SimulationParameters, // Parâmetros da execução
+    pub seed: u64,                        // Semente usada, mesmo quando tirada do relógio
+    pub estimates: Estimates,             // Estimativas de todas as métricas com seus ICs
+    pub analytic_values: Option<AnalyticValues>, // Valores analíticos, None numa fila instável
+    pub stop_reason: StopReason,          // Motivo pelo qual a simulação parou de ser estendida
+    pub transient_phase_size: usize,      // Tamanho da fase transiente, em média nas replicações
+    pub total_clients: usize,             // Quantidade de fregueses usados nas estimativas
+    // Nomes das relações de consistência interna que as estimativas não satisfazem
+    pub consistency_violations: Vec<String>,
+    pub elapsed_time: Duration, // Tempo de execução, incluindo a fase transiente
+    pub results: ResultsRecord, // Colunas escritas no arquivo dos resultados
+    // Relatório textual, quando a saída da execução é adiada, ou vazio quando já foi impresso
+    pub text: String,
+}
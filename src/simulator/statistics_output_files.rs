@@ -1,110 +1,154 @@
-use crate::simulator::QueuePolicy;
-use std::fs::OpenOptions;
+use crate::error::SimulationError;
+use crate::simulator::output_analysis::autocorrelation::AutocorrelationAnalysis;
+use crate::simulator::output_analysis::distributions::DistributionRow;
+use crate::simulator::{AnalysisMethod, QueuePolicy};
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
-// Função que recebe inúmeros dados e escreve de maneira organizada num arquivo .csv
-pub fn write_csv_file(
-    rho: f64,
-    clients: usize,
-    transient_phase: usize,
-    policy: QueuePolicy,
-    means_n_t_x: &[f64; 3],
-    variances_n_t_x: &[f64; 3],
-    mean_and_ic_w: &[f64; 5],
-    variance_and_ic_t_student_chi_square_w: &[f64; 9],
-    mean_and_ic_nq: &[f64; 5],
-    variance_and_ic_t_student_chi_square_nq: &[f64; 9],
-    analytic_mean_w: f64,
-    analytic_variance_w: f64,
-    analytic_mean_nq: f64,
-    analytic_variance_nq: f64,
-    elapsed_time: f64,
-) {
-    let csv_file_path = Path::new("output.csv"); // Path do arquivo csv
-    let mut file = if csv_file_path.exists() {
+// Trava tomada durante cada escrita, para que execuções paralelas não criem o mesmo arquivo duas
+// vezes nem intercalem seus blocos de linhas
+static CSV_FILES_LOCK: Mutex<()> = Mutex::new(());
+
+// Toma a trava das escritas. Ela não protege nenhum dado, então uma escrita que entrou em pânico
+// com a trava tomada não impede as próximas
+fn lock_csv_files() -> MutexGuard<'static, ()> {
+    CSV_FILES_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+// Caminho de um arquivo auxiliar, escrito no mesmo diretório do arquivo de resultados
+pub fn side_file_path(output_path: &Path, file_name: &str) -> PathBuf {
+    output_path.with_file_name(file_name)
+}
+
+// Abre um arquivo .csv em modo append ou, caso ele não exista, cria o arquivo com o cabeçalho
+fn open_csv_file(csv_file_path: &Path, header: &[u8]) -> Result<File, SimulationError> {
+    if csv_file_path.exists() {
         // Caso o arquivo já exista, abrimos o mesmo em modo append para inserir os dados ao final
         OpenOptions::new()
             .append(true)
             .open(csv_file_path)
-            .expect("Unable to open csv file")
+            .map_err(SimulationError::io(csv_file_path))
     } else {
         // Caso não existe, criamos um novo arquivo e colocamos o cabeçalho das colunas nele
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
+            .truncate(true)
             .open(csv_file_path)
-            .expect("Unable to open csv file");
-        file.write_all(
-            [
-                "rho",
-                "fregueses",
-                "fase_transiente",
-                "policy",
-                "E[N]",
-                "E[T]",
-                "E[X]",
-                "V(N)",
-                "V(T)",
-                "V(X)",
-                "E[W]",
-                "E[W]_IC_TS_L",
-                "E[W]_IC_TS_C",
-                "E[W]_IC_TS_U",
-                "E[W]_IC_TS_P",
-                "V(W)",
-                "V(W)_IC_TS_L",
-                "V(W)_IC_TS_C",
-                "V(W)_IC_TS_U",
-                "V(W)_IC_TS_P",
-                "V(W)_IC_C2_L",
-                "V(W)_IC_C2_C",
-                "V(W)_IC_C2_U",
-                "V(W)_IC_C2_P",
-                "E[Nq]",
-                "E[Nq]_IC_TS_L",
-                "E[Nq]_IC_TS_C",
-                "E[Nq]_IC_TS_U",
-                "E[Nq]_IC_TS_P",
-                "V(Nq)",
-                "V(Nq)_IC_TS_L",
-                "V(Nq)_IC_TS_C",
-                "V(Nq)_IC_TS_U",
-                "V(Nq)_IC_TS_P",
-                "V(Nq)_IC_C2_L",
-                "V(Nq)_IC_C2_C",
-                "V(Nq)_IC_C2_U",
-                "V(Nq)_IC_C2_P",
-                "E[W]_analytic",
-                "V(W)_analytic",
-                "E[Nq]_analytic",
-                "V(Nq)_analytic",
-                "elapsed_time(s)\n",
-            ]
-            .join(",")
-            .as_bytes(),
-        )
-        .expect("Failed to write csv file");
-        file
-    };
+            .map_err(SimulationError::io(csv_file_path))?;
+        file.write_all(header)
+            .map_err(SimulationError::io(csv_file_path))?;
+        Ok(file)
+    }
+}
 
-    // Adicionamos os dados nas linhas do csv
-    let mut output_string = format!("{},{},{},{:?}", rho, clients, transient_phase, policy);
-    for num in means_n_t_x
+// Função que escreve as médias móveis de W e N do procedimento de Welch num arquivo .csv ao lado
+// do arquivo de resultados, para que o gráfico possa ser inspecionado visualmente
+pub fn write_welch_csv_file(
+    output_path: &Path,
+    rho: f64,
+    policy: QueuePolicy,
+    w_moving_averages: &[f64],
+    n_moving_averages: &[f64],
+) -> Result<(), SimulationError> {
+    let csv_file_path = side_file_path(output_path, "welch.csv"); // Path do arquivo csv
+    let _lock = lock_csv_files();
+    let mut file = open_csv_file(
+        &csv_file_path,
+        b"rho,policy,fregues,W_media_movel,N_media_movel\n",
+    )?;
+
+    // Uma linha por posição das médias móveis
+    let mut output_string = String::new();
+    for (index, (w, n)) in w_moving_averages
         .iter()
-        .chain(variances_n_t_x.iter())
-        .chain(mean_and_ic_w.iter())
-        .chain(variance_and_ic_t_student_chi_square_w.iter())
-        .chain(mean_and_ic_nq.iter())
-        .chain(variance_and_ic_t_student_chi_square_nq.iter())
+        .zip(n_moving_averages.iter())
+        .enumerate()
     {
-        output_string += &format!("{},", num);
+        output_string += &format!("{},{:?},{},{},{}\n", rho, policy, index, w, n);
+    }
+
+    file.write_all(output_string.as_bytes())
+        .map_err(SimulationError::io(&csv_file_path))
+}
+
+// Função que escreve os histogramas de W e T e a distribuição de ocupação de N num arquivo .csv ao
+// lado do arquivo de resultados, junto com as probabilidades e distribuições analíticas para
+// comparação
+pub fn write_distributions_csv_file(
+    output_path: &Path,
+    rho: f64,
+    policy: QueuePolicy,
+    rows: &[DistributionRow],
+) -> Result<(), SimulationError> {
+    let csv_file_path = side_file_path(output_path, "distributions.csv"); // Path do arquivo csv
+    let _lock = lock_csv_files();
+    let mut file = open_csv_file(
+        &csv_file_path,
+        b"rho,policy,metrica,inicio_classe,fim_classe,probabilidade,distribuicao_empirica,\
+probabilidade_analitica,distribuicao_analitica\n",
+    )?;
+
+    // Uma linha por classe de cada histograma
+    let mut output_string = String::new();
+    for row in rows {
+        output_string += &format!(
+            "{},{:?},{},{},{},{},{},{},{}\n",
+            rho,
+            policy,
+            row.metric,
+            row.bin.lower_bound,
+            row.bin.upper_bound,
+            row.bin.probability,
+            row.bin.cumulative,
+            row.analytic_probability,
+            row.analytic_cumulative
+        );
+    }
+
+    file.write_all(output_string.as_bytes())
+        .map_err(SimulationError::io(&csv_file_path))
+}
+
+// Função que escreve as funções de autocorrelação das séries de saída num arquivo .csv ao lado do
+// arquivo de resultados, junto com o limite de significância e o tempo de autocorrelação
+// integrado de cada série
+pub fn write_autocorrelation_csv_file(
+    output_path: &Path,
+    rho: f64,
+    policy: QueuePolicy,
+    analysis_method: AnalysisMethod,
+    analyses: &[(&str, &AutocorrelationAnalysis)],
+) -> Result<(), SimulationError> {
+    let csv_file_path = side_file_path(output_path, "autocorrelation.csv"); // Path do arquivo csv
+    let _lock = lock_csv_files();
+    let mut file = open_csv_file(
+        &csv_file_path,
+        b"rho,policy,method,serie,lag,autocorrelacao,limite_significancia,tempo_integrado\n",
+    )?;
+
+    // Uma linha por lag de cada série
+    let mut output_string = String::new();
+    for (series, analysis) in analyses {
+        for (lag, autocorrelation) in analysis.autocorrelations.iter().enumerate() {
+            output_string += &format!(
+                "{},{:?},{:?},{},{},{},{},{}\n",
+                rho,
+                policy,
+                analysis_method,
+                series,
+                lag,
+                autocorrelation,
+                analysis.significance_threshold,
+                analysis.integrated_time
+            );
+        }
     }
-    output_string += &format!(
-        "{},{},{},{},{}\n",
-        analytic_mean_w, analytic_variance_w, analytic_mean_nq, analytic_variance_nq, elapsed_time
-    );
 
     file.write_all(output_string.as_bytes())
-        .expect("Failed to write csv file");
+        .map_err(SimulationError::io(&csv_file_path))
 }
@@ -0,0 +1,448 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+
+// Escrita de arquivos, formatação de strings e caminhos da biblioteca padrão
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// Dimensões do gráfico e das margens em volta da área dos dados, em pixels. A margem direita
+// abriga a legenda
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 500.0;
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 190.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+// Quantidade aproximada de marcações em cada eixo
+const TICKS: usize = 5;
+
+// Estilo de desenho de uma série
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeriesStyle {
+    Line,       // Linha contínua ligando os pontos
+    DashedLine, // Linha tracejada, usada nas curvas analíticas
+    Markers,    // Círculos em cada ponto, usados nas estimativas com seus ICs
+    Dots,       // Pontos pequenos e translúcidos, para séries com muitos valores
+}
+
+// Série de um gráfico, com seus pontos e, opcionalmente, barras de erro
+#[derive(Debug, Clone)]
+pub struct Series {
+    name: String,                     // Nome exibido na legenda
+    color: &'static str,              // Cor da série, em qualquer formato aceito pelo SVG
+    style: SeriesStyle,               // Estilo de desenho
+    points: Vec<(f64, f64)>,          // Pontos (x, y), na ordem em que são ligados
+    error_bars: Vec<(f64, f64, f64)>, // Barras de erro (x, limite inferior, limite superior)
+}
+
+impl Series {
+    // Instancia a série com seus pontos e sem barras de erro
+    pub fn new(
+        name: &str,
+        color: &'static str,
+        style: SeriesStyle,
+        points: Vec<(f64, f64)>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+            style,
+            points,
+            error_bars: vec![],
+        }
+    }
+
+    // Acrescenta as barras de erro, como os ICs das estimativas
+    pub fn error_bars(mut self, error_bars: Vec<(f64, f64, f64)>) -> Self {
+        self.error_bars = error_bars;
+        self
+    }
+}
+
+// Gráfico de linhas e pontos em SVG, desenhado sem bibliotecas externas. Valores não finitos, como
+// os NaN das colunas sem valor, são ignorados, e uma linha é interrompida onde eles aparecem
+#[derive(Debug, Clone)]
+pub struct Chart {
+    title: String,                      // Título, acima da área dos dados
+    x_label: String,                    // Nome do eixo x
+    y_label: String,                    // Nome do eixo y
+    series: Vec<Series>,                // Séries, desenhadas na ordem em que foram acrescentadas
+    vertical_lines: Vec<(f64, String)>, // Linhas verticais de referência com seus rótulos
+}
+
+impl Chart {
+    // Instancia o gráfico sem nenhuma série
+    pub fn new(title: &str, x_label: &str, y_label: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            series: vec![],
+            vertical_lines: vec![],
+        }
+    }
+
+    // Acrescenta uma série ao gráfico
+    pub fn add_series(&mut self, series: Series) {
+        self.series.push(series);
+    }
+
+    // Acrescenta uma linha vertical de referência, como um ponto de truncamento
+    pub fn add_vertical_line(&mut self, x: f64, label: &str) {
+        self.vertical_lines.push((x, label.to_string()));
+    }
+
+    // Intervalos dos eixos x e y que contêm todos os valores finitos do gráfico
+    fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let mut xs = vec![];
+        let mut ys = vec![];
+        for series in self.series.iter() {
+            for &(x, y) in series.points.iter() {
+                if x.is_finite() && y.is_finite() {
+                    xs.push(x);
+                    ys.push(y);
+                }
+            }
+            for &(x, lower, upper) in series.error_bars.iter() {
+                if x.is_finite() && lower.is_finite() && upper.is_finite() {
+                    xs.push(x);
+                    ys.push(lower);
+                    ys.push(upper);
+                }
+            }
+        }
+        xs.extend(
+            self.vertical_lines
+                .iter()
+                .map(|(x, _)| *x)
+                .filter(|x| x.is_finite()),
+        );
+        (range(&xs), range(&ys))
+    }
+
+    // Desenha o gráfico como um documento SVG
+    pub fn to_svg(&self) -> String {
+        let ((x_min, x_max), (y_min, y_max)) = self.bounds();
+        let x_ticks = nice_ticks(x_min, x_max);
+        let y_ticks = nice_ticks(y_min, y_max);
+        let (x_min, x_max) = (x_ticks[0], x_ticks[x_ticks.len() - 1]);
+        let (y_min, y_max) = (y_ticks[0], y_ticks[y_ticks.len() - 1]);
+        let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        let x_pixel = |x: f64| MARGIN_LEFT + (x - x_min) / (x_max - x_min) * plot_width;
+        let y_pixel =
+            |y: f64| MARGIN_TOP + plot_height - (y - y_min) / (y_max - y_min) * plot_height;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\" font-family=\"sans-serif\" font-size=\"12\">",
+            WIDTH, HEIGHT
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+            WIDTH, HEIGHT
+        );
+
+        // Grade e marcações dos eixos
+        let x_decimals = tick_decimals(&x_ticks);
+        for &tick in x_ticks.iter() {
+            let x = x_pixel(tick);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{0:.2}\" y1=\"{1:.2}\" x2=\"{0:.2}\" y2=\"{2:.2}\" stroke=\"#e0e0e0\"/>",
+                x,
+                MARGIN_TOP,
+                MARGIN_TOP + plot_height
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\">{:.*}</text>",
+                x,
+                MARGIN_TOP + plot_height + 18.0,
+                x_decimals,
+                tick
+            );
+        }
+        let y_decimals = tick_decimals(&y_ticks);
+        for &tick in y_ticks.iter() {
+            let y = y_pixel(tick);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{1:.2}\" y1=\"{0:.2}\" x2=\"{2:.2}\" y2=\"{0:.2}\" stroke=\"#e0e0e0\"/>",
+                y,
+                MARGIN_LEFT,
+                MARGIN_LEFT + plot_width
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"end\">{:.*}</text>",
+                MARGIN_LEFT - 8.0,
+                y + 4.0,
+                y_decimals,
+                tick
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>",
+            MARGIN_LEFT, MARGIN_TOP, plot_width, plot_height
+        );
+
+        // Título e nomes dos eixos
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.2}\" y=\"30\" text-anchor=\"middle\" font-size=\"16\">{}</text>",
+            MARGIN_LEFT + plot_width / 2.0,
+            escape(&self.title)
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\">{}</text>",
+            MARGIN_LEFT + plot_width / 2.0,
+            HEIGHT - 15.0,
+            escape(&self.x_label)
+        );
+        let _ = writeln!(
+            svg,
+            "<text transform=\"translate(20 {:.2}) rotate(-90)\" text-anchor=\"middle\">{}</text>",
+            MARGIN_TOP + plot_height / 2.0,
+            escape(&self.y_label)
+        );
+
+        // Linhas verticais de referência, com o rótulo ao longo da linha
+        for (x, label) in self.vertical_lines.iter().filter(|(x, _)| x.is_finite()) {
+            let x = x_pixel(*x);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{0:.2}\" y1=\"{1:.2}\" x2=\"{0:.2}\" y2=\"{2:.2}\" stroke=\"gray\" \
+                 stroke-dasharray=\"2 3\"/>",
+                x,
+                MARGIN_TOP,
+                MARGIN_TOP + plot_height
+            );
+            let _ = writeln!(
+                svg,
+                "<text transform=\"translate({:.2} {:.2}) rotate(-90)\" text-anchor=\"end\" \
+                 fill=\"gray\">{}</text>",
+                x - 4.0,
+                MARGIN_TOP + 6.0,
+                escape(label)
+            );
+        }
+
+        // Séries, com as barras de erro desenhadas antes dos pontos
+        for series in self.series.iter() {
+            for &(x, lower, upper) in series.error_bars.iter() {
+                if !(x.is_finite() && lower.is_finite() && upper.is_finite()) {
+                    continue;
+                }
+                let (x, lower, upper) = (x_pixel(x), y_pixel(lower), y_pixel(upper));
+                let _ = writeln!(
+                    svg,
+                    "<path d=\"M{0:.2} {1:.2}V{2:.2}M{3:.2} {1:.2}H{4:.2}M{3:.2} {2:.2}H{4:.2}\" \
+                     stroke=\"{5}\" fill=\"none\"/>",
+                    x,
+                    lower,
+                    upper,
+                    x - 4.0,
+                    x + 4.0,
+                    series.color
+                );
+            }
+            let points = series
+                .points
+                .iter()
+                .map(|&(x, y)| Some((x, y)).filter(|_| x.is_finite() && y.is_finite()));
+            match series.style {
+                SeriesStyle::Line | SeriesStyle::DashedLine => {
+                    let dash = if series.style == SeriesStyle::DashedLine {
+                        " stroke-dasharray=\"6 4\""
+                    } else {
+                        ""
+                    };
+                    // Cada trecho sem valores não finitos vira uma polyline
+                    let mut segments: Vec<Vec<String>> = vec![vec![]];
+                    for point in points {
+                        match point {
+                            Some((x, y)) => segments
+                                .last_mut()
+                                .expect("Sempre há um trecho aberto")
+                                .push(format!("{:.2},{:.2}", x_pixel(x), y_pixel(y))),
+                            None => segments.push(vec![]),
+                        }
+                    }
+                    for segment in segments.iter().filter(|segment| !segment.is_empty()) {
+                        let _ = writeln!(
+                            svg,
+                            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" \
+                             stroke-width=\"2\"{}/>",
+                            segment.join(" "),
+                            series.color,
+                            dash
+                        );
+                    }
+                }
+                SeriesStyle::Markers | SeriesStyle::Dots => {
+                    let (radius, opacity) = if series.style == SeriesStyle::Markers {
+                        (3.5, 1.0)
+                    } else {
+                        (1.5, 0.4)
+                    };
+                    for (x, y) in points.flatten() {
+                        let _ = writeln!(
+                            svg,
+                            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{}\" fill=\"{}\" \
+                             fill-opacity=\"{}\"/>",
+                            x_pixel(x),
+                            y_pixel(y),
+                            radius,
+                            series.color,
+                            opacity
+                        );
+                    }
+                }
+            }
+        }
+
+        // Legenda, à direita da área dos dados
+        let legend_x = MARGIN_LEFT + plot_width + 15.0;
+        for (index, series) in self.series.iter().enumerate() {
+            let y = MARGIN_TOP + 10.0 + 20.0 * index as f64;
+            match series.style {
+                SeriesStyle::Line | SeriesStyle::DashedLine => {
+                    let dash = if series.style == SeriesStyle::DashedLine {
+                        " stroke-dasharray=\"6 4\""
+                    } else {
+                        ""
+                    };
+                    let _ = writeln!(
+                        svg,
+                        "<line x1=\"{0:.2}\" y1=\"{1:.2}\" x2=\"{2:.2}\" y2=\"{1:.2}\" \
+                         stroke=\"{3}\" stroke-width=\"2\"{4}/>",
+                        legend_x,
+                        y,
+                        legend_x + 20.0,
+                        series.color,
+                        dash
+                    );
+                }
+                SeriesStyle::Markers | SeriesStyle::Dots => {
+                    let _ = writeln!(
+                        svg,
+                        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"3.5\" fill=\"{}\"/>",
+                        legend_x + 10.0,
+                        y,
+                        series.color
+                    );
+                }
+            }
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.2}\" y=\"{:.2}\">{}</text>",
+                legend_x + 28.0,
+                y + 4.0,
+                escape(&series.name)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // Escreve o gráfico num arquivo .svg, substituindo o arquivo caso ele exista
+    pub fn write(&self, path: &Path) -> Result<(), SimulationError> {
+        fs::write(path, self.to_svg()).map_err(SimulationError::io(path))
+    }
+}
+
+// Menor e maior dos valores, alargados quando são iguais para que o eixo tenha uma extensão
+fn range(values: &[f64]) -> (f64, f64) {
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if values.is_empty() {
+        (0.0, 1.0)
+    } else if min == max {
+        let padding = if min == 0.0 { 1.0 } else { min.abs() / 10.0 };
+        (min - padding, max + padding)
+    } else {
+        (min, max)
+    }
+}
+
+// Marcações de um eixo em múltiplos de 1, 2 ou 5 vezes uma potência de 10, da maior marcação até
+// min à menor marcação a partir de max, de forma que o intervalo todo fique dentro do eixo
+fn nice_ticks(min: f64, max: f64) -> Vec<f64> {
+    let rough_step = (max - min) / TICKS as f64;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|index| index as f64 * step).collect()
+}
+
+// Casas decimais necessárias para distinguir as marcações de um eixo
+fn tick_decimals(ticks: &[f64]) -> usize {
+    let step = ticks[1] - ticks[0];
+    (-step.log10().floor()).max(0.0) as usize
+}
+
+// Escapa os caracteres especiais do XML num texto
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_are_round_and_cover_the_range() {
+        let ticks = nice_ticks(0.13, 0.87);
+        let expected = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
+        assert_eq!(ticks.len(), expected.len());
+        for (tick, expected) in ticks.iter().zip(expected.iter()) {
+            assert!((tick - expected).abs() < 1e-12);
+        }
+        assert_eq!(tick_decimals(&ticks), 1);
+        assert_eq!(nice_ticks(0.0, 3200.0).last(), Some(&4000.0));
+        let (min, max) = range(&[2.0, 2.0]);
+        assert!((min - 1.8).abs() < 1e-12 && (max - 2.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn non_finite_values_are_skipped() {
+        let mut chart = Chart::new("E[W] & <ρ>", "ρ", "E[W]");
+        chart.add_series(
+            Series::new(
+                "FCFS",
+                "blue",
+                SeriesStyle::Markers,
+                vec![(0.2, 0.25), (0.4, f64::NAN), (0.6, 1.5)],
+            )
+            .error_bars(vec![(0.2, 0.2, 0.3), (0.6, f64::NAN, f64::NAN)]),
+        );
+        chart.add_series(Series::new(
+            "analítico",
+            "blue",
+            SeriesStyle::DashedLine,
+            vec![(0.2, 0.25), (0.4, f64::INFINITY), (0.5, 1.0), (0.6, 1.5)],
+        ));
+        let svg = chart.to_svg();
+        assert!(svg.contains("E[W] &amp; &lt;ρ&gt;"));
+        assert!(!svg.contains("NaN"));
+        // Dois pontos com valor, uma barra de erro, dois trechos da curva e o círculo da legenda
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches("<polyline").count(), 2);
+    }
+}
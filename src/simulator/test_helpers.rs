@@ -0,0 +1,26 @@
+// Asserções compartilhadas pelos testes dos módulos do simulador
+
+// Compara dois valores reais a menos de erros de arredondamento
+pub(crate) fn assert_close(actual: f64, expected: f64) {
+    assert_close_within(actual, expected, 1e-12);
+}
+
+// Compara dois valores reais com a tolerância absoluta dada
+pub(crate) fn assert_close_within(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() < tolerance,
+        "Esperado {}, obtido {}",
+        expected,
+        actual
+    );
+}
+
+// Compara um valor com a referência a menos de um erro relativo de 1e-9
+pub(crate) fn assert_relatively_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() <= 1e-9 * expected.abs(),
+        "Esperado {}, obtido {}",
+        expected,
+        actual
+    );
+}
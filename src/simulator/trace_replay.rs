@@ -0,0 +1,295 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Importando a leitura e a representação do registro de eventos
+use crate::simulator::event_trace::{read_trace, TraceEvent, TraceRecord};
+
+// Estrutura HashMap e caminhos de arquivos da biblioteca padrão
+use std::collections::HashMap;
+use std::path::Path;
+
+// Estatísticas de uma rodada recalculadas a partir do registro de eventos, para conferir os
+// acumuladores do simulador. Assim como na fila, W só é contabilizado para os fregueses que
+// chegaram e saíram na mesma rodada, e N e Nq são ponderados pelo tempo desde o início da rodada
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedRound {
+    pub queue: u64,     // Semente da fila da rodada
+    pub round: usize,   // Rodada, a partir de 1 depois da fase transiente
+    pub clients: usize, // Fregueses com W contabilizado na rodada
+    pub mean_w: f64,    // Média de W na rodada
+    pub mean_n: f64,    // Média de N ponderada pelo tempo
+    pub mean_nq: f64,   // Média de Nq ponderada pelo tempo
+}
+
+// Rodada em andamento durante a releitura, com as integrais de N e Nq até o último evento
+struct OpenRound {
+    round: usize,     // Rodada
+    start_time: f64,  // Instante do início da rodada
+    last_time: f64,   // Instante do último evento
+    n: usize,         // N desde o último evento
+    nq: usize,        // Nq desde o último evento
+    n_integral: f64,  // Integral de N desde o início da rodada
+    nq_integral: f64, // Integral de Nq desde o início da rodada
+    w_sum: f64,       // Soma dos W contabilizados
+    clients: usize,   // Quantidade de W contabilizados
+    events: usize,    // Eventos da rodada além do seu início
+}
+
+impl OpenRound {
+    // Acumula as integrais até o instante do evento e passa para o estado depois dele
+    fn advance(&mut self, record: &TraceRecord) {
+        let elapsed = record.time - self.last_time;
+        self.n_integral += self.n as f64 * elapsed;
+        self.nq_integral += self.nq as f64 * elapsed;
+        self.last_time = record.time;
+        self.n = record.queue_length + record.in_service.map_or(0, |_| 1);
+        self.nq = record.queue_length;
+        self.events += 1;
+    }
+}
+
+// Estado de uma fila durante a releitura do registro
+#[derive(Default)]
+struct QueueReplay {
+    arrivals: HashMap<usize, (f64, usize)>, // Instante e rodada de chegada dos fregueses na fila
+    waiting_times: HashMap<usize, (f64, usize)>, // W e rodada de chegada dos fregueses em serviço
+    open_round: Option<OpenRound>,          // Rodada em andamento, se seu início foi registrado
+    rounds: Vec<ReplayedRound>,             // Rodadas completas
+    incomplete_rounds: usize,               // Rodadas sem o início ou o fim no registro
+}
+
+impl QueueReplay {
+    // Processa um evento da fila
+    fn process(&mut self, record: &TraceRecord) {
+        if let Some(open_round) = self.open_round.as_mut() {
+            open_round.advance(record);
+        }
+        match (record.event, record.client) {
+            (TraceEvent::Arrival, Some(client)) => {
+                self.arrivals.insert(client, (record.time, record.round));
+            }
+            (TraceEvent::ServiceStart, Some(client)) => {
+                if let Some((arrival_time, round)) = self.arrivals.remove(&client) {
+                    self.waiting_times
+                        .insert(client, (record.time - arrival_time, round));
+                }
+            }
+            (TraceEvent::Departure, Some(client)) => {
+                if let (Some((w, arrival_round)), Some(open_round)) =
+                    (self.waiting_times.remove(&client), self.open_round.as_mut())
+                {
+                    if arrival_round == record.round && record.round == open_round.round {
+                        open_round.w_sum += w;
+                        open_round.clients += 1;
+                    }
+                }
+            }
+            (TraceEvent::RoundStart, _) => {
+                self.close_incomplete_round();
+                self.open_round = Some(OpenRound {
+                    round: record.round,
+                    start_time: record.time,
+                    last_time: record.time,
+                    n: record.queue_length + record.in_service.map_or(0, |_| 1),
+                    nq: record.queue_length,
+                    n_integral: 0.0,
+                    nq_integral: 0.0,
+                    w_sum: 0.0,
+                    clients: 0,
+                    events: 0,
+                });
+            }
+            (TraceEvent::RoundEnd, _) if record.round > 0 => match self.open_round.take() {
+                Some(open_round) if open_round.round == record.round => {
+                    let duration = open_round.last_time - open_round.start_time;
+                    let time_average = |integral: f64| {
+                        if duration > 0.0 {
+                            integral / duration
+                        } else {
+                            0.0
+                        }
+                    };
+                    self.rounds.push(ReplayedRound {
+                        queue: record.queue,
+                        round: open_round.round,
+                        clients: open_round.clients,
+                        mean_w: if open_round.clients > 0 {
+                            open_round.w_sum / open_round.clients as f64
+                        } else {
+                            0.0
+                        },
+                        mean_n: time_average(open_round.n_integral),
+                        mean_nq: time_average(open_round.nq_integral),
+                    });
+                }
+                _ => self.incomplete_rounds += 1,
+            },
+            _ => (),
+        }
+    }
+
+    // Descarta a rodada em andamento, que é incompleta caso algum evento dela tenha sido registrado
+    fn close_incomplete_round(&mut self) {
+        if let Some(open_round) = self.open_round.take() {
+            if open_round.events > 0 {
+                self.incomplete_rounds += 1;
+            }
+        }
+    }
+}
+
+// Resultado da releitura de um registro de eventos: as rodadas completas de cada fila, na ordem em
+// que as filas aparecem no registro, e a quantidade de rodadas incompletas, que foram ignoradas
+pub struct TraceReplay {
+    pub rounds: Vec<ReplayedRound>, // Rodadas completas de todas as filas
+    pub incomplete_rounds: usize,   // Rodadas cortadas pelos filtros ou pelo fim da execução
+}
+
+// Relê os eventos registrados e recalcula as estatísticas de W, N e Nq de cada rodada completa.
+// Os eventos de cada fila devem estar na ordem em que foram registrados
+pub fn replay_trace(records: &[TraceRecord]) -> TraceReplay {
+    let mut queues: Vec<(u64, QueueReplay)> = vec![];
+    for record in records.iter() {
+        let index = match queues.iter().position(|(queue, _)| *queue == record.queue) {
+            Some(index) => index,
+            None => {
+                queues.push((record.queue, QueueReplay::default()));
+                queues.len() - 1
+            }
+        };
+        queues[index].1.process(record);
+    }
+    let mut replay = TraceReplay {
+        rounds: vec![],
+        incomplete_rounds: 0,
+    };
+    for (_, mut queue) in queues.into_iter() {
+        queue.close_incomplete_round();
+        replay.rounds.extend(queue.rounds);
+        replay.incomplete_rounds += queue.incomplete_rounds;
+    }
+    replay
+}
+
+// Imprime a média das médias das rodadas, como no método das rodadas
+fn print_round_means(name: &str, rounds: &[&ReplayedRound]) {
+    let count = rounds.len() as f64;
+    let mean = |value: fn(&ReplayedRound) -> f64| {
+        rounds.iter().map(|round| value(round)).sum::<f64>() / count
+    };
+    println!(
+        "{}: {} rodadas; {} fregueses\n\tE[W] = {:0.5}\tE[N] = {:0.5}\tE[Nq] = {:0.5}",
+        name,
+        rounds.len(),
+        rounds.iter().map(|round| round.clients).sum::<usize>(),
+        mean(|round| round.mean_w),
+        mean(|round| round.mean_n),
+        mean(|round| round.mean_nq)
+    );
+}
+
+// Relê um arquivo de eventos e imprime as médias de W, N e Nq de cada fila e de todas juntas, para
+// serem comparadas com as estimativas do relatório da execução
+pub fn print_trace_replay(path: &Path) -> Result<(), SimulationError> {
+    let records = read_trace(path)?;
+    let replay = replay_trace(&records);
+    println!(
+        "{}: {} eventos; {} rodadas completas; {} rodadas incompletas ignoradas",
+        path.display(),
+        records.len(),
+        replay.rounds.len(),
+        replay.incomplete_rounds
+    );
+    let mut queues: Vec<u64> = vec![];
+    for round in replay.rounds.iter() {
+        if !queues.contains(&round.queue) {
+            queues.push(round.queue);
+        }
+    }
+    for &queue in queues.iter() {
+        let rounds: Vec<&ReplayedRound> = replay
+            .rounds
+            .iter()
+            .filter(|round| round.queue == queue)
+            .collect();
+        print_round_means(&format!("Fila com semente {}", queue), &rounds);
+    }
+    if queues.len() > 1 {
+        let rounds: Vec<&ReplayedRound> = replay.rounds.iter().collect();
+        print_round_means("Todas as filas", &rounds);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::event_trace::{EventTrace, TraceFilter};
+    use crate::simulator::queue::{Queue, QueuePolicy, N, NQ, W};
+    use crate::simulator::random_number_generator::GeneratorKind;
+    use std::env;
+    use std::fs;
+
+    // Executa rodadas de uma fila registrando seus eventos e devolve as médias dos acumuladores
+    fn traced_rounds(path: &Path, filter: TraceFilter) -> Vec<[f64; 3]> {
+        let trace = EventTrace::create(path, filter).unwrap();
+        let mut queue = Queue::new(0.6, QueuePolicy::LCFS, 5, GeneratorKind::Std);
+        queue.add_observer(trace.observer_factory().create(5));
+        queue.transient_phase().unwrap();
+        let accumulated = (0..4)
+            .map(|_| {
+                let (samples, processes) = queue.run_one_simulation_round(200).unwrap();
+                [samples[W].mean(), processes[N].mean(), processes[NQ].mean()]
+            })
+            .collect();
+        trace.finish().unwrap();
+        accumulated
+    }
+
+    #[test]
+    fn replayed_rounds_match_the_accumulators() {
+        for &extension in ["csv", "jsonl"].iter() {
+            let path = env::temp_dir().join(format!("simulador_ad_trace.{}", extension));
+            let accumulated = traced_rounds(&path, TraceFilter::default());
+            let replay = replay_trace(&read_trace(&path).unwrap());
+            fs::remove_file(&path).unwrap();
+            assert_eq!(replay.rounds.len(), 4);
+            assert_eq!(replay.incomplete_rounds, 0);
+            for (round, values) in replay.rounds.iter().zip(accumulated.iter()) {
+                assert_eq!(round.clients, 200);
+                let replayed = [round.mean_w, round.mean_n, round.mean_nq];
+                for (replayed, accumulated) in replayed.iter().zip(values.iter()) {
+                    assert!((replayed - accumulated).abs() <= 1e-9 * accumulated.abs());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filtered_traces_only_replay_the_complete_rounds() {
+        let path = env::temp_dir().join("simulador_ad_filtered_trace.csv");
+        let filter = TraceFilter {
+            time_window: None,
+            rounds: Some((2, 3)),
+        };
+        let accumulated = traced_rounds(&path, filter);
+        let records = read_trace(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(records
+            .iter()
+            .all(|record| record.round == 2 || record.round == 3));
+        let replay = replay_trace(&records);
+        let rounds: Vec<usize> = replay.rounds.iter().map(|round| round.round).collect();
+        assert_eq!(rounds, vec![2, 3]);
+        assert!((replay.rounds[0].mean_w - accumulated[1][0]).abs() <= 1e-9);
+
+        // Uma janela de tempo que corta as rodadas deixa apenas rodadas incompletas
+        let (start, end) = (records[10].time, records[records.len() - 10].time);
+        let window: Vec<TraceRecord> = records
+            .into_iter()
+            .filter(|record| start <= record.time && record.time <= end)
+            .collect();
+        let replay = replay_trace(&window);
+        assert!(replay.rounds.is_empty());
+        assert_eq!(replay.incomplete_rounds, 2);
+    }
+}
@@ -0,0 +1,409 @@
+// Importando o tipo de erro da biblioteca
+use crate::error::SimulationError;
+// Serialização dos checkpoints
+use serde::{Deserialize, Serialize};
+// Importando o cálculo das médias em lotes e a escolha automática do tamanho de lote
+use crate::simulator::output_analysis::batch_means::{batch_means, select_batch_size};
+// Importando a representação do nossa fila M/M/1 e algumas constantes
+use crate::simulator::queue::{Queue, N, W};
+// Importando o quantil da T-Student
+use crate::simulator::statistical_distributions::student_t_quantile;
+// Importando os valores analíticos, mostrados no gráfico de Welch
+use crate::simulator::analytic_models::analytic_model;
+// Importando o gráfico das médias móveis do procedimento de Welch
+use crate::simulator::plots::plot_welch;
+// Importando a derivação das sementes das filas piloto
+use crate::simulator::random_number_generator::pilot_seed;
+// Importando a função que exporta as médias móveis do procedimento de Welch
+use crate::simulator::statistics_output_files::write_welch_csv_file;
+// Importando a função que instancia uma fila vazia, os parâmetros de uma execução e o modo da fila
+use crate::simulator::{new_queue, QueueMode, SimulationParameters};
+// Trait para converter uma string no enum correspondente
+use std::str::FromStr;
+
+// Quantidade de fregueses de cada trecho da rodada piloto usada para detectar o aquecimento
+const PILOT_CHUNK_SIZE: usize = 5_000;
+// Quantidade máxima de trechos da rodada piloto antes de desistirmos da detecção
+const MAXIMUM_PILOT_CHUNKS: usize = 20;
+// Tamanho dos lotes do MSER-5
+const MSER_BATCH_SIZE: usize = 5;
+// Quantidade de replicações piloto do procedimento de Welch
+const WELCH_REPLICATIONS: usize = 10;
+// Quantidade de fregueses de cada replicação piloto do procedimento de Welch
+const WELCH_REPLICATION_SIZE: usize = 10_000;
+// Fração da rodada piloto usada como janela das médias móveis de Welch
+const WELCH_WINDOW_FRACTION: usize = 100;
+// Passo do truncamento testado pelo teste de Schruben, como fração da rodada piloto
+const SCHRUBEN_STEP_FRACTION: usize = 10;
+
+// Enum para selecionar o procedimento que detecta o fim da fase transiente
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WarmupMethod {
+    // Heurística original: a utilização simulada fica próxima da utilização esperada
+    Utilization,
+    // Truncamento que minimiza o erro padrão marginal das médias em lotes de 5
+    Mser5,
+    // Médias móveis de várias replicações piloto, segundo o procedimento gráfico de Welch
+    Welch,
+    // Truncamento crescente até que o teste de viés de inicialização de Schruben não rejeite
+    Schruben,
+}
+
+impl FromStr for WarmupMethod {
+    type Err = String;
+
+    // Converte o nome do procedimento, como passado na linha de comando, no enum correspondente
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "utilization" => Ok(WarmupMethod::Utilization),
+            "mser-5" => Ok(WarmupMethod::Mser5),
+            "welch" => Ok(WarmupMethod::Welch),
+            "schruben" => Ok(WarmupMethod::Schruben),
+            _ => Err(format!(
+                "Procedimento de aquecimento desconhecido: {}",
+                name
+            )),
+        }
+    }
+}
+
+// Truncamento pela regra MSER: agrupamos a série em lotes de batch_size e escolhemos a quantidade
+// d de lotes descartados que minimiza a soma dos desvios quadráticos dos lotes restantes dividida
+// por (b - d)². A busca é feita só na primeira metade, pois um mínimo além dela não é confiável.
+// Retorna a quantidade de observações descartadas
+pub fn mser_truncation(series: &[f64], batch_size: usize) -> usize {
+    let means = batch_means(series, batch_size);
+    let batch_count = means.len();
+    let mut best_truncation = 0;
+    let mut best_statistic = f64::INFINITY;
+    for truncation in 0..=batch_count / 2 {
+        let remaining = &means[truncation..];
+        let remaining_count = remaining.len() as f64;
+        let mean = remaining.iter().sum::<f64>() / remaining_count;
+        let statistic = remaining
+            .iter()
+            .fold(0.0, |sum, &value| sum + (value - mean).powi(2))
+            / remaining_count.powi(2);
+        if statistic < best_statistic {
+            best_statistic = statistic;
+            best_truncation = truncation;
+        }
+    }
+    best_truncation * batch_size
+}
+
+// Médias móveis do procedimento de Welch com janela w. Nas w primeiras posições a janela é
+// reduzida para continuar centrada, e as últimas w posições não têm média móvel
+pub fn welch_moving_averages(series: &[f64], window: usize) -> Vec<f64> {
+    let count = series.len().saturating_sub(window);
+    (0..count)
+        .map(|index| {
+            let half_width = usize::min(index, window);
+            let neighbours = &series[index - half_width..=index + half_width];
+            neighbours.iter().sum::<f64>() / neighbours.len() as f64
+        })
+        .collect()
+}
+
+// Automatiza a inspeção visual do gráfico de Welch: o nível estacionário é estimado pela média da
+// segunda metade das médias móveis e o truncamento é o primeiro ponto em que a curva o alcança
+pub fn welch_truncation(moving_averages: &[f64]) -> usize {
+    if moving_averages.is_empty() {
+        return 0;
+    }
+    let second_half = &moving_averages[moving_averages.len() / 2..];
+    let level = second_half.iter().sum::<f64>() / second_half.len() as f64;
+    let starts_below = moving_averages[0] < level;
+    moving_averages
+        .iter()
+        .position(|&average| (average >= level) == starts_below)
+        .unwrap_or(moving_averages.len() / 2)
+}
+
+// Resultado do teste de viés de inicialização de Schruben
+#[derive(Debug, Copy, Clone)]
+pub struct SchrubenTest {
+    statistic: f64,      // Estatística do teste, com distribuição T-Student sem viés
+    critical_value: f64, // Valor crítico bilateral da T-Student
+}
+
+impl SchrubenTest {
+    // Indica se a hipótese de ausência de viés de inicialização foi rejeitada
+    pub fn biased(&self) -> bool {
+        self.statistic.abs() > self.critical_value
+    }
+}
+
+// Teste de Schruben para viés de inicialização. A primeira metade da série é resumida pela série
+// temporal padronizada S_k = k (Ȳ_m - Ȳ_k), cuja soma ponderada √45 Σ (1 - k/m) S_k / (m^(3/2) σ)
+// tem distribuição Normal padrão sem viés. O parâmetro de variância σ² é estimado pelas médias em
+// lotes da segunda metade, então a estatística tem distribuição T-Student com b - 1 graus de
+// liberdade
+pub fn schruben_test(series: &[f64], confidence_level: f64) -> SchrubenTest {
+    let (tested, reference) = series.split_at(series.len() / 2);
+    let batch_size = select_batch_size(reference, confidence_level);
+    let means = batch_means(reference, batch_size);
+    let batch_count = means.len() as f64;
+    let mean = means.iter().sum::<f64>() / batch_count;
+    let variance_parameter = batch_size as f64
+        * means
+            .iter()
+            .fold(0.0, |sum, &value| sum + (value - mean).powi(2))
+        / (batch_count - 1.0);
+
+    let tested_count = tested.len() as f64;
+    let tested_mean = tested.iter().sum::<f64>() / tested_count;
+    let mut partial_sum = 0.0;
+    let mut weighted_sum = 0.0;
+    for (index, &value) in tested.iter().enumerate() {
+        let k = (index + 1) as f64;
+        partial_sum += value;
+        weighted_sum += (1.0 - k / tested_count) * (k * tested_mean - partial_sum);
+    }
+    SchrubenTest {
+        statistic: 45f64.sqrt() * weighted_sum
+            / (tested_count.powf(1.5) * variance_parameter.sqrt()),
+        critical_value: student_t_quantile(1.0 - (1.0 - confidence_level) / 2.0, batch_count - 1.0),
+    }
+}
+
+// Executa um trecho da rodada piloto e acrescenta às séries de W, por freguês, e de N, discretizado
+// em uma fatia de tempo por freguês
+fn extend_pilot_series(
+    queue: &mut Queue,
+    w_series: &mut Vec<f64>,
+    n_series: &mut Vec<f64>,
+) -> Result<(), SimulationError> {
+    let (samples, stochastic_process_samples) = queue.run_one_simulation_round(PILOT_CHUNK_SIZE)?;
+    w_series.extend_from_slice(samples[W].values());
+    n_series
+        .extend(stochastic_process_samples[N].time_slot_averages(PILOT_CHUNK_SIZE, |value| value));
+    Ok(())
+}
+
+// Estende uma rodada piloto, executada numa fila separada que também começa vazia, até que a
+// regra de truncamento aceite um ponto na primeira metade das séries de W e N. A fila principal
+// então descarta a quantidade de fregueses encontrada, ou toda a rodada piloto caso nenhum
+// truncamento seja aceito. Retorna a quantidade de fregueses descartados
+fn sequential_pilot<F: Fn(&[f64]) -> Option<usize>>(
+    queue: &mut Queue,
+    parameters: &SimulationParameters,
+    seed: u64,
+    name: &str,
+    truncation_rule: F,
+) -> Result<usize, SimulationError> {
+    let mut pilot_queue = new_queue(parameters, pilot_seed(seed, 0));
+    let mut w_series = vec![];
+    let mut n_series = vec![];
+    for _ in 0..MAXIMUM_PILOT_CHUNKS {
+        extend_pilot_series(&mut pilot_queue, &mut w_series, &mut n_series)?;
+        if let (Some(w_truncation), Some(n_truncation)) =
+            (truncation_rule(&w_series), truncation_rule(&n_series))
+        {
+            if !parameters.deferred_output {
+                println!(
+                    "{}: truncamento de W = {}; truncamento de N = {}; rodada piloto = {} \
+                     fregueses",
+                    name,
+                    w_truncation,
+                    n_truncation,
+                    w_series.len()
+                );
+            }
+            let truncation = usize::max(w_truncation, n_truncation);
+            queue.run_one_simulation_round(truncation)?;
+            return Ok(truncation);
+        }
+    }
+    if !parameters.deferred_output {
+        println!(
+            "{}: nenhum truncamento aceito após {} fregueses, o aquecimento pode ser insuficiente",
+            name,
+            w_series.len()
+        );
+    }
+    queue.run_one_simulation_round(w_series.len())?;
+    Ok(w_series.len())
+}
+
+// Procedimento de Welch: replicações piloto independentes são executadas, suas séries de W e N
+// são promediadas posição a posição e suavizadas por médias móveis, que são exportadas para
+// inspeção visual, também como gráfico quando pedido. Retorna a quantidade de fregueses que as
+// filas principais devem descartar
+fn welch_pilot(parameters: &SimulationParameters, seed: u64) -> Result<usize, SimulationError> {
+    let mut w_averages = vec![0.0; WELCH_REPLICATION_SIZE];
+    let mut n_averages = vec![0.0; WELCH_REPLICATION_SIZE];
+    for replication in 0..WELCH_REPLICATIONS {
+        // As replicações piloto usam um fluxo de sementes disjunto das filas principais
+        let mut pilot_queue = new_queue(parameters, pilot_seed(seed, replication));
+        let (samples, stochastic_process_samples) =
+            pilot_queue.run_one_simulation_round(WELCH_REPLICATION_SIZE)?;
+        let n_series =
+            stochastic_process_samples[N].time_slot_averages(WELCH_REPLICATION_SIZE, |value| value);
+        for index in 0..WELCH_REPLICATION_SIZE {
+            w_averages[index] += samples[W].values()[index] / WELCH_REPLICATIONS as f64;
+            n_averages[index] += n_series[index] / WELCH_REPLICATIONS as f64;
+        }
+    }
+    let window = WELCH_REPLICATION_SIZE / WELCH_WINDOW_FRACTION;
+    let w_moving_averages = welch_moving_averages(&w_averages, window);
+    let n_moving_averages = welch_moving_averages(&n_averages, window);
+    write_welch_csv_file(
+        &parameters.output_path,
+        parameters.rho,
+        parameters.queue_policy,
+        &w_moving_averages,
+        &n_moving_averages,
+    )?;
+    let w_truncation = welch_truncation(&w_moving_averages);
+    let n_truncation = welch_truncation(&n_moving_averages);
+    if let Some(directory) = &parameters.plots_directory {
+        let analytic_values =
+            analytic_model(parameters).map(|model| [model.mean_w(), model.mean_n()]);
+        plot_welch(
+            directory,
+            parameters,
+            [&w_moving_averages, &n_moving_averages],
+            [w_truncation, n_truncation],
+            analytic_values,
+        )?;
+    }
+    if !parameters.deferred_output {
+        println!(
+            "Welch: truncamento de W = {}; truncamento de N = {}; médias móveis exportadas",
+            w_truncation, n_truncation
+        );
+    }
+    Ok(usize::max(w_truncation, n_truncation))
+}
+
+// Executa uma única vez por execução os procedimentos cujas rodadas piloto não dependem da fila
+// principal, hoje só o de Welch, para que as replicações piloto, o welch.csv e o gráfico não se
+// repitam a cada fila paralela ou replicação. Retorna o truncamento a ser aplicado em todas as
+// filas da execução, se houver
+pub(crate) fn execution_truncation(
+    parameters: &SimulationParameters,
+    seed: u64,
+) -> Result<Option<usize>, SimulationError> {
+    if parameters.queue_mode == QueueMode::ForReal
+        && parameters.warmup_method == WarmupMethod::Welch
+    {
+        welch_pilot(parameters, seed).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+// Executa a fase transiente da fila pelo procedimento escolhido nos parâmetros, ou descarta o
+// truncamento já encontrado para a execução, quando dado.
+// Retorna o tamanho da fase transiente, em eventos para a heurística da utilização e em
+// fregueses para os demais procedimentos
+pub fn warm_up(
+    queue: &mut Queue,
+    parameters: &SimulationParameters,
+    seed: u64,
+    execution_truncation: Option<usize>,
+) -> Result<usize, SimulationError> {
+    let transient_phase_size = match parameters.warmup_method {
+        // A heurística da utilização já avisa os observadores do fim da fase transiente
+        WarmupMethod::Utilization => return queue.transient_phase(),
+        WarmupMethod::Mser5 => sequential_pilot(queue, parameters, seed, "MSER-5", |series| {
+            let truncation = mser_truncation(series, MSER_BATCH_SIZE);
+            if truncation < series.len() / 2 {
+                Some(truncation)
+            } else {
+                None
+            }
+        }),
+        WarmupMethod::Welch => {
+            let truncation = match execution_truncation {
+                Some(truncation) => truncation,
+                None => welch_pilot(parameters, seed)?,
+            };
+            queue.run_one_simulation_round(truncation)?;
+            Ok(truncation)
+        }
+        WarmupMethod::Schruben => {
+            sequential_pilot(queue, parameters, seed, "Schruben", |series| {
+                // Aumentamos o truncamento até que o teste não rejeite a ausência de viés
+                let step = usize::max(1, series.len() / SCHRUBEN_STEP_FRACTION);
+                (0..series.len() / 2).step_by(step).find(|&truncation| {
+                    !schruben_test(&series[truncation..], parameters.confidence_level).biased()
+                })
+            })
+        }
+    }?;
+    queue.end_warmup(transient_phase_size);
+    Ok(transient_phase_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::output_analysis::{new_analysis, AnalysisMethod};
+    use crate::simulator::queue::QueuePolicy;
+    use crate::simulator::queue_observer::QueueObserver;
+    use crate::simulator::Client;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::{env, fs};
+
+    // Observador que conta as saídas da fila principal
+    struct DepartureCounter(Arc<AtomicUsize>);
+
+    impl QueueObserver for DepartureCounter {
+        fn on_departure(&mut self, _client: &Client, _time: f64) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn sequential_pilot_discards_only_the_detected_truncation() {
+        // A rodada piloto roda numa fila separada, então a fila principal só atende os fregueses
+        // do truncamento aceito
+        let parameters =
+            SimulationParameters::new(0.5, 1000, 10, QueuePolicy::FCFS, QueueMode::ForReal);
+        let departures = Arc::new(AtomicUsize::new(0));
+        let mut queue = new_queue(&parameters, 1);
+        queue.add_observer(Box::new(DepartureCounter(Arc::clone(&departures))));
+        let truncation = sequential_pilot(&mut queue, &parameters, 1, "Teste", |series| {
+            if series.len() >= 2 * PILOT_CHUNK_SIZE {
+                Some(123)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+        assert_eq!(truncation, 123);
+        assert_eq!(departures.load(Ordering::Relaxed), 123);
+    }
+
+    #[test]
+    fn welch_pilot_runs_once_per_execution() {
+        // Cada execução exporta uma única série de médias móveis, e todas as suas replicações ou
+        // filas paralelas descartam o mesmo truncamento
+        let directory = env::temp_dir().join("simulador_ad_welch");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let mut parameters =
+            SimulationParameters::new(0.5, 100, 3, QueuePolicy::FCFS, QueueMode::ForReal);
+        parameters.warmup_method = WarmupMethod::Welch;
+        parameters.deferred_output = true;
+        parameters.parallel_queues = 3;
+        parameters.output_path = directory.join("output.csv");
+        let mut transient_phase_sizes = vec![];
+        for &method in [AnalysisMethod::Replications, AnalysisMethod::Rounds].iter() {
+            parameters.analysis_method = method;
+            let mut analysis = new_analysis(&parameters, 1).unwrap();
+            analysis.extend(300).unwrap();
+            transient_phase_sizes.push(analysis.transient_phase_size());
+        }
+        assert_eq!(transient_phase_sizes[0], transient_phase_sizes[1]);
+        let rows = fs::read_to_string(directory.join("welch.csv"))
+            .unwrap()
+            .lines()
+            .count();
+        let series_size = WELCH_REPLICATION_SIZE - WELCH_REPLICATION_SIZE / WELCH_WINDOW_FRACTION;
+        assert_eq!(rows, 1 + 2 * series_size);
+    }
+}